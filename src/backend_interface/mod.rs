@@ -2,7 +2,8 @@ use std::{collections::HashMap};
 
 use serde_json::Value;
 
-use crate::{blocks::BlockMap, step::Step, steps_generator::StepError, new_ids::NewIds, steps_actualisor::{actualise_steps, UpdatedState}, custom_copy::CustomCopy};
+use crate::{blocks::BlockMap, step::Step, steps_generator::{StepError, document_diff::generate_diff_steps}, new_ids::NewIds, steps_actualisor::{actualise_steps, UpdatedState, actualise_move_blocks_to_page::actualise_move_blocks_to_page_across_block_maps}, custom_copy::CustomCopy,
+    utilities::presence::{RemotePresence, map_presences_through_step}};
 
 
 pub fn actualise_mirror_step(
@@ -26,6 +27,61 @@ pub fn actualise_mirror_step(
     }
 }
 
+/// For steps that move blocks from one document (source) into another (target)
+/// -> returns (source state, target state), each with its Rust block map & the blocks to update / remove in that document
+pub fn actualise_mirror_step_across_documents(
+    step_as_json: (String, String),
+    source_block_map_rust: HashMap<String, String>,
+    target_block_map_rust: HashMap<String, String>
+) -> Result<(UpdatedState, UpdatedState), StepError> {
+    let (_type, data) = step_as_json;
+    let move_step = match Step::from_json(&_type, &data)? {
+        Step::MoveBlocksToPage(move_step) => move_step,
+        step => return Err(StepError(format!("Step: {:?} cannot be applied across documents", step)))
+    };
+    return actualise_move_blocks_to_page_across_block_maps(
+        move_step,
+        BlockMap::Rust(source_block_map_rust),
+        BlockMap::Rust(target_block_map_rust)
+    )
+}
+
+/// Steps (as (_type, data) like actualise_mirror_step takes) that turn the old snapshot of the page into the new snapshot
+pub fn diff_mirror_block_maps(
+    root_block_id: String,
+    old_block_map_rust: HashMap<String, String>,
+    new_block_map_rust: HashMap<String, String>,
+    new_ids: Vec<String>
+) -> Result<Vec<(String, String)>, StepError> {
+    let mut new_ids = NewIds::Rust(new_ids);
+    let steps = generate_diff_steps(
+        &root_block_id,
+        &BlockMap::Rust(old_block_map_rust),
+        &BlockMap::Rust(new_block_map_rust),
+        &mut new_ids
+    )?;
+    return steps.into_iter().map(|step| step.to_json()).collect()
+}
+
+/// Remaps presences (as json strings) through a step applied to the old block map (giving the new block map).
+/// Presences whose blocks were removed are dropped
+pub fn map_mirror_presences_through_step(
+    presences_as_json: Vec<String>,
+    step_as_json: (String, String),
+    old_block_map_rust: HashMap<String, String>,
+    new_block_map_rust: HashMap<String, String>
+) -> Result<Vec<String>, StepError> {
+    let mut presences = vec![];
+    for presence in presences_as_json {
+        let json: Value = serde_json::from_str(&presence).map_err(|_| StepError(format!("Presence is not valid json: {}", presence)))?;
+        presences.push(RemotePresence::from_json(&json)?);
+    }
+    let (_type, data) = step_as_json;
+    let step = Step::from_json(&_type, &data)?;
+    let presences = map_presences_through_step(presences, &step, &BlockMap::Rust(old_block_map_rust), &BlockMap::Rust(new_block_map_rust));
+    return presences.into_iter().map(|presence| Ok(presence.to_json()?.to_string())).collect()
+}
+
 pub fn get_json_field_as_string(json: &Value, field: &str) -> Result<String, StepError> {
     Ok(json.get(field)
         .ok_or(StepError(format!("json does not have {} field: {}", field, json)))?
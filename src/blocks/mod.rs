@@ -12,6 +12,7 @@ use self::{standard_blocks::{StandardBlock, StandardBlockType}, inline_blocks::{
 
 pub mod standard_blocks;
 pub mod inline_blocks;
+pub mod nesting_schema;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Block {
@@ -268,11 +269,10 @@ impl Block {
 
     pub fn splice_children(&mut self, from: usize, to: usize, insert: Vec<String>) -> Result<(), StepError> {
         match self {
-            Block::StandardBlock(block) => block.children.splice(
-                from..to,
-                insert
-            ),
-            Block::Root(block) => block.children.splice(from..to, insert),
+            Block::StandardBlock(block) => block.splice_children(from..to, insert),
+            Block::Root(block) => {
+                block.children.splice(from..to, insert);
+            },
             Block::InlineBlock(_) => return Err(StepError("InlineBlock does not have children".to_string()))
         };
         Ok(())
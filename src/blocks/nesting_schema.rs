@@ -0,0 +1,102 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use serde_json::Value;
+
+use crate::steps_generator::StepError;
+
+use super::{Block, standard_blocks::{StandardBlockType, layout_block::LayoutBlock}};
+
+thread_local! {
+    static NESTING_SCHEMA: RefCell<NestingSchema> = RefCell::new(NestingSchema::default());
+}
+
+/// Which blocks can be nested inside which, consulted when blocks are tabbed, dropped or pasted.
+/// Block types are named by their "_type" (eg: "h1", "inline page")
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct NestingSchema {
+    /// Block types that cannot have children, on top of the page & table of contents blocks that never can
+    pub childless_types: Vec<String>,
+    /// Parent type -> the only block types allowed directly inside it ("root" for a page's top level)
+    pub allowed_children: HashMap<String, Vec<String>>,
+    /// Block types allowed inside layout columns, any type when None
+    pub layout_column_types: Option<Vec<String>>
+}
+
+impl NestingSchema {
+    /// eg: { "childless_types": ["h1"], "allowed_children": { "root": ["paragraph", "h1"] }, "layout_column_types": null }
+    /// Missing fields keep their default
+    pub fn from_json(json: &Value) -> Result<Self, StepError> {
+        let mut schema = Self::default();
+        if let Some(childless_types) = json.get("childless_types") {
+            schema.childless_types.extend(string_vec_from_json(childless_types)?);
+        }
+        if let Some(allowed_children) = json.get("allowed_children") {
+            let allowed_children = allowed_children.as_object()
+                .ok_or(StepError(format!("allowed_children should be an object. Got: {}", allowed_children)))?;
+            for (parent_type, child_types) in allowed_children {
+                schema.allowed_children.insert(parent_type.clone(), string_vec_from_json(child_types)?);
+            }
+        }
+        if let Some(layout_column_types) = json.get("layout_column_types") {
+            schema.layout_column_types = match layout_column_types.is_null() {
+                true => None,
+                false => Some(string_vec_from_json(layout_column_types)?)
+            };
+        }
+        return Ok(schema)
+    }
+
+    /// Horizontal layouts can only contain their columns, pages keep their content on their own root,
+    /// a table of contents is generated from the page's headings & custom block types declare if they can have children
+    pub fn can_have_children(&self, content: &StandardBlockType) -> bool {
+        return match content {
+            StandardBlockType::Layout(layout_block) => !layout_block.horizontal,
+            StandardBlockType::InlinePage(_) | StandardBlockType::SquarePage(_) | StandardBlockType::LinkBlock(_)
+            | StandardBlockType::TableOfContents(_) => false,
+            StandardBlockType::Custom(custom_block) if !custom_block.block_type().map_or(false, |block_type| block_type.can_have_children()) => false,
+            content => !self.childless_types.contains(&content._type_as_string())
+        }
+    }
+
+    pub fn allows_child(&self, parent: &Block, child: &StandardBlockType) -> bool {
+        let parent_type = match parent {
+            Block::Root(_) => "root".to_string(),
+            Block::StandardBlock(parent) => match &parent.content {
+                StandardBlockType::Layout(LayoutBlock { horizontal: true, .. }) =>
+                    return matches!(child, StandardBlockType::Layout(LayoutBlock { horizontal: false, .. })),
+                StandardBlockType::Layout(_) => return self.allows_in_layout_column(child),
+                content if !self.can_have_children(content) => return false,
+                content => content._type_as_string()
+            },
+            Block::InlineBlock(_) => return false
+        };
+        return match self.allowed_children.get(&parent_type) {
+            Some(child_types) => child_types.contains(&child._type_as_string()),
+            None => true
+        }
+    }
+
+    /// Layouts can always be nested in columns
+    pub fn allows_in_layout_column(&self, child: &StandardBlockType) -> bool {
+        return match (child, &self.layout_column_types) {
+            (StandardBlockType::Layout(_), _) | (_, None) => true,
+            (child, Some(column_types)) => column_types.contains(&child._type_as_string())
+        }
+    }
+}
+
+fn string_vec_from_json(json: &Value) -> Result<Vec<String>, StepError> {
+    let values = json.as_array().ok_or(StepError(format!("Expected an array of block types. Got: {}", json)))?;
+    return values.iter()
+        .map(|value| value.as_str().map(|value| value.to_string()).ok_or(StepError(format!("Block type should be a string. Got: {}", value))))
+        .collect()
+}
+
+/// Runs f with the schema used by step generators & actualisers, defaults to NestingSchema::default()
+pub fn with_nesting_schema<T>(f: impl FnOnce(&NestingSchema) -> T) -> T {
+    return NESTING_SCHEMA.with(|schema| f(&schema.borrow()))
+}
+
+pub fn set_nesting_schema(schema: NestingSchema) {
+    NESTING_SCHEMA.with(|current| *current.borrow_mut() = schema);
+}
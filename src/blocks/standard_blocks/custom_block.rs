@@ -0,0 +1,209 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use serde_json::{Value, Map, json};
+use wasm_bindgen::JsValue;
+
+use crate::{steps_generator::StepError, frontend_interface::{get_js_field, get_js_field_as_string, get_js_field_as_bool}};
+
+use super::content_block::ContentBlock;
+
+thread_local! {
+    static CUSTOM_BLOCK_TYPES: RefCell<HashMap<String, Rc<dyn CustomBlockType>>> = RefCell::new(HashMap::new());
+}
+
+/// What enter does in a block with text
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EnterBehaviour {
+    /// Like paragraphs & headings: the text after the caret goes into a new paragraph
+    NewParagraph,
+    /// Like lists: the text after the caret goes into a new block of the same type,
+    /// & enter in an empty block turns it into a paragraph
+    NewBlockOfSameType
+}
+
+/// What backspace does at the start of a block with text
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BackspaceBehaviour {
+    /// Like paragraphs: the block is joined onto the block above
+    JoinWithBlockAbove,
+    /// Like lists: the block is turned into a paragraph
+    TurnIntoParagraph
+}
+
+/// A standard block type an application adds without changing StandardBlockType.
+/// Register it with register_custom_block_type, blocks with its name as their "_type" are then parsed as StandardBlockType::Custom
+pub trait CustomBlockType {
+    /// The block's "_type", eg: "callout"
+    fn name(&self) -> String;
+    /// Whether the block has text (a ContentBlock with inline blocks)
+    fn has_content(&self) -> bool;
+    fn can_have_children(&self) -> bool {
+        return true
+    }
+    /// The block's own fields from its json "content" (inline_blocks is read separately).
+    /// Errors if the fields aren't valid
+    fn data_from_json(&self, _content: &Value) -> Result<Map<String, Value>, StepError> {
+        return Ok(Map::new())
+    }
+    /// Fields written into the block's "content" next to inline_blocks
+    fn data_to_json(&self, data: &Map<String, Value>) -> Map<String, Value> {
+        return data.clone()
+    }
+    /// Fields of a new block of this type (eg: after turn into or enter)
+    fn default_data(&self) -> Map<String, Value> {
+        return Map::new()
+    }
+    fn enter_behaviour(&self) -> EnterBehaviour {
+        return EnterBehaviour::NewParagraph
+    }
+    fn backspace_behaviour(&self) -> BackspaceBehaviour {
+        return BackspaceBehaviour::JoinWithBlockAbove
+    }
+}
+
+/// The "_type"s StandardBlockType parses before looking for a custom block type
+const BUILT_IN_BLOCK_TYPES: [&str; 14] = ["paragraph", "h1", "h2", "h3", "to-do list", "dotpoint list", "numbered list", "arrow list",
+    "toggle list", "inline page", "square page", "link page", "layout", "table of contents"];
+
+/// Replaces any block type already registered with the same name.
+/// Errors if the name is a built in block type, as blocks with it would never be parsed as the custom type
+pub fn register_custom_block_type(block_type: Rc<dyn CustomBlockType>) -> Result<(), StepError> {
+    if BUILT_IN_BLOCK_TYPES.contains(&block_type.name().as_str()) {
+        return Err(StepError(format!("Block type: {} is already a built in block type", block_type.name())))
+    }
+    CUSTOM_BLOCK_TYPES.with(|block_types| block_types.borrow_mut().insert(block_type.name(), block_type));
+    return Ok(())
+}
+
+pub fn get_custom_block_type(name: &str) -> Option<Rc<dyn CustomBlockType>> {
+    return CUSTOM_BLOCK_TYPES.with(|block_types| block_types.borrow().get(name).cloned())
+}
+
+/// A custom block type declared by the frontend, its data is every field in "content" except inline_blocks
+/// eg: { name: "callout", has_content: true, can_have_children: false, enter: "new_block_of_same_type", backspace: "turn_into_paragraph" }
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeclaredBlockType {
+    pub name: String,
+    pub has_content: bool,
+    pub can_have_children: bool,
+    pub enter_behaviour: EnterBehaviour,
+    pub backspace_behaviour: BackspaceBehaviour
+}
+
+impl DeclaredBlockType {
+    pub fn from_js_obj(obj: &JsValue) -> Result<Self, StepError> {
+        let enter_behaviour = match get_js_field(obj, "enter")?.as_string().as_deref() {
+            Some("new_block_of_same_type") => EnterBehaviour::NewBlockOfSameType,
+            Some("new_paragraph") | None => EnterBehaviour::NewParagraph,
+            Some(enter) => return Err(StepError(format!("Invalid enter behaviour: {}", enter)))
+        };
+        let backspace_behaviour = match get_js_field(obj, "backspace")?.as_string().as_deref() {
+            Some("turn_into_paragraph") => BackspaceBehaviour::TurnIntoParagraph,
+            Some("join_with_block_above") | None => BackspaceBehaviour::JoinWithBlockAbove,
+            Some(backspace) => return Err(StepError(format!("Invalid backspace behaviour: {}", backspace)))
+        };
+        return Ok(Self {
+            name: get_js_field_as_string(obj, "name")?,
+            has_content: get_js_field_as_bool(obj, "has_content")?,
+            can_have_children: get_js_field(obj, "can_have_children")?.as_bool().unwrap_or(true),
+            enter_behaviour,
+            backspace_behaviour
+        })
+    }
+}
+
+impl CustomBlockType for DeclaredBlockType {
+    fn name(&self) -> String {
+        return self.name.clone()
+    }
+    fn has_content(&self) -> bool {
+        return self.has_content
+    }
+    fn can_have_children(&self) -> bool {
+        return self.can_have_children
+    }
+    fn data_from_json(&self, content: &Value) -> Result<Map<String, Value>, StepError> {
+        let mut data = content.as_object().ok_or(StepError(format!("Block content should be an object. Got: {}", content)))?.clone();
+        data.remove("inline_blocks");
+        return Ok(data)
+    }
+    fn enter_behaviour(&self) -> EnterBehaviour {
+        return self.enter_behaviour
+    }
+    fn backspace_behaviour(&self) -> BackspaceBehaviour {
+        return self.backspace_behaviour
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CustomBlock {
+    pub _type: String,
+    /// Only set if the block type has content
+    pub content: Option<ContentBlock>,
+    pub data: Map<String, Value>
+}
+
+impl CustomBlock {
+    /// An empty block of the registered type
+    pub fn new(_type: &str) -> Result<Self, StepError> {
+        let block_type = get_block_type(_type)?;
+        return Ok(Self {
+            _type: block_type.name(),
+            content: match block_type.has_content() {
+                true => Some(ContentBlock::new(vec![])),
+                false => None
+            },
+            data: block_type.default_data()
+        })
+    }
+
+    pub fn block_type(&self) -> Result<Rc<dyn CustomBlockType>, StepError> {
+        return get_block_type(&self._type)
+    }
+
+    pub fn from_js_block(obj: &JsValue) -> Result<Self, StepError> {
+        let content = js_sys::JSON::stringify(&get_js_field(obj, "content")?)
+            .map_err(|_| StepError("Failed to stringify custom block content".to_string()))?;
+        let content: Value = serde_json::from_str(&String::from(content))
+            .map_err(|_| StepError("Custom block content is not valid json".to_string()))?;
+        return Self::from_json(&json!({ "_type": get_js_field_as_string(obj, "_type")?, "content": content }))
+    }
+
+    pub fn from_json(json: &Value) -> Result<Self, StepError> {
+        let _type = json.get("_type").and_then(|_type| _type.as_str())
+            .ok_or(StepError("Block does not have _type field".to_string()))?;
+        let block_type = get_block_type(_type)?;
+        let content = json.get("content").ok_or(StepError("Block does not have content field".to_string()))?;
+        return Ok(Self {
+            _type: block_type.name(),
+            content: match block_type.has_content() {
+                true => Some(ContentBlock::from_json(json)?),
+                false => None
+            },
+            data: block_type.data_from_json(content)?
+        })
+    }
+
+    /// The block's "content" field
+    pub fn content_to_json(&self) -> Value {
+        let mut content = match self.block_type() {
+            Ok(block_type) => block_type.data_to_json(&self.data),
+            Err(_) => self.data.clone()
+        };
+        if let Some(content_block) = &self.content {
+            content.insert("inline_blocks".to_string(), json!(content_block.inline_blocks));
+        }
+        return Value::Object(content)
+    }
+
+    pub fn update_content(&self, content_block: ContentBlock) -> Result<Self, StepError> {
+        if self.content.is_none() {
+            return Err(StepError(format!("Block type: {} does not have 'content'", self._type)))
+        }
+        return Ok(Self { content: Some(content_block), ..self.clone() })
+    }
+}
+
+fn get_block_type(_type: &str) -> Result<Rc<dyn CustomBlockType>, StepError> {
+    return get_custom_block_type(_type).ok_or(StepError(format!("Block type '{}' not found", _type)))
+}
@@ -1,19 +1,37 @@
+use std::ops::Range;
+
 use wasm_bindgen::JsValue;
 use crate::{steps_generator::StepError, frontend_interface::{get_js_field, get_js_field_as_bool}};
 
+/// Smallest ratio a column can be resized to
+pub const MIN_COLUMN_WIDTH: f64 = 0.05;
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct LayoutBlock{
     pub horizontal: bool,
+    /// Width ratio of each column (only used by horizontal layouts), sums to 1
+    pub widths: Vec<f64>,
 }
 impl LayoutBlock {
     pub fn new() -> Self {
-        LayoutBlock { horizontal: true }
+        LayoutBlock { horizontal: true, widths: vec![] }
+    }
+
+    pub fn with_equal_widths(horizontal: bool, column_count: usize) -> Self {
+        return LayoutBlock { horizontal, widths: equal_widths(horizontal, column_count) }
     }
 
     pub fn from_js_block(obj: &JsValue) -> Result<Self, StepError> {
         let content = get_js_field(obj, "content")?;
         let horizontal = get_js_field_as_bool(&content, "horizontal")?;
-        return Ok(LayoutBlock {  horizontal})
+        let widths_js = get_js_field(&content, "widths")?;
+        let widths = match widths_js.is_undefined() || widths_js.is_null() {
+            true => vec![],
+            false => js_sys::Array::from(&widths_js).iter()
+                .map(|width| width.as_f64().ok_or(StepError("widths on layout block should only contain numbers".to_string())))
+                .collect::<Result<Vec<f64>, StepError>>()?
+        };
+        return Ok(LayoutBlock { horizontal, widths })
     }
 
     pub fn from_json(block: &serde_json::Value) -> Result<Self, StepError> {
@@ -22,6 +40,70 @@ impl LayoutBlock {
             Some(horizontal) => horizontal,
             None => return Err(StepError("horizontal on layout block json should be a bool".to_string()))
         };
-        return Ok(LayoutBlock { horizontal })
+        let widths = match block.get("widths") {
+            Some(widths) if !widths.is_null() => widths.as_array()
+                .ok_or(StepError("widths on layout block json should be an array".to_string()))?
+                .iter()
+                .map(|width| width.as_f64().ok_or(StepError("widths on layout block json should only contain numbers".to_string())))
+                .collect::<Result<Vec<f64>, StepError>>()?,
+            _ => vec![]
+        };
+        return Ok(LayoutBlock { horizontal, widths })
+    }
+
+    /// Keeps the widths in line with the columns when columns are added or removed.
+    /// -> widths that don't match the column count (eg: older layouts without widths) are reset to equal widths
+    /// -> removed columns lose their width & each new column gets an equal share
+    /// -> normalise so the widths sum to 1
+    pub fn splice_widths(&mut self, range: Range<usize>, inserted: usize, column_count: usize) {
+        if !self.horizontal {
+            return
+        }
+        if self.widths.len() != column_count {
+            self.widths = equal_widths(true, column_count);
+        }
+        let new_column_count = column_count - range.len() + inserted;
+        let new_column_width = match new_column_count {
+            0 => 0.0,
+            count => 1.0 / count as f64
+        };
+        self.widths.splice(range, vec![new_column_width; inserted]);
+        self.normalise_widths();
     }
-}
\ No newline at end of file
+
+    pub fn normalise_widths(&mut self) {
+        let total: f64 = self.widths.iter().sum();
+        if total <= 0.0 || self.widths.iter().any(|width| *width <= 0.0) {
+            self.widths = equal_widths(self.horizontal, self.widths.len());
+            return
+        }
+        for width in self.widths.iter_mut() {
+            *width = *width / total;
+        }
+    }
+
+    /// Moves the divider between the column at column_index and the next column.
+    /// ratio is the new share of the left column out of the two columns' combined width
+    pub fn resize_columns(&mut self, column_index: usize, ratio: f64) -> Result<(), StepError> {
+        if !self.horizontal {
+            return Err(StepError("Cannot resize the columns of a vertical layout block".to_string()))
+        }
+        if column_index + 1 >= self.widths.len() {
+            return Err(StepError(format!("Layout block does not have a column after column: {}", column_index)))
+        }
+        let combined_width = self.widths[column_index] + self.widths[column_index + 1];
+        let min_ratio = (MIN_COLUMN_WIDTH / combined_width).min(0.5);
+        let ratio = ratio.max(min_ratio).min(1.0 - min_ratio);
+        self.widths[column_index] = combined_width * ratio;
+        self.widths[column_index + 1] = combined_width - self.widths[column_index];
+        self.normalise_widths();
+        return Ok(())
+    }
+}
+
+fn equal_widths(horizontal: bool, column_count: usize) -> Vec<f64> {
+    return match horizontal && column_count > 0 {
+        true => vec![1.0 / column_count as f64; column_count],
+        false => vec![]
+    }
+}
@@ -7,22 +7,27 @@ use super::content_block::ContentBlock;
 #[derive(Debug, PartialEq, Clone)]
 pub struct ListBlock {
     pub content: ContentBlock,
-    pub completed: bool
+    pub completed: bool,
+    /// Restarts numbering at this value (only used by numbered lists)
+    pub start: Option<usize>
 }
 
 impl ListBlock {
     pub fn new() ->Self {
         return Self {
             content: ContentBlock { inline_blocks: vec![] },
-            completed: false
+            completed: false,
+            start: None
         }
     }
 
     pub fn from_js_block(obj: &JsValue) -> Result<Self, StepError> {
         let content = get_js_field(obj, "content")?;
+        let start = get_js_field(&content, "start")?.as_f64().map(|start| start as usize);
         return Ok(Self {
             content: ContentBlock::from_js_block(obj)?,
-            completed: get_js_field_as_bool(&content, "completed")?
+            completed: get_js_field_as_bool(&content, "completed")?,
+            start
         })
     }
 
@@ -33,9 +38,17 @@ impl ListBlock {
             Some(completed) => completed,
             None => return Err(StepError("'Completed' value is not a bool".to_string()))
         };
+        let start = match content.get("start") {
+            Some(start) if !start.is_null() => match start.as_u64() {
+                Some(start) => Some(start as usize),
+                None => return Err(StepError("'Start' value is not a positive integer".to_string()))
+            },
+            _ => None
+        };
         return Ok(Self {
             content: ContentBlock::from_json(block)?,
-            completed
+            completed,
+            start
         })
     }
 }
\ No newline at end of file
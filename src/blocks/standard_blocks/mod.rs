@@ -1,16 +1,21 @@
+use std::ops::Range;
+
 use serde_json::json;
 use wasm_bindgen::JsValue;
 
 use crate::{mark::Mark, steps_generator::{StepError, mark_steps::ForSelection}, new_ids::NewIds, frontend_interface::get_js_field_as_string, utilities::update_state_tools};
 
-use self::{content_block::ContentBlock, list_block::ListBlock, page_block::PageBlock, layout_block::LayoutBlock};
+use self::{content_block::ContentBlock, list_block::ListBlock, page_block::PageBlock, layout_block::LayoutBlock, toggle_block::ToggleBlock, table_of_contents_block::TableOfContentsBlock, custom_block::{CustomBlock, get_custom_block_type}};
 
-use super::{inline_blocks::InlineBlock, BlockMap, Block, vec_string_to_arr};
+use super::{inline_blocks::InlineBlock, BlockMap, Block, vec_string_to_arr, nesting_schema::with_nesting_schema};
 
 pub mod content_block;
 pub mod list_block;
 pub mod page_block;
 pub mod layout_block;
+pub mod toggle_block;
+pub mod table_of_contents_block;
+pub mod custom_block;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct StandardBlock {
@@ -115,6 +120,8 @@ impl StandardBlock {
             StandardBlockType::H2(block) | StandardBlockType::H3(block) => Ok(block),
             StandardBlockType::TodoList(content) | StandardBlockType::ArrowList(content) |
             StandardBlockType::DotPointList(content) | StandardBlockType::NumberedList(content) => Ok(&content.content),
+            StandardBlockType::ToggleList(toggle_block) => Ok(&toggle_block.content),
+            StandardBlockType::Custom(CustomBlock { content: Some(content), .. }) => Ok(content),
             _ => Err(StepError("Block does not have a content block".to_string()))
         }
     }
@@ -249,9 +256,9 @@ impl StandardBlock {
         return Ok(())
     }
 
-    /// Getting the last child on the deepest layer
+    /// Getting the last visible child on the deepest layer
     pub fn get_youngest_descendant(self, block_map: &BlockMap) -> Result<Self, StepError> {
-        match self.children.len() > 0 {
+        match self.visible_children().len() > 0 {
             true => {
                 let youngest_child = block_map.get_standard_block(&self.children[self.children.len() - 1])?;
                 return youngest_child.get_youngest_descendant(block_map)
@@ -339,11 +346,27 @@ impl StandardBlock {
     pub fn is_list(&self) -> bool {
         return match self.content {
             StandardBlockType::TodoList(_) | StandardBlockType::DotPointList(_)
-            | StandardBlockType::ArrowList(_) | StandardBlockType::NumberedList(_) => true,
+            | StandardBlockType::ArrowList(_) | StandardBlockType::NumberedList(_)
+            | StandardBlockType::ToggleList(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        return match &self.content {
+            StandardBlockType::ToggleList(toggle_block) => toggle_block.collapsed,
             _ => false
         }
     }
 
+    /// Children of a collapsed toggle are hidden, so for navigation this block has no children
+    pub fn visible_children(&self) -> Vec<String> {
+        return match self.is_collapsed() {
+            true => vec![],
+            false => self.children.clone()
+        }
+    }
+
     pub fn text_is_empty(&self, block_map: &BlockMap) -> Result<bool, StepError> {
         let content = self.content_block()?;
         if content.inline_blocks.len() == 1 {
@@ -377,10 +400,18 @@ impl StandardBlock {
         return Ok(inline_blocks)
     }
 
+    /// Horizontal layouts keep a width for each of their children (columns), so they're updated together
+    pub fn splice_children(&mut self, range: Range<usize>, insert: Vec<String>) {
+        if let StandardBlockType::Layout(layout_block) = &mut self.content {
+            layout_block.splice_widths(range.clone(), insert.len(), self.children.len());
+        }
+        self.children.splice(range, insert);
+    }
+
     pub fn new_layout_block(_id: String, horizontal: bool, children: Vec<String>, parent: String) -> Result<StandardBlock, StepError> {
         return Ok(Self {
             _id,
-            content: StandardBlockType::Layout(LayoutBlock { horizontal }),
+            content: StandardBlockType::Layout(LayoutBlock::with_equal_widths(horizontal, children.len())),
             children,
             parent,
             marks: vec![]
@@ -407,7 +438,7 @@ impl StandardBlock {
 
     pub fn is_horizontal_layout(&self) -> bool {
         return match &self.content {
-            StandardBlockType::Layout(LayoutBlock { horizontal: true }) => true,
+            StandardBlockType::Layout(LayoutBlock { horizontal: true, .. }) => true,
             _ => false
         }
     }
@@ -423,10 +454,14 @@ pub enum StandardBlockType {
     DotPointList(ListBlock),
     NumberedList(ListBlock),
     ArrowList(ListBlock),
+    ToggleList(ToggleBlock),
     InlinePage(PageBlock),
     SquarePage(PageBlock),
     LinkBlock(PageBlock),
     Layout(LayoutBlock),
+    TableOfContents(TableOfContentsBlock),
+    /// A block type registered by the application (see CustomBlockType)
+    Custom(CustomBlock),
 }
 
 impl StandardBlockType {
@@ -442,10 +477,13 @@ impl StandardBlockType {
             "dotpoint list" => Ok(StandardBlockType::DotPointList(ListBlock::from_js_block(obj)?)),
             "numbered list" => Ok(StandardBlockType::NumberedList(ListBlock::from_js_block(obj)?)),
             "arrow list" => Ok(StandardBlockType::ArrowList(ListBlock::from_js_block(obj)?)),
+            "toggle list" => Ok(StandardBlockType::ToggleList(ToggleBlock::from_js_block(obj)?)),
             "inline page" => Ok(StandardBlockType::InlinePage(PageBlock::from_js_block(obj)?)),
             "square page" => Ok(StandardBlockType::SquarePage(PageBlock::from_js_block(obj)?)),
             "link page" => Ok(StandardBlockType::LinkBlock(PageBlock::from_js_block(obj)?)),
             "layout" => Ok(StandardBlockType::Layout(LayoutBlock::from_js_block(obj)?)),
+            "table of contents" => Ok(StandardBlockType::TableOfContents(TableOfContentsBlock::from_js_block(obj)?)),
+            _type if get_custom_block_type(_type).is_some() => Ok(StandardBlockType::Custom(CustomBlock::from_js_block(obj)?)),
             _type => Err(StepError(format!("Block type '{}' not found", _type)))
         }
     }
@@ -461,10 +499,13 @@ impl StandardBlockType {
             "dotpoint list" => Ok(StandardBlockType::DotPointList(ListBlock::from_json(json)?)),
             "numbered list" => Ok(StandardBlockType::NumberedList(ListBlock::from_json(json)?)),
             "arrow list" => Ok(StandardBlockType::ArrowList(ListBlock::from_json(json)?)),
+            "toggle list" => Ok(StandardBlockType::ToggleList(ToggleBlock::from_json(json)?)),
             "inline page" => Ok(StandardBlockType::InlinePage(PageBlock::from_json(json)?)),
             "square page" => Ok(StandardBlockType::SquarePage(PageBlock::from_json(json)?)),
             "link page" => Ok(StandardBlockType::LinkBlock(PageBlock::from_json(json)?)),
             "layout" => Ok(StandardBlockType::Layout(LayoutBlock::from_json(json)?)),
+            "table of contents" => Ok(StandardBlockType::TableOfContents(TableOfContentsBlock::from_json(json)?)),
+            _type if get_custom_block_type(_type).is_some() => Ok(StandardBlockType::Custom(CustomBlock::from_json(json)?)),
             _ => Err(StepError(format!("Block type {} not found", block_type)))
         }
     }
@@ -508,7 +549,8 @@ impl StandardBlockType {
                     "_type": "to-do list",
                     "content": {
                         "inline_blocks": block.content.inline_blocks.iter().map(|inline_block| inline_block.to_string()).collect::<Vec<String>>(),
-                        "completed": block.completed
+                        "completed": block.completed,
+                        "start": block.start
                     }
                 })
             },
@@ -517,7 +559,8 @@ impl StandardBlockType {
                     "_type": "dotpoint list",
                     "content": {
                         "inline_blocks": block.content.inline_blocks.iter().map(|inline_block| inline_block.to_string()).collect::<Vec<String>>(),
-                        "completed": block.completed
+                        "completed": block.completed,
+                        "start": block.start
                     }
                 })
             },
@@ -526,7 +569,8 @@ impl StandardBlockType {
                     "_type": "numbered list",
                     "content": {
                         "inline_blocks": block.content.inline_blocks.iter().map(|inline_block| inline_block.to_string()).collect::<Vec<String>>(),
-                        "completed": block.completed
+                        "completed": block.completed,
+                        "start": block.start
                     }
                 })
             },
@@ -535,7 +579,17 @@ impl StandardBlockType {
                     "_type": "arrow list",
                     "content": {
                         "inline_blocks": block.content.inline_blocks.iter().map(|inline_block| inline_block.to_string()).collect::<Vec<String>>(),
-                        "completed": block.completed
+                        "completed": block.completed,
+                        "start": block.start
+                    }
+                })
+            },
+            StandardBlockType::ToggleList(block) => {
+                json!({
+                    "_type": "toggle list",
+                    "content": {
+                        "inline_blocks": block.content.inline_blocks.iter().map(|inline_block| inline_block.to_string()).collect::<Vec<String>>(),
+                        "collapsed": block.collapsed
                     }
                 })
             },
@@ -567,10 +621,25 @@ impl StandardBlockType {
                 json!({
                     "_type": "layout",
                     "content": {
-                        "horizontal": block.horizontal
+                        "horizontal": block.horizontal,
+                        "widths": block.widths
+                    }
+                })
+            },
+            StandardBlockType::TableOfContents(block) => {
+                json!({
+                    "_type": "table of contents",
+                    "content": {
+                        "entries": block.entries_to_json()
                     }
                 })
             },
+            StandardBlockType::Custom(block) => {
+                json!({
+                    "_type": block._type,
+                    "content": block.content_to_json()
+                })
+            },
         }
     }
 
@@ -584,10 +653,13 @@ impl StandardBlockType {
             StandardBlockType::DotPointList(_) => return "dotpoint list".to_string(),
             StandardBlockType::NumberedList(_) => return "numbered list".to_string(),
             StandardBlockType::ArrowList(_) => return "arrow list".to_string(),
+            StandardBlockType::ToggleList(_) => return "toggle list".to_string(),
             StandardBlockType::InlinePage(_) => return "inline page".to_string(),
             StandardBlockType::SquarePage(_) => return "square page".to_string(),
             StandardBlockType::LinkBlock(_) => return "link page".to_string(),
             StandardBlockType::Layout(_) => return "layout".to_string(),
+            StandardBlockType::TableOfContents(_) => return "table of contents".to_string(),
+            StandardBlockType::Custom(block) => return block._type.clone(),
         }
     }
 
@@ -604,12 +676,33 @@ impl StandardBlockType {
                 => {
                     js_sys::Reflect::set(&content, &JsValue::from_str("inline_blocks"), &vec_string_to_arr(&list_block.content.inline_blocks)?.into()).unwrap();
                     js_sys::Reflect::set(&content, &JsValue::from_str("completed"), &JsValue::from(list_block.completed)).unwrap();
+                    let start = match list_block.start {
+                        Some(start) => JsValue::from_f64(start as f64),
+                        None => JsValue::null()
+                    };
+                    js_sys::Reflect::set(&content, &JsValue::from_str("start"), &start).unwrap();
+            },
+            StandardBlockType::ToggleList(toggle_block) => {
+                js_sys::Reflect::set(&content, &JsValue::from_str("inline_blocks"), &vec_string_to_arr(&toggle_block.content.inline_blocks)?.into()).unwrap();
+                js_sys::Reflect::set(&content, &JsValue::from_str("collapsed"), &JsValue::from(toggle_block.collapsed)).unwrap();
             },
             StandardBlockType::InlinePage(page_block) | StandardBlockType::SquarePage(page_block) | StandardBlockType::LinkBlock(page_block) => {
                 js_sys::Reflect::set(&content, &JsValue::from_str("page_id"), &JsValue::from_str(&page_block.page_id)).unwrap();
             },
             StandardBlockType::Layout(layout_block) => {
                 js_sys::Reflect::set(&content, &JsValue::from_str("horizontal"), &JsValue::from(layout_block.horizontal)).unwrap();
+                let widths = js_sys::Array::new();
+                for width in &layout_block.widths {
+                    widths.push(&JsValue::from_f64(*width));
+                }
+                js_sys::Reflect::set(&content, &JsValue::from_str("widths"), &JsValue::from(widths)).unwrap();
+            },
+            StandardBlockType::TableOfContents(table_of_contents_block) => {
+                js_sys::Reflect::set(&content, &JsValue::from_str("entries"), &JsValue::from(table_of_contents_block.entries_to_js())).unwrap();
+            },
+            StandardBlockType::Custom(custom_block) => {
+                return js_sys::JSON::parse(&custom_block.content_to_json().to_string())
+                    .map_err(|_| StepError(format!("Failed to convert content of block type: {} to js", custom_block._type)))
             }
 
         }
@@ -624,20 +717,29 @@ impl StandardBlockType {
             StandardBlockType::H3(_) => Ok(StandardBlockType::H3(content_block)),
             StandardBlockType::TodoList(list_block) => Ok(StandardBlockType::TodoList(ListBlock {
                 content: content_block,
-                completed: list_block.completed
+                completed: list_block.completed,
+                start: list_block.start
             })),
             StandardBlockType::DotPointList(list_block) => Ok(StandardBlockType::DotPointList(ListBlock {
                 content: content_block,
-                completed: list_block.completed
+                completed: list_block.completed,
+                start: list_block.start
             })),
             StandardBlockType::NumberedList(list_block) => Ok(StandardBlockType::NumberedList(ListBlock {
                 content: content_block,
-                completed: list_block.completed
+                completed: list_block.completed,
+                start: list_block.start
             })),
             StandardBlockType::ArrowList(list_block) => Ok(StandardBlockType::ArrowList(ListBlock {
                 content: content_block,
-                completed: list_block.completed
+                completed: list_block.completed,
+                start: list_block.start
+            })),
+            StandardBlockType::ToggleList(toggle_block) => Ok(StandardBlockType::ToggleList(ToggleBlock {
+                content: content_block,
+                collapsed: toggle_block.collapsed
             })),
+            StandardBlockType::Custom(custom_block) => Ok(StandardBlockType::Custom(custom_block.update_content(content_block)?)),
             block => Err(StepError(format!("This block type does not have 'content': {:#?}", block)))
         }
     }
@@ -664,30 +766,45 @@ impl StandardBlockType {
                 let updated_inline_blocks = vec![list_block.content.inline_blocks, new_inline_blocks].concat();
                 return Ok(Self::TodoList(ListBlock {
                     content: ContentBlock { inline_blocks: updated_inline_blocks },
-                    completed: list_block.completed
+                    completed: list_block.completed,
+                    start: list_block.start
                 }))
             },
             StandardBlockType::DotPointList(list_block) => {
                 let updated_inline_blocks = vec![list_block.content.inline_blocks, new_inline_blocks].concat();
                 return Ok(Self::DotPointList(ListBlock {
                     content: ContentBlock { inline_blocks: updated_inline_blocks },
-                    completed: list_block.completed
+                    completed: list_block.completed,
+                    start: list_block.start
                 }))
             },
             StandardBlockType::NumberedList(list_block) => {
                 let updated_inline_blocks = vec![list_block.content.inline_blocks, new_inline_blocks].concat();
                 return Ok(Self::NumberedList(ListBlock {
                     content: ContentBlock { inline_blocks: updated_inline_blocks },
-                    completed: list_block.completed
+                    completed: list_block.completed,
+                    start: list_block.start
                 }))
             },
             StandardBlockType::ArrowList(list_block) => {
                 let updated_inline_blocks = vec![list_block.content.inline_blocks, new_inline_blocks].concat();
                 return Ok(Self::ArrowList(ListBlock {
                     content: ContentBlock { inline_blocks: updated_inline_blocks },
-                    completed: list_block.completed
+                    completed: list_block.completed,
+                    start: list_block.start
                 }))
             },
+            StandardBlockType::ToggleList(toggle_block) => {
+                let updated_inline_blocks = vec![toggle_block.content.inline_blocks, new_inline_blocks].concat();
+                return Ok(Self::ToggleList(ToggleBlock {
+                    content: ContentBlock { inline_blocks: updated_inline_blocks },
+                    collapsed: toggle_block.collapsed
+                }))
+            },
+            StandardBlockType::Custom(CustomBlock { content: Some(content), _type, data }) => {
+                let updated_inline_blocks = vec![content.inline_blocks, new_inline_blocks].concat();
+                return Ok(Self::Custom(CustomBlock { _type, content: Some(ContentBlock { inline_blocks: updated_inline_blocks }), data }))
+            },
             block => Err(StepError(format!("This block type does not have 'content': {:#?}", block)))
         }
     }
@@ -695,8 +812,15 @@ impl StandardBlockType {
     pub fn has_content(&self) -> bool {
         match self {
             Self::Paragraph(_) | Self::H1(_) | Self::H2(_) | Self::H3(_) |
-            Self::DotPointList(_) | Self::NumberedList(_) | Self::ArrowList(_) | Self::TodoList(_) => true,
+            Self::DotPointList(_) | Self::NumberedList(_) | Self::ArrowList(_) | Self::TodoList(_) |
+            Self::ToggleList(_) => true,
+            Self::Custom(custom_block) => custom_block.content.is_some(),
             _ => false
         }
     }
+
+    /// See NestingSchema
+    pub fn can_have_children(&self) -> bool {
+        return with_nesting_schema(|schema| schema.can_have_children(self))
+    }
 }
\ No newline at end of file
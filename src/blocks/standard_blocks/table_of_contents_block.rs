@@ -0,0 +1,83 @@
+use serde_json::json;
+use wasm_bindgen::JsValue;
+
+use crate::{steps_generator::StepError, frontend_interface::{get_js_field, get_js_field_as_string, get_js_field_as_f64},
+    backend_interface::{get_json_field_as_string, get_json_field_as_int}};
+
+/// A heading listed in a table of contents (the nested outline is rebuilt from the levels)
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableOfContentsEntry {
+    pub block_id: String,
+    pub text: String,
+    pub level: usize, // 1 - 3
+    pub anchor: String
+}
+
+/// Content is generated from the headings under the block's root & regenerated when they change
+#[derive(Debug, PartialEq, Clone)]
+pub struct TableOfContentsBlock {
+    pub entries: Vec<TableOfContentsEntry>
+}
+
+impl TableOfContentsBlock {
+    pub fn new() -> Self {
+        return Self { entries: vec![] }
+    }
+
+    pub fn from_js_block(obj: &JsValue) -> Result<Self, StepError> {
+        let content = get_js_field(obj, "content")?;
+        let entries_js = get_js_field(&content, "entries")?;
+        let entries = match entries_js.is_undefined() || entries_js.is_null() {
+            true => vec![],
+            false => js_sys::Array::from(&entries_js).iter()
+                .map(|entry| Ok(TableOfContentsEntry {
+                    block_id: get_js_field_as_string(&entry, "block_id")?,
+                    text: get_js_field_as_string(&entry, "text")?,
+                    level: get_js_field_as_f64(&entry, "level")? as usize,
+                    anchor: get_js_field_as_string(&entry, "anchor")?
+                }))
+                .collect::<Result<Vec<TableOfContentsEntry>, StepError>>()?
+        };
+        return Ok(Self { entries })
+    }
+
+    pub fn from_json(block: &serde_json::Value) -> Result<Self, StepError> {
+        let content = block.get("content").ok_or(StepError("Block does not have content field".to_string()))?;
+        let entries = match content.get("entries") {
+            Some(entries) if !entries.is_null() => entries.as_array()
+                .ok_or(StepError("entries on table of contents block json should be an array".to_string()))?
+                .iter()
+                .map(|entry| Ok(TableOfContentsEntry {
+                    block_id: get_json_field_as_string(entry, "block_id")?,
+                    text: get_json_field_as_string(entry, "text")?,
+                    level: get_json_field_as_int(entry, "level")? as usize,
+                    anchor: get_json_field_as_string(entry, "anchor")?
+                }))
+                .collect::<Result<Vec<TableOfContentsEntry>, StepError>>()?,
+            _ => vec![]
+        };
+        return Ok(Self { entries })
+    }
+
+    pub fn entries_to_json(&self) -> serde_json::Value {
+        return serde_json::Value::Array(self.entries.iter().map(|entry| json!({
+            "block_id": entry.block_id,
+            "text": entry.text,
+            "level": entry.level,
+            "anchor": entry.anchor
+        })).collect())
+    }
+
+    pub fn entries_to_js(&self) -> js_sys::Array {
+        let entries = js_sys::Array::new();
+        for entry in &self.entries {
+            let obj = js_sys::Object::new();
+            js_sys::Reflect::set(&obj, &JsValue::from_str("block_id"), &JsValue::from_str(&entry.block_id)).unwrap();
+            js_sys::Reflect::set(&obj, &JsValue::from_str("text"), &JsValue::from_str(&entry.text)).unwrap();
+            js_sys::Reflect::set(&obj, &JsValue::from_str("level"), &JsValue::from_f64(entry.level as f64)).unwrap();
+            js_sys::Reflect::set(&obj, &JsValue::from_str("anchor"), &JsValue::from_str(&entry.anchor)).unwrap();
+            entries.push(&JsValue::from(obj));
+        }
+        return entries
+    }
+}
@@ -0,0 +1,41 @@
+use wasm_bindgen::JsValue;
+
+use crate::{steps_generator::StepError, frontend_interface::{get_js_field, get_js_field_as_bool}};
+
+use super::content_block::ContentBlock;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ToggleBlock {
+    pub content: ContentBlock,
+    pub collapsed: bool // when true the block's children are hidden
+}
+
+impl ToggleBlock {
+    pub fn new() -> Self {
+        return Self {
+            content: ContentBlock { inline_blocks: vec![] },
+            collapsed: false
+        }
+    }
+
+    pub fn from_js_block(obj: &JsValue) -> Result<Self, StepError> {
+        let content = get_js_field(obj, "content")?;
+        return Ok(Self {
+            content: ContentBlock::from_js_block(obj)?,
+            collapsed: get_js_field_as_bool(&content, "collapsed")?
+        })
+    }
+
+    pub fn from_json(block: &serde_json::Value) -> Result<Self, StepError> {
+        let content = block.get("content").ok_or(StepError("Block does not have content field".to_string()))?;
+        let collapsed = match content.get("collapsed").ok_or(StepError("Block does not have collapsed field".to_string()))?
+        .as_bool() {
+            Some(collapsed) => collapsed,
+            None => return Err(StepError("'Collapsed' value is not a bool".to_string()))
+        };
+        return Ok(Self {
+            content: ContentBlock::from_json(block)?,
+            collapsed
+        })
+    }
+}
@@ -2,7 +2,9 @@
 use wasm_bindgen::JsValue;
 
 use crate::{steps_generator::{event::Event, selection::{Selection, SubSelection}, generate_steps, StepError},
-new_ids::NewIds, blocks::{BlockMap}, steps_actualisor::{actualise_steps, UpdatedState}, custom_copy::CustomCopy};
+new_ids::NewIds, blocks::{BlockMap, nesting_schema::{NestingSchema, set_nesting_schema}, standard_blocks::custom_block::{DeclaredBlockType, register_custom_block_type}}, step::Step, steps_actualisor::{actualise_steps, UpdatedState}, custom_copy::CustomCopy, mark::{DeclaredMarkType, register_custom_mark_type},
+utilities::{list_numbering::get_list_numbering, document_statistics::{get_document_statistics, get_selection_statistics}, outline::get_outline, comments::get_comments, suggestions::get_suggestions, snapshots::{capture_snapshot, list_snapshots, Snapshot}, position_mapping::map_selection, presence::{RemotePresence, map_presences_through_step}}, steps_generator::slash_scrim::get_slash_scrim_candidates,
+steps_generator::find_replace::{find_matches, SearchOptions}, steps_generator::smart_typography::get_smart_typography_conversion};
 
 pub fn actualise_event(
     selection_js: js_sys::Object,
@@ -17,12 +19,19 @@ pub fn actualise_event(
     let event = Event::from_js_obj(event_js).unwrap();
     let mut new_ids = NewIds::Js(new_ids_arr);
 
+    let smart_typography_conversion = match &event {
+        Event::KeyPress(key_press) => selection.clone().get_from_to(&block_map)
+            .and_then(|(from, to)| get_smart_typography_conversion(key_press, &block_map, &from, &to))
+            .unwrap_or(None),
+        _ => None
+    };
     let steps = match generate_steps(&event, &block_map, selection, &copy, &mut new_ids) {
         Ok(steps) => steps,
         Err(StepError(err)) => return Response {
             selection: None,
             blocks_to_update: JsValue::from(js_sys::Array::new()),
             steps: JsValue::from(js_sys::Array::new()),
+            smart_typography_conversion: None,
             err: Some(err)
         }
     };
@@ -47,6 +56,7 @@ pub fn actualise_event(
                 selection,
                 blocks_to_update: JsValue::from(js_blocks_to_update),
                 steps: JsValue::from(steps_js),
+                smart_typography_conversion: smart_typography_conversion.map(|conversion| conversion.to_js_obj().unwrap()),
                 err: None
             }
         },
@@ -54,6 +64,7 @@ pub fn actualise_event(
             selection: None,
             blocks_to_update: JsValue::from(js_sys::Array::new()),
             steps: JsValue::from(js_sys::Array::new()),
+            smart_typography_conversion: None,
             err: Some(err)
         }
     }
@@ -63,6 +74,8 @@ pub struct Response {
     pub selection: Option<JsValue>,
     pub blocks_to_update: JsValue,
     pub steps: JsValue,
+    /// Sent back in the next key press's metadata (as last_conversion), so backspace can undo the conversion
+    pub smart_typography_conversion: Option<JsValue>,
     pub err: Option<String>
 }
 
@@ -138,4 +151,276 @@ pub fn get_selection(
     };
 
     return selection.to_js_obj().unwrap()
-}
\ No newline at end of file
+}
+
+/// Returns a js Map of numbered list block id -> display label (eg: "1." || "a." || "i.")
+pub fn get_list_numbering_labels(
+    root_block_id: String,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let labels = js_sys::Map::new();
+    match get_list_numbering(&root_block_id, &block_map) {
+        Ok(numbering) => {
+            for (id, list_number) in numbering {
+                labels.set(&JsValue::from_str(&id), &JsValue::from_str(&list_number.label()));
+            }
+        },
+        Err(_) => {}
+    };
+    return JsValue::from(labels)
+}
+
+/// Returns a js Array of the slash commands matching the text typed after the "/" before the caret
+/// eg: [{ name: "heading 1", aliases: ["h1", "title"], description: "Large section heading" }]
+pub fn get_slash_scrim_commands(
+    block_id: String,
+    offset: usize,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let caret = SubSelection { block_id, offset, subselection: None };
+    let commands_js = js_sys::Array::new();
+    match get_slash_scrim_candidates(&caret, &block_map) {
+        Ok(commands) => {
+            for command in commands {
+                let obj = js_sys::Object::new();
+                let aliases = js_sys::Array::new();
+                for alias in command.aliases {
+                    aliases.push(&JsValue::from_str(alias));
+                }
+                js_sys::Reflect::set(&obj, &JsValue::from_str("name"), &JsValue::from_str(command.name)).unwrap();
+                js_sys::Reflect::set(&obj, &JsValue::from_str("aliases"), &JsValue::from(aliases)).unwrap();
+                js_sys::Reflect::set(&obj, &JsValue::from_str("description"), &JsValue::from_str(command.description)).unwrap();
+                commands_js.push(&JsValue::from(obj));
+            }
+        },
+        Err(_) => {}
+    };
+    return JsValue::from(commands_js)
+}
+
+/// Returns a js Array of selections for every match of the query in the page, in document order
+/// Returns the error message if the query is an invalid regex
+pub fn find_in_page(
+    query: String,
+    case_sensitive: bool,
+    whole_word: bool,
+    regex: bool,
+    root_block_id: String,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let options = SearchOptions { case_sensitive, whole_word, regex };
+    return match find_matches(&query, &root_block_id, &options, &block_map) {
+        Ok(matches) => {
+            let matches_js = js_sys::Array::new();
+            for selection in matches {
+                matches_js.push(&selection.to_js_obj().unwrap());
+            }
+            JsValue::from(matches_js)
+        },
+        Err(StepError(err)) => JsValue::from_str(&err)
+    }
+}
+
+/// Returns a js obj of counts (characters, words, sentences, blocks, headings, todos, reading time) for the whole page
+pub fn get_page_statistics(
+    root_block_id: String,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    return match get_document_statistics(&root_block_id, &block_map) {
+        Ok(statistics) => statistics.to_js_obj().unwrap(),
+        Err(_) => JsValue::NULL
+    }
+}
+
+/// Returns a js Array of the page's headings, nested by level
+/// eg: [{ block_id, text: "Intro", level: 1, anchor: "intro", children: [...] }]
+pub fn get_page_outline(
+    root_block_id: String,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let outline_js = js_sys::Array::new();
+    match get_outline(&root_block_id, &block_map) {
+        Ok(outline) => {
+            for item in outline {
+                outline_js.push(&item.to_js_obj().unwrap());
+            }
+        },
+        Err(_) => {}
+    };
+    return JsValue::from(outline_js)
+}
+
+/// Returns a js Array of the page's comments with their current selections, in document order
+/// eg: [{ comment_id, resolved: false, selection: { anchor, head }, text: "commented text" }]
+pub fn get_page_comments(
+    root_block_id: String,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let comments_js = js_sys::Array::new();
+    match get_comments(&root_block_id, &block_map) {
+        Ok(comments) => {
+            for comment in comments {
+                comments_js.push(&comment.to_js_obj().unwrap());
+            }
+        },
+        Err(_) => {}
+    };
+    return JsValue::from(comments_js)
+}
+
+/// Returns a js Array of the page's pending suggestions, in document order
+/// eg: [{ suggestion_id, author, inserted_text: "new", deleted_text: "old" }]
+pub fn get_page_suggestions(
+    root_block_id: String,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let suggestions_js = js_sys::Array::new();
+    match get_suggestions(&root_block_id, &block_map) {
+        Ok(suggestions) => {
+            for suggestion in suggestions {
+                suggestions_js.push(&suggestion.to_js_obj().unwrap());
+            }
+        },
+        Err(_) => {}
+    };
+    return JsValue::from(suggestions_js)
+}
+
+/// Returns a js obj of the snapshot: { id, name, timestamp, root_block_id, block_map }
+/// Restore it with a "restore_snapshot" event
+pub fn capture_page_snapshot(
+    name: String,
+    root_block_id: String,
+    new_ids_arr: js_sys::Array,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let mut new_ids = NewIds::Js(new_ids_arr);
+    return match capture_snapshot(name, js_sys::Date::now(), &root_block_id, &block_map, &mut new_ids) {
+        Ok(snapshot) => snapshot.to_js_obj().unwrap(),
+        Err(_) => JsValue::null()
+    }
+}
+
+/// Returns a js Array of the page's snapshots without their blocks, newest first
+/// eg: [{ id, name, timestamp, root_block_id }]
+pub fn list_page_snapshots(
+    root_block_id: String,
+    snapshots_js: js_sys::Array,
+) -> JsValue {
+    let mut snapshots = vec![];
+    for snapshot_js in snapshots_js.to_vec() {
+        match Snapshot::from_js_obj(&snapshot_js) {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(_) => {}
+        };
+    }
+    let summaries_js = js_sys::Array::new();
+    for summary in list_snapshots(&root_block_id, &snapshots) {
+        summaries_js.push(&summary.to_js_obj().unwrap());
+    }
+    return JsValue::from(summaries_js)
+}
+
+/// Where the selection is after a (remote) step, given the block map before & after the step.
+/// step_js is a step as sent in Response.steps: { _type, data }
+/// Returns null if the selection can't be placed anywhere in the page
+pub fn map_selection_through_step(
+    selection_js: js_sys::Object,
+    step_js: js_sys::Object,
+    old_block_map_js: js_sys::Map,
+    new_block_map_js: js_sys::Map,
+) -> JsValue {
+    let mapped_selection = Selection::from_js_obj(selection_js).and_then(|selection| {
+        let step = Step::from_json(&get_js_field_as_string(&step_js, "_type")?, &get_js_field_as_string(&step_js, "data")?)?;
+        map_selection(&selection, &step, &BlockMap::from_js_map(old_block_map_js), &BlockMap::from_js_map(new_block_map_js))
+    });
+    return match mapped_selection {
+        Ok(Some(selection)) => selection.to_js_obj().unwrap(),
+        _ => JsValue::null()
+    }
+}
+
+/// Remaps other users' presences through a step, dropping ones whose blocks were removed
+pub fn map_presences_through_js_step(
+    presences_js: js_sys::Array,
+    step_js: js_sys::Object,
+    old_block_map_js: js_sys::Map,
+    new_block_map_js: js_sys::Map,
+) -> JsValue {
+    let mapped_presences = presences_js.iter()
+        .map(|presence| RemotePresence::from_js_obj(&presence))
+        .collect::<Result<Vec<RemotePresence>, StepError>>()
+        .and_then(|presences| {
+            let step = Step::from_json(&get_js_field_as_string(&step_js, "_type")?, &get_js_field_as_string(&step_js, "data")?)?;
+            Ok(map_presences_through_step(presences, &step, &BlockMap::from_js_map(old_block_map_js), &BlockMap::from_js_map(new_block_map_js)))
+        });
+    let arr = js_sys::Array::new();
+    if let Ok(presences) = mapped_presences {
+        for presence in presences {
+            arr.push(&presence.to_js_obj().unwrap());
+        }
+    }
+    return JsValue::from(arr)
+}
+
+/// Sets which blocks can be nested inside which (see NestingSchema::from_json)
+/// eg: { childless_types: ["h1", "h2", "h3"], allowed_children: { root: ["paragraph", "h1"] } }
+/// Returns the error message if the schema is invalid
+pub fn set_block_nesting_schema(schema_js: js_sys::Object) -> JsValue {
+    let schema = js_sys::JSON::stringify(&schema_js)
+        .map_err(|_| StepError("Failed to stringify nesting schema".to_string()))
+        .and_then(|schema| serde_json::from_str(&String::from(schema))
+            .map_err(|_| StepError("Nesting schema is not valid json".to_string())))
+        .and_then(|json| NestingSchema::from_json(&json));
+    return match schema {
+        Ok(schema) => {
+            set_nesting_schema(schema);
+            JsValue::null()
+        },
+        Err(StepError(err)) => JsValue::from_str(&err)
+    }
+}
+
+/// Registers a custom standard block type (see DeclaredBlockType)
+/// eg: { name: "callout", has_content: true, can_have_children: false, enter: "new_block_of_same_type", backspace: "turn_into_paragraph" }
+/// Returns the error message if the block type is invalid or has the name of a built in block type
+pub fn register_block_type(block_type_js: js_sys::Object) -> JsValue {
+    return match DeclaredBlockType::from_js_obj(&JsValue::from(block_type_js))
+        .and_then(|block_type| register_custom_block_type(std::rc::Rc::new(block_type))) {
+        Ok(_) => JsValue::null(),
+        Err(StepError(err)) => JsValue::from_str(&err)
+    }
+}
+
+/// Registers a custom mark (see DeclaredMarkType)
+/// eg: { name: "highlight", has_value: true, exclusivity_group: "highlight" }
+/// Returns the error message if the mark type is invalid or has the name of a built in mark
+pub fn register_mark_type(mark_type_js: js_sys::Object) -> JsValue {
+    return match DeclaredMarkType::from_js_obj(&JsValue::from(mark_type_js))
+        .and_then(|mark_type| register_custom_mark_type(std::rc::Rc::new(mark_type))) {
+        Ok(_) => JsValue::null(),
+        Err(StepError(err)) => JsValue::from_str(&err)
+    }
+}
+
+/// Same counts as get_page_statistics, but only for the selected text
+pub fn get_selected_statistics(
+    selection_js: js_sys::Object,
+    block_map_js: js_sys::Map,
+) -> JsValue {
+    let block_map = BlockMap::from_js_map(block_map_js);
+    let statistics = Selection::from_js_obj(selection_js)
+        .and_then(|selection| get_selection_statistics(&selection, &block_map));
+    return match statistics {
+        Ok(statistics) => statistics.to_js_obj().unwrap(),
+        Err(_) => JsValue::NULL
+    }
+}
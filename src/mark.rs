@@ -1,6 +1,13 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
 use serde_json::{Value, json};
+use wasm_bindgen::JsValue;
+
+use crate::{steps_generator::StepError, frontend_interface::{get_js_field, get_js_field_as_string}};
 
-use crate::steps_generator::StepError;
+thread_local! {
+    static CUSTOM_MARK_TYPES: RefCell<HashMap<String, Rc<dyn CustomMarkType>>> = RefCell::new(HashMap::new());
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Mark {
@@ -10,6 +17,11 @@ pub enum Mark {
     Strikethrough,
     ForeColor(Color),
     BackColor(Color),
+    Comment(CommentMark),
+    Insertion(SuggestionMark),
+    Deletion(SuggestionMark),
+    /// A mark registered by the application (see CustomMarkType)
+    Custom(CustomMark),
 }
 
 impl Mark {
@@ -20,6 +32,10 @@ impl Mark {
             "underline" => Ok(Mark::Underline),
             "strikethrough" => Ok(Mark::Strikethrough),
             mark if mark.contains("fore_color") | mark.contains("back_color") => Mark::color_mark_from_str(mark),
+            mark if mark.starts_with("comment(") => Ok(Mark::Comment(CommentMark::from_str(mark)?)),
+            mark if mark.starts_with("insertion(") => Ok(Mark::Insertion(SuggestionMark::from_str("insertion", mark)?)),
+            mark if mark.starts_with("deletion(") => Ok(Mark::Deletion(SuggestionMark::from_str("deletion", mark)?)),
+            mark if get_custom_mark_type(CustomMark::name_from_str(mark)).is_some() => Ok(Mark::Custom(CustomMark::from_str(mark)?)),
             _ => Err(StepError(format!("Invalid Mark: {}", mark)))
         }
     }
@@ -32,30 +48,16 @@ impl Mark {
             Mark::Strikethrough => "strikethrough".to_string(),
             Mark::ForeColor(color) => format!("fore_color{}", color.to_string()),
             Mark::BackColor(color) => format!("back_color{}", color.to_string()),
+            Mark::Comment(comment) => comment.to_string(),
+            Mark::Insertion(suggestion) => suggestion.to_string("insertion"),
+            Mark::Deletion(suggestion) => suggestion.to_string("deletion"),
+            Mark::Custom(custom_mark) => custom_mark.to_string(),
         }
     }
 
     /// eg: "fore_color(0, 0, 0, 1) || back_color(0, 0, 0, 1)"
     pub fn color_mark_from_str(mark: &str) -> Result<Mark, StepError> {
-        let color = mark.split("(").last().ok_or(StepError(format!("Invalid Mark: {}", mark)))?;
-        let color = color.split(")").next().ok_or(StepError(format!("Invalid Mark: {}", mark)))?;
-        let color_as_str = color.split(",").collect::<Vec<&str>>();
-        let mut i = 0;
-        let mut color =  vec![];
-        for c in color_as_str {
-            let c = c.trim();
-            if i != 3 {
-                color.push(c.parse::<u8>().map_err(|_| StepError(format!("Invalid Mark: {}", mark)))?);
-            } else {
-                let c = c.parse::<f32>().map_err(|_| StepError(format!("Invalid Mark: {}", mark)))?;
-                color.push((c * 100.0) as u8)
-            }
-            i += 1;
-        }
-        if color.len() != 4 {
-            return Err(StepError(format!("Color should have 4 numbers. Got: {}", mark)))
-        }
-        let color = Color::from(color[0], color[1], color[2], color[3]);
+        let color = Color::from_str(mark)?;
         if mark.contains("fore_color") {
             Ok(Mark::ForeColor(color))
         } else if mark.contains("back_color") {
@@ -76,6 +78,10 @@ impl Mark {
                 Mark::BackColor(_) => true,
                 _ => false
             },
+            Mark::Custom(custom_mark) => match other_mark {
+                Mark::Custom(other_custom_mark) => custom_mark.exclusivity_group() == other_custom_mark.exclusivity_group(),
+                _ => false
+            },
             _ => self == other_mark
         }
     }
@@ -83,6 +89,80 @@ impl Mark {
     pub fn to_json(&self) -> Result<Value, StepError> {
         return Ok(json!(self.to_string()))
     }
+
+    pub fn is_comment(&self) -> bool {
+        return match self {
+            Mark::Comment(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn is_suggestion(&self) -> bool {
+        return match self {
+            Mark::Insertion(_) | Mark::Deletion(_) => true,
+            _ => false
+        }
+    }
+}
+
+/// Anchors a comment thread to the marked text.
+/// Comments with different ids are different marks, so overlapping comments stay in separate inline blocks
+#[derive(Debug, PartialEq, Clone)]
+pub struct CommentMark {
+    pub id: String,
+    pub resolved: bool
+}
+
+impl CommentMark {
+    /// eg: "comment(id)" || "comment(id, resolved)"
+    pub fn from_str(mark: &str) -> Result<Self, StepError> {
+        let inner = mark.strip_prefix("comment(").and_then(|mark| mark.strip_suffix(")"))
+            .ok_or(StepError(format!("Invalid Mark: {}", mark)))?;
+        let mut parts = inner.split(",").map(|part| part.trim());
+        let id = match parts.next() {
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => return Err(StepError(format!("Comment mark should have an id. Got: {}", mark)))
+        };
+        let resolved = match parts.next() {
+            Some("resolved") => true,
+            None => false,
+            Some(_) => return Err(StepError(format!("Invalid Mark: {}", mark)))
+        };
+        return Ok(Self { id, resolved })
+    }
+
+    pub fn to_string(&self) -> String {
+        return match self.resolved {
+            true => format!("comment({}, resolved)", self.id),
+            false => format!("comment({})", self.id)
+        }
+    }
+}
+
+/// Marks text as suggested (inserted or deleted) while in suggestion mode.
+/// The inserted & deleted text of a single edit share the same id, so they are accepted / rejected together
+#[derive(Debug, PartialEq, Clone)]
+pub struct SuggestionMark {
+    pub id: String,
+    pub author: String
+}
+
+impl SuggestionMark {
+    /// eg: "insertion(id, author)" || "deletion(id, author)"
+    pub fn from_str(name: &str, mark: &str) -> Result<Self, StepError> {
+        let inner = mark.strip_prefix(name).and_then(|mark| mark.strip_prefix("("))
+            .and_then(|mark| mark.strip_suffix(")"))
+            .ok_or(StepError(format!("Invalid Mark: {}", mark)))?;
+        let parts: Vec<&str> = inner.split(",").map(|part| part.trim()).collect();
+        return match parts[..] {
+            [id, author] if !id.is_empty() && !author.is_empty() => Ok(Self { id: id.to_string(), author: author.to_string() }),
+            _ => Err(StepError(format!("Suggestion mark should have an id & author. Got: {}", mark)))
+        }
+    }
+
+    pub fn to_string(&self, name: &str) -> String {
+        return format!("{}({}, {})", name, self.id, self.author)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -93,7 +173,150 @@ impl Color {
         Self(r, g, b, a)
     }
 
+    /// eg: "(0, 0, 0, 1)", the alpha is stored as a percentage
+    pub fn from_str(color: &str) -> Result<Self, StepError> {
+        let inner = color.split("(").last().ok_or(StepError(format!("Invalid Color: {}", color)))?;
+        let inner = inner.split(")").next().ok_or(StepError(format!("Invalid Color: {}", color)))?;
+        let mut i = 0;
+        let mut values = vec![];
+        for c in inner.split(",") {
+            let c = c.trim();
+            if i != 3 {
+                values.push(c.parse::<u8>().map_err(|_| StepError(format!("Invalid Color: {}", color)))?);
+            } else {
+                let c = c.parse::<f32>().map_err(|_| StepError(format!("Invalid Color: {}", color)))?;
+                values.push((c * 100.0) as u8)
+            }
+            i += 1;
+        }
+        if values.len() != 4 {
+            return Err(StepError(format!("Color should have 4 numbers. Got: {}", color)))
+        }
+        return Ok(Color::from(values[0], values[1], values[2], values[3]))
+    }
+
     pub fn to_string(&self) -> String {
         format!("({}, {}, {}, {})", self.0, self.1, self.2, self.3 as f32 / 100.0)
     }
-}
\ No newline at end of file
+}
+
+/// A mark an application adds without changing Mark.
+/// Register it with register_custom_mark_type, it is then parsed from "name" or "name(value)"
+/// & works with the mark steps like the built in marks
+pub trait CustomMarkType {
+    /// eg: "highlight"
+    fn name(&self) -> String;
+    /// Whether the mark has a value, eg: "highlight(yellow)"
+    fn has_value(&self) -> bool {
+        return false
+    }
+    /// Adding a mark removes any marks in the same group (like colours replace each other).
+    /// Defaults to the name, so a mark with a value replaces the same mark with another value
+    fn exclusivity_group(&self) -> String {
+        return self.name()
+    }
+    /// Parses the value between the brackets, errors if it isn't valid
+    fn value_from_str(&self, value: &str) -> Result<String, StepError> {
+        return Ok(value.to_string())
+    }
+    /// Written between the brackets
+    fn value_to_string(&self, value: &str) -> String {
+        return value.to_string()
+    }
+}
+
+/// Names Mark::from_str & FormatBarEvent::from_js_obj match before looking for a custom mark type
+const BUILT_IN_MARK_NAMES: [&str; 8] = ["bold", "italic", "underline", "strikethrough", "comment", "insertion", "deletion", "turn_into"];
+
+/// Replaces any mark type already registered with the same name.
+/// Errors if the name would be parsed as a built in mark (or format bar event) instead
+pub fn register_custom_mark_type(mark_type: Rc<dyn CustomMarkType>) -> Result<(), StepError> {
+    let name = mark_type.name();
+    if BUILT_IN_MARK_NAMES.contains(&name.as_str()) || name.contains("fore_color") || name.contains("back_color") {
+        return Err(StepError(format!("Mark: {} is already a built in mark", name)))
+    }
+    CUSTOM_MARK_TYPES.with(|mark_types| mark_types.borrow_mut().insert(name, mark_type));
+    return Ok(())
+}
+
+pub fn get_custom_mark_type(name: &str) -> Option<Rc<dyn CustomMarkType>> {
+    return CUSTOM_MARK_TYPES.with(|mark_types| mark_types.borrow().get(name).cloned())
+}
+
+/// A custom mark type declared by the frontend
+/// eg: { name: "highlight", has_value: true, exclusivity_group: "highlight" }
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeclaredMarkType {
+    pub name: String,
+    pub has_value: bool,
+    pub exclusivity_group: Option<String>
+}
+
+impl DeclaredMarkType {
+    pub fn from_js_obj(obj: &JsValue) -> Result<Self, StepError> {
+        return Ok(Self {
+            name: get_js_field_as_string(obj, "name")?,
+            has_value: get_js_field(obj, "has_value")?.as_bool().unwrap_or(false),
+            exclusivity_group: get_js_field(obj, "exclusivity_group")?.as_string()
+        })
+    }
+}
+
+impl CustomMarkType for DeclaredMarkType {
+    fn name(&self) -> String {
+        return self.name.clone()
+    }
+    fn has_value(&self) -> bool {
+        return self.has_value
+    }
+    fn exclusivity_group(&self) -> String {
+        return self.exclusivity_group.clone().unwrap_or(self.name.clone())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CustomMark {
+    pub name: String,
+    pub value: Option<String>
+}
+
+impl CustomMark {
+    /// eg: "highlight(yellow)" -> "highlight"
+    pub fn name_from_str(mark: &str) -> &str {
+        return mark.split("(").next().unwrap_or(mark).trim()
+    }
+
+    /// eg: "highlight(yellow)" || "spoiler"
+    pub fn from_str(mark: &str) -> Result<Self, StepError> {
+        let name = Self::name_from_str(mark);
+        let mark_type = get_custom_mark_type(name).ok_or(StepError(format!("Invalid Mark: {}", mark)))?;
+        let value = match mark[name.len()..].trim() {
+            "" => None,
+            value => Some(value.strip_prefix("(").and_then(|value| value.strip_suffix(")"))
+                .ok_or(StepError(format!("Invalid Mark: {}", mark)))?)
+        };
+        let value = match (value, mark_type.has_value()) {
+            (Some(value), true) => Some(mark_type.value_from_str(value.trim())?),
+            (None, false) => None,
+            (Some(_), false) => return Err(StepError(format!("Mark: {} does not have a value. Got: {}", name, mark))),
+            (None, true) => return Err(StepError(format!("Mark: {} should have a value. Got: {}", name, mark)))
+        };
+        return Ok(Self { name: name.to_string(), value })
+    }
+
+    pub fn to_string(&self) -> String {
+        let mark_type = get_custom_mark_type(&self.name);
+        return match (&self.value, mark_type) {
+            (Some(value), Some(mark_type)) => format!("{}({})", self.name, mark_type.value_to_string(value)),
+            (Some(value), None) => format!("{}({})", self.name, value),
+            (None, _) => self.name.clone()
+        }
+    }
+
+    pub fn exclusivity_group(&self) -> String {
+        return match get_custom_mark_type(&self.name) {
+            Some(mark_type) => mark_type.exclusivity_group(),
+            None => self.name.clone()
+        }
+    }
+}
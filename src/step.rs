@@ -4,8 +4,8 @@ use std::str::FromStr;
 use serde_json::{Value, json};
 use wasm_bindgen::JsValue;
 
-use crate::{steps_generator::{selection::{SubSelection}, event::{DropBlockEvent, ReplaceWithChildrenEvent}, StepError},
-mark::Mark, blocks::{standard_blocks::StandardBlockType},
+use crate::{steps_generator::{selection::{SubSelection}, event::{DropBlockEvent, ReplaceWithChildrenEvent, ResizeLayoutColumnsEvent}, StepError},
+mark::{Mark, SuggestionMark}, blocks::{standard_blocks::StandardBlockType},
 utilities::Tree, backend_interface::{get_json_field_as_string, get_json_field_as_int, get_json_field_as_bool}};
 
 
@@ -20,13 +20,25 @@ pub enum Step {
     AddBlock(AddBlockStep),
     TurnInto(TurnInto),
     ToggleCompleted(String), //block id
+    ToggleCollapsed(String), //block id
     Copy(SubSelection, SubSelection),
     Paste(PasteStep), // TODO: NEEDS TO STORE WHAT WAS PASTED INSIDE STEP
     DropBlock(DropBlockEvent),
     DeleteBlock(String), //ID
     Duplicate(DuplicateStep),
     ReplaceWithChildren(ReplaceWithChildrenEvent),
-    AddParagraphAtBottom(AddParagraphAtBottomStep) // (Root block id)
+    AddParagraphAtBottom(AddParagraphAtBottomStep), // (Root block id)
+    SetListStart(SetListStartStep),
+    MoveBlocks(MoveBlocksStep),
+    ResizeLayoutColumns(ResizeLayoutColumnsEvent),
+    MoveBlocksToPage(MoveBlocksToPageStep),
+    AddPage(AddPageStep),
+    AddTableOfContents(AddTableOfContentsStep),
+    ResolveComment(ResolveCommentStep),
+    DeleteComment(DeleteCommentStep),
+    Suggest(SuggestStep),
+    AcceptSuggestion(ResolveSuggestionStep),
+    RejectSuggestion(ResolveSuggestionStep)
     //ReplaceAroundStep
 }
 
@@ -39,13 +51,14 @@ impl Step {
         return Ok(match _type {
             "AddBlock" => Step::AddBlock(AddBlockStep::from_json(json)?),
             "AddMarkStep" => Step::AddMarkStep(MarkStep::from_json(json)?),
-            "RemoveMarkStep" => Step::AddMarkStep(MarkStep::from_json(json)?),
+            "RemoveMarkStep" => Step::RemoveMarkStep(MarkStep::from_json(json)?),
             "ReplaceStep" => Step::ReplaceStep(ReplaceStep::from_json(json)?),
             "SplitStep" => Step::SplitStep(SplitStep::from_json(json)?),
             "TurnToChild" => Step::TurnToChild(TurnToChild::from_json(json)?),
             "TurnToParent" => Step::TurnToParent(TurnToParent::from_json(json)?),
             "TurnInto" => Step::TurnInto(TurnInto::from_json(json)?),
             "ToggleCompleted" => Step::ToggleCompleted(get_json_field_as_string(&json, "block_id")?),
+            "ToggleCollapsed" => Step::ToggleCollapsed(get_json_field_as_string(&json, "block_id")?),
             "Copy" => unreachable!(), // copy should be ignored everywhere except when applied on frontend
             "Paste" => unimplemented!(), // need to add
             "DropBlock" => Step::DropBlock(DropBlockEvent::from_json(json)?),
@@ -53,13 +66,23 @@ impl Step {
             "Duplicate" => Step::Duplicate(DuplicateStep::from_json(json)?),
             "ReplaceWithChildren" => Step::ReplaceWithChildren(ReplaceWithChildrenEvent::from_json(json)?),
             "AddParagraphAtBottom" => Step::AddParagraphAtBottom(AddParagraphAtBottomStep::from_json(json)?),
+            "SetListStart" => Step::SetListStart(SetListStartStep::from_json(json)?),
+            "MoveBlocks" => Step::MoveBlocks(MoveBlocksStep::from_json(json)?),
+            "ResizeLayoutColumns" => Step::ResizeLayoutColumns(ResizeLayoutColumnsEvent::from_json(json)?),
+            "MoveBlocksToPage" => Step::MoveBlocksToPage(MoveBlocksToPageStep::from_json(json)?),
+            "AddPage" => Step::AddPage(AddPageStep::from_json(json)?),
+            "AddTableOfContents" => Step::AddTableOfContents(AddTableOfContentsStep::from_json(json)?),
+            "ResolveComment" => Step::ResolveComment(ResolveCommentStep::from_json(json)?),
+            "DeleteComment" => Step::DeleteComment(DeleteCommentStep::from_json(json)?),
+            "Suggest" => Step::Suggest(SuggestStep::from_json(json)?),
+            "AcceptSuggestion" => Step::AcceptSuggestion(ResolveSuggestionStep::from_json(json)?),
+            "RejectSuggestion" => Step::RejectSuggestion(ResolveSuggestionStep::from_json(json)?),
             _type => Err(StepError(format!("_type: {:?}, is not a valid step type!", _type)))?
         })
     }
 
-    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
-        let obj = js_sys::Object::new();
-
+    /// (_type, data) -> the same shape actualise_mirror_step takes
+    pub fn to_json(self) -> Result<(String, String), StepError> {
         let _type = match &self {
             Self::AddBlock(_) => "AddBlock",
             Self::AddMarkStep(_) => "AddMarkStep",
@@ -70,13 +93,25 @@ impl Step {
             Self::TurnToParent(_) => "TurnToParent",
             Self::TurnInto(_) => "TurnInto",
             Self::ToggleCompleted(_) => "ToggleCompleted",
+            Self::ToggleCollapsed(_) => "ToggleCollapsed",
             Self::Copy(_, _) => "Copy",
             Self::Paste(_) => "Paste",
             Self::DropBlock(_) => "DropBlock",
             Self::DeleteBlock(_) => "DeleteBlock",
             Self::Duplicate(_) => "Duplicate",
             Self::ReplaceWithChildren(_) => "ReplaceWithChildren",
-            Self::AddParagraphAtBottom(_) => "AddParagraphAtBottom"
+            Self::AddParagraphAtBottom(_) => "AddParagraphAtBottom",
+            Self::SetListStart(_) => "SetListStart",
+            Self::MoveBlocks(_) => "MoveBlocks",
+            Self::ResizeLayoutColumns(_) => "ResizeLayoutColumns",
+            Self::MoveBlocksToPage(_) => "MoveBlocksToPage",
+            Self::AddPage(_) => "AddPage",
+            Self::AddTableOfContents(_) => "AddTableOfContents",
+            Self::ResolveComment(_) => "ResolveComment",
+            Self::DeleteComment(_) => "DeleteComment",
+            Self::Suggest(_) => "Suggest",
+            Self::AcceptSuggestion(_) => "AcceptSuggestion",
+            Self::RejectSuggestion(_) => "RejectSuggestion"
         };
 
         let data = match self {
@@ -89,17 +124,34 @@ impl Step {
             Self::TurnToParent(step) => step.to_json()?,
             Self::TurnInto(step) => step.to_json()?,
             Self::ToggleCompleted(block_id) => json!({ "block_id": block_id }),
+            Self::ToggleCollapsed(block_id) => json!({ "block_id": block_id }),
             Self::Copy(from, to) => json!({ "from": from.to_json()?, "to": to.to_json()? }),
             Self::Paste(paste_step) => unimplemented!(),// json!({ "from": from.to_json()?, "to": to.to_json()? }),
             Self::DropBlock(event) => event.to_json()?,
             Self::DeleteBlock(block_id) => json!({ "block_id": block_id }),
             Self::Duplicate(step) => step.to_json()?,
             Self::ReplaceWithChildren(event) => event.to_json()?,
-            Self::AddParagraphAtBottom(step) => step.to_json()?
+            Self::AddParagraphAtBottom(step) => step.to_json()?,
+            Self::SetListStart(step) => step.to_json()?,
+            Self::MoveBlocks(step) => step.to_json()?,
+            Self::ResizeLayoutColumns(event) => event.to_json()?,
+            Self::MoveBlocksToPage(step) => step.to_json()?,
+            Self::AddPage(step) => step.to_json()?,
+            Self::AddTableOfContents(step) => step.to_json()?,
+            Self::ResolveComment(step) => step.to_json()?,
+            Self::DeleteComment(step) => step.to_json()?,
+            Self::Suggest(step) => step.to_json()?,
+            Self::AcceptSuggestion(step) => step.to_json()?,
+            Self::RejectSuggestion(step) => step.to_json()?
         };
+        return Ok((_type.to_string(), data.to_string()))
+    }
 
+    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let (_type, data) = self.to_json()?;
         js_sys::Reflect::set(&obj, &JsValue::from_str("_type"), &JsValue::from(_type)).unwrap();
-        js_sys::Reflect::set(&obj, &JsValue::from_str("data"), &JsValue::from(data.to_string())).unwrap();
+        js_sys::Reflect::set(&obj, &JsValue::from_str("data"), &JsValue::from(data)).unwrap();
         return Ok(JsValue::from(obj))
     }
 }
@@ -382,4 +434,293 @@ impl AddParagraphAtBottomStep {
         })
     }
 
-}
\ No newline at end of file
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetListStartStep {
+    pub block_id: String,
+    pub start: Option<usize> // None -> continue numbering from the previous list item
+}
+
+impl SetListStartStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "block_id": self.block_id,
+            "start": self.start
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        let start = match json.get("start") {
+            Some(start) if !start.is_null() => Some(get_json_field_as_int(&json, "start")? as usize),
+            _ => None
+        };
+        return Ok(Self {
+            block_id: get_json_field_as_string(&json, "block_id")?,
+            start
+        })
+    }
+}
+
+/// Moves sibling blocks (with their children) to child_offset of the new parent.
+/// child_offset is the index after the blocks have been removed from their current parent
+#[derive(Debug, PartialEq, Clone)]
+pub struct MoveBlocksStep {
+    pub block_ids: Vec<String>,
+    pub new_parent_id: String,
+    pub child_offset: usize,
+    pub from: SubSelection,
+    pub to: SubSelection
+}
+
+impl MoveBlocksStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "block_ids": self.block_ids,
+            "new_parent_id": self.new_parent_id,
+            "child_offset": self.child_offset,
+            "from": self.from.to_json()?,
+            "to": self.to.to_json()?
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        let block_ids = json.get("block_ids")
+            .ok_or(StepError(format!("Block does not have block_ids field: {}", json)))?
+            .as_array().ok_or(StepError("block_ids field is not an array".to_string()))?
+            .iter()
+            .map(|id| id.as_str().map(|id| id.to_string()).ok_or(StepError("block_ids should only contain strings".to_string())))
+            .collect::<Result<Vec<String>, StepError>>()?;
+        let from = SubSelection::from_json(json.get("from")
+            .ok_or(StepError(format!("Block does not have from field: {}", json)))?.clone())?;
+        let to = SubSelection::from_json(json.get("to")
+            .ok_or(StepError(format!("Block does not have to field: {}", json)))?.clone())?;
+        return Ok(Self {
+            block_ids,
+            new_parent_id: get_json_field_as_string(&json, "new_parent_id")?,
+            child_offset: get_json_field_as_int(&json, "child_offset")? as usize,
+            from,
+            to
+        })
+    }
+}
+
+/// Moves block subtrees out of their document into the root block of another page
+#[derive(Debug, PartialEq, Clone)]
+pub struct MoveBlocksToPageStep {
+    pub block_ids: Vec<String>,
+    pub page_id: String, // root block id of the page the blocks are moved into
+    pub child_offset: Option<usize> // None -> add to the end of the page
+}
+
+impl MoveBlocksToPageStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "block_ids": self.block_ids,
+            "page_id": self.page_id,
+            "child_offset": self.child_offset
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        let block_ids = json.get("block_ids")
+            .ok_or(StepError(format!("Block does not have block_ids field: {}", json)))?
+            .as_array().ok_or(StepError("block_ids field is not an array".to_string()))?
+            .iter()
+            .map(|id| id.as_str().map(|id| id.to_string()).ok_or(StepError("block_ids should only contain strings".to_string())))
+            .collect::<Result<Vec<String>, StepError>>()?;
+        let child_offset = match json.get("child_offset") {
+            Some(Value::Null) | None => None,
+            Some(_) => Some(get_json_field_as_int(&json, "child_offset")? as usize)
+        };
+        return Ok(Self {
+            block_ids,
+            page_id: get_json_field_as_string(&json, "page_id")?,
+            child_offset
+        })
+    }
+}
+
+/// Creates the root block of a new sub-page
+#[derive(Debug, PartialEq, Clone)]
+pub struct AddPageStep {
+    pub root_block_id: String,
+    pub initial_paragraph: Option<(String, String)> // (standard block id, inline block id)
+}
+
+impl AddPageStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        let (paragraph_block_id, paragraph_inline_block_id) = match self.initial_paragraph {
+            Some((std_block_id, inline_block_id)) => (Some(std_block_id), Some(inline_block_id)),
+            None => (None, None)
+        };
+        return Ok(json!({
+            "root_block_id": self.root_block_id,
+            "paragraph_block_id": paragraph_block_id,
+            "paragraph_inline_block_id": paragraph_inline_block_id
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        let initial_paragraph = match json.get("paragraph_block_id") {
+            Some(Value::Null) | None => None,
+            Some(_) => Some((
+                get_json_field_as_string(&json, "paragraph_block_id")?,
+                get_json_field_as_string(&json, "paragraph_inline_block_id")?
+            ))
+        };
+        return Ok(Self {
+            root_block_id: get_json_field_as_string(&json, "root_block_id")?,
+            initial_paragraph
+        })
+    }
+}
+
+/// Inserts a table of contents block (it's entries are generated when the step is actualised)
+#[derive(Debug, PartialEq, Clone)]
+pub struct AddTableOfContentsStep {
+    pub block_id: String, // parent
+    pub new_block_id: String,
+    pub child_offset: usize
+}
+
+impl AddTableOfContentsStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "block_id": self.block_id,
+            "new_block_id": self.new_block_id,
+            "child_offset": self.child_offset
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        return Ok(Self {
+            block_id: get_json_field_as_string(&json, "block_id")?,
+            new_block_id: get_json_field_as_string(&json, "new_block_id")?,
+            child_offset: get_json_field_as_int(&json, "child_offset")? as usize
+        })
+    }
+}
+
+/// Resolves (or reopens) every comment mark with the comment id under the root
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolveCommentStep {
+    pub root_block_id: String,
+    pub comment_id: String,
+    pub resolved: bool
+}
+
+impl ResolveCommentStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "root_block_id": self.root_block_id,
+            "comment_id": self.comment_id,
+            "resolved": self.resolved
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        return Ok(Self {
+            root_block_id: get_json_field_as_string(&json, "root_block_id")?,
+            comment_id: get_json_field_as_string(&json, "comment_id")?,
+            resolved: get_json_field_as_bool(&json, "resolved")?
+        })
+    }
+}
+
+/// Removes every comment mark with the comment id under the root
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeleteCommentStep {
+    pub root_block_id: String,
+    pub comment_id: String
+}
+
+impl DeleteCommentStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "root_block_id": self.root_block_id,
+            "comment_id": self.comment_id
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        return Ok(Self {
+            root_block_id: get_json_field_as_string(&json, "root_block_id")?,
+            comment_id: get_json_field_as_string(&json, "comment_id")?
+        })
+    }
+}
+
+/// Suggests replacing the text between from & to with the text, without removing any text:
+/// -> the selected text is marked as deleted
+/// -> the text is inserted after the selection & marked as inserted
+#[derive(Debug, PartialEq, Clone)]
+pub struct SuggestStep {
+    pub block_id: String,
+    pub from: SubSelection,
+    pub to: SubSelection,
+    pub text: String,
+    pub suggestion: SuggestionMark,
+    pub from_new_inline_id: String,
+    pub to_new_inline_id: String,
+    pub new_inline_id: String // for the inserted text
+}
+
+impl SuggestStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "block_id": self.block_id,
+            "from": self.from.to_json()?,
+            "to": self.to.to_json()?,
+            "text": self.text,
+            "suggestion_id": self.suggestion.id,
+            "author": self.suggestion.author,
+            "from_new_inline_id": self.from_new_inline_id,
+            "to_new_inline_id": self.to_new_inline_id,
+            "new_inline_id": self.new_inline_id
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        let from = SubSelection::from_json(json.get("from")
+            .ok_or(StepError(format!("Block does not have from field: {}", json)))?.clone())?;
+        let to = SubSelection::from_json(json.get("to")
+            .ok_or(StepError(format!("Block does not have to field: {}", json)))?.clone())?;
+        return Ok(Self {
+            block_id: get_json_field_as_string(&json, "block_id")?,
+            from,
+            to,
+            text: get_json_field_as_string(&json, "text")?,
+            suggestion: SuggestionMark {
+                id: get_json_field_as_string(&json, "suggestion_id")?,
+                author: get_json_field_as_string(&json, "author")?
+            },
+            from_new_inline_id: get_json_field_as_string(&json, "from_new_inline_id")?,
+            to_new_inline_id: get_json_field_as_string(&json, "to_new_inline_id")?,
+            new_inline_id: get_json_field_as_string(&json, "new_inline_id")?
+        })
+    }
+}
+
+/// Accepts or rejects every insertion & deletion with the suggestion id under the root
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResolveSuggestionStep {
+    pub root_block_id: String,
+    pub suggestion_id: String
+}
+
+impl ResolveSuggestionStep {
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "root_block_id": self.root_block_id,
+            "suggestion_id": self.suggestion_id
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        return Ok(Self {
+            root_block_id: get_json_field_as_string(&json, "root_block_id")?,
+            suggestion_id: get_json_field_as_string(&json, "suggestion_id")?
+        })
+    }
+}
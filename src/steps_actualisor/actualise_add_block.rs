@@ -1,4 +1,4 @@
-use crate::{step::AddBlockStep, blocks::{BlockMap, inline_blocks::InlineBlock, standard_blocks::{StandardBlock, content_block::ContentBlock}, Block}, steps_generator::{StepError, selection::{Selection, SubSelection}}, new_ids::NewIds};
+use crate::{step::AddBlockStep, blocks::{BlockMap, inline_blocks::InlineBlock, standard_blocks::{StandardBlock, content_block::ContentBlock}, Block, nesting_schema::with_nesting_schema}, steps_generator::{StepError, selection::{Selection, SubSelection}}, new_ids::NewIds};
 
 use super::UpdatedState;
 
@@ -21,6 +21,9 @@ pub fn actualise_add_block(
     mut blocks_to_update: Vec<String>
 ) -> Result<UpdatedState, StepError> {
     let mut parent = block_map.get_block(&add_block_step.block_id)?;
+    if !with_nesting_schema(|schema| schema.allows_child(&parent, &add_block_step.block_type)) {
+        return Err(StepError(format!("Block type: {} cannot be added to block: {}", add_block_step.block_type._type_as_string(), parent.id())))
+    }
     let new_std_block_id = new_ids.get_id()?;
     let new_inline_block_id = new_ids.get_id()?;
 
@@ -0,0 +1,44 @@
+use crate::{step::AddPageStep, blocks::{BlockMap, Block, RootBlock, inline_blocks::{InlineBlock, InlineBlockType, text_block::{TextBlock, StringUTF16}}, standard_blocks::StandardBlock},
+steps_generator::StepError};
+
+use super::UpdatedState;
+
+/// Adds the root block of a new sub-page to the block map
+/// -> with an empty paragraph if the step has an initial paragraph
+pub fn actualise_add_page(
+    add_page_step: AddPageStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    if block_map.contains(&add_page_step.root_block_id) {
+        return Err(StepError(format!("Block with id {} already exists", add_page_step.root_block_id)))
+    }
+
+    let mut children = vec![];
+    if let Some((paragraph_block_id, inline_block_id)) = add_page_step.initial_paragraph {
+        let inline_block = InlineBlock {
+            _id: inline_block_id.clone(),
+            content: InlineBlockType::TextBlock(TextBlock(StringUTF16::new())),
+            marks: vec![],
+            parent: paragraph_block_id.clone()
+        };
+        let paragraph = StandardBlock::new_paragraph_block(
+            paragraph_block_id.clone(),
+            vec![inline_block_id],
+            vec![],
+            vec![],
+            add_page_step.root_block_id.clone()
+        );
+        block_map.update_blocks(vec![Block::InlineBlock(inline_block), Block::StandardBlock(paragraph)], &mut blocks_to_update)?;
+        children.push(paragraph_block_id);
+    }
+    block_map.update_block(Block::Root(RootBlock { _id: add_page_step.root_block_id, children }), &mut blocks_to_update)?;
+
+    return Ok(UpdatedState {
+        block_map,
+        selection: None,
+        blocks_to_update,
+        blocks_to_remove: vec![],
+        copy: None
+    })
+}
@@ -0,0 +1,38 @@
+use crate::{step::AddTableOfContentsStep, blocks::{BlockMap, Block, standard_blocks::{StandardBlock, StandardBlockType, table_of_contents_block::TableOfContentsBlock}},
+    steps_generator::StepError, utilities::{find_root_block_id, outline::get_headings}};
+
+use super::UpdatedState;
+
+/// Inserts a table of contents block listing the headings under the parent's root
+pub fn actualise_add_table_of_contents(
+    step: AddTableOfContentsStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    let mut parent = block_map.get_block(&step.block_id)?;
+    if let Block::StandardBlock(parent) = &parent {
+        if !parent.content.can_have_children() {
+            return Err(StepError(format!("Block: {} cannot have children", parent.id())))
+        }
+    }
+    let root_block_id = find_root_block_id(&step.block_id, &block_map)
+        .ok_or(StepError(format!("Could not find the root of block: {}", step.block_id)))?;
+
+    let table_of_contents = StandardBlock {
+        _id: step.new_block_id.clone(),
+        content: StandardBlockType::TableOfContents(TableOfContentsBlock { entries: get_headings(&root_block_id, &block_map)? }),
+        children: vec![],
+        parent: parent.id(),
+        marks: vec![]
+    };
+    parent.insert_child(step.new_block_id, step.child_offset)?;
+    block_map.update_blocks(vec![Block::StandardBlock(table_of_contents), parent], &mut blocks_to_update)?;
+
+    return Ok(UpdatedState {
+        block_map,
+        selection: None,
+        blocks_to_update,
+        blocks_to_remove: vec![],
+        copy: None
+    })
+}
@@ -1,4 +1,4 @@
-use crate::{steps_generator::StepError, blocks::{BlockMap, Block}, step::TurnToChild};
+use crate::{steps_generator::StepError, blocks::{BlockMap, Block, nesting_schema::with_nesting_schema}, step::TurnToChild};
 
 use super::UpdatedState;
 
@@ -36,6 +36,9 @@ pub fn actualise_child_steps(
     let block_above = new_child_block.get_previous(&block_map)?;
     match block_above {
         Some(mut block_above) => {
+            if !with_nesting_schema(|schema| schema.allows_child(&Block::StandardBlock(block_above.clone()), &new_child_block.content)) {
+                return Err(StepError(format!("Block: {} cannot be a child of block: {}", new_child_block.id(), block_above.id())))
+            }
             block_above.children.push(new_child_block.id());
             new_child_block.parent = block_above.id();
             block_map.update_blocks(vec![
@@ -0,0 +1,57 @@
+use crate::{step::{ResolveCommentStep, DeleteCommentStep}, blocks::{BlockMap, Block}, mark::Mark,
+    steps_generator::StepError, utilities::comments::get_inline_blocks_with_comment};
+
+use super::{UpdatedState, clean_block_after_transform};
+
+/// Updates the resolved flag on each of the comment's marks (the marked text is unchanged)
+pub fn actualise_resolve_comment(
+    step: ResolveCommentStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    for mut inline_block in get_inline_blocks_with_comment(&step.root_block_id, &step.comment_id, &block_map)? {
+        for mark in inline_block.marks.iter_mut() {
+            match mark {
+                Mark::Comment(comment) if comment.id == step.comment_id => comment.resolved = step.resolved,
+                _ => {}
+            };
+        }
+        block_map.update_block(Block::InlineBlock(inline_block), &mut blocks_to_update)?;
+    }
+
+    return Ok(UpdatedState {
+        block_map,
+        selection: None,
+        blocks_to_update,
+        blocks_to_remove: vec![],
+        copy: None
+    })
+}
+
+/// Removes the comment's marks & merges inline blocks that only differed by the comment
+pub fn actualise_delete_comment(
+    step: DeleteCommentStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    let mut parent_ids: Vec<String> = vec![];
+    for mut inline_block in get_inline_blocks_with_comment(&step.root_block_id, &step.comment_id, &block_map)? {
+        inline_block.marks.retain(|mark| !matches!(mark, Mark::Comment(comment) if comment.id == step.comment_id));
+        if !parent_ids.contains(&inline_block.parent) {
+            parent_ids.push(inline_block.parent.clone());
+        }
+        block_map.update_block(Block::InlineBlock(inline_block), &mut blocks_to_update)?;
+    }
+    for parent_id in parent_ids {
+        let block = block_map.get_standard_block(&parent_id)?;
+        block_map = clean_block_after_transform(block, block_map, &mut blocks_to_update)?;
+    }
+
+    return Ok(UpdatedState {
+        block_map,
+        selection: None,
+        blocks_to_update,
+        blocks_to_remove: vec![],
+        copy: None
+    })
+}
@@ -1,28 +1,53 @@
 use crate::{steps_generator::{StepError, event::{DropBlockEvent, Side}},
-blocks::{BlockMap, Block, standard_blocks::{StandardBlock, StandardBlockType, layout_block::LayoutBlock}},
+blocks::{BlockMap, Block, standard_blocks::{StandardBlock, StandardBlockType, layout_block::LayoutBlock}, nesting_schema::with_nesting_schema},
 new_ids::NewIds, utilities::update_state_tools};
 
 use super::UpdatedState;
 
-/// -> Remove drag block from current place
-/// -> If top or bottom -> add to parent at insertion point & change drag block's parent to new parent
+/// -> Order the drag blocks by their position in the document (ignoring blocks whose ancestor is also dragged)
+/// -> Remove drag blocks from current place
+/// -> If top or bottom -> add to parent at insertion point & change drag blocks' parent to new parent
+/// -> If left or right -> add all drag blocks to a single new layout column
+/// -> If first or last child -> add to the start or end of the drop block's children
 pub fn actualise_drop_block(
     drop_block_event: DropBlockEvent,
     mut block_map: BlockMap,
     mut blocks_to_update: Vec<String>,
     new_ids: &mut NewIds
 ) -> Result<UpdatedState, StepError> {
-    let mut drag_block = block_map.get_standard_block(&drop_block_event.drag_block_id)?;
-    if drag_block.get_parent(&block_map).is_ok() {
-        remove_drag_block_from_current_place(&mut block_map, &drop_block_event, &mut blocks_to_update)?;
+    let drag_blocks = get_drag_blocks_in_document_order(&drop_block_event, &block_map)?;
+    for drag_block in &drag_blocks {
+        if drag_block.get_parent(&block_map).is_ok() {
+            remove_drag_block_from_current_place(&mut block_map, &drag_block.id(), &mut blocks_to_update)?;
+        }
     }
+    let mut drag_blocks = block_map.ids_to_blocks(&drag_blocks.iter().map(|block| block.id()).collect())?
+        .into_iter()
+        .map(|block| match block {
+            Block::StandardBlock(block) => Ok(block),
+            _ => Err(StepError("Drag blocks should be standard blocks".to_string()))
+        })
+        .collect::<Result<Vec<StandardBlock>, StepError>>()?;
 
     let drop_block = block_map.get_standard_block(&drop_block_event.drop_block_id)?;
     let drop_parent = drop_block.get_parent(&block_map)?;
 
     match drop_block_event.side_dropped {
+        Side::FirstChild | Side::LastChild => {
+            let insertion_index = match drop_block_event.side_dropped {
+                Side::FirstChild => 0,
+                _ => drop_block.children.len()
+            };
+            update_state_tools::splice_children(
+                Block::StandardBlock(drop_block),
+                insertion_index..insertion_index,
+                drag_blocks.iter().map(|block| block.id()).collect(),
+                &mut blocks_to_update,
+                &mut block_map
+            )?;
+        },
         Side::Top | Side::Bottom => {
-            drop_block_top_or_bottom(drag_block, drop_block, drop_parent, drop_block_event, &mut block_map, &mut blocks_to_update)?;
+            drop_blocks_top_or_bottom(&drag_blocks, drop_block, drop_parent, drop_block_event, &mut block_map, &mut blocks_to_update)?;
         },
         Side::Left | Side::Right => {
             if is_layout_block_or_is_inside_layout_block(&drop_block, &drop_parent) {
@@ -31,13 +56,15 @@ pub fn actualise_drop_block(
                 let new_column_layout = StandardBlock::new_layout_block(
                     new_column_id.clone(),
                     false,
-                    vec![drag_block.id()],
+                    drag_blocks.iter().map(|block| block.id()).collect(),
                     horizontal_layout_id.clone()
                 )?;
-                drag_block.parent = new_column_id.clone();
-                block_map.update_blocks(vec![
-                    Block::StandardBlock(drag_block), Block::StandardBlock(new_column_layout)
-                ], &mut blocks_to_update)?;
+                for drag_block in drag_blocks.iter_mut() {
+                    drag_block.parent = new_column_id.clone();
+                }
+                let mut blocks: Vec<Block> = drag_blocks.into_iter().map(|block| Block::StandardBlock(block)).collect();
+                blocks.push(Block::StandardBlock(new_column_layout));
+                block_map.update_blocks(blocks, &mut blocks_to_update)?;
 
                 let new_column_index = get_index_of_new_layout_column(&drop_block, &drop_block_event.side_dropped, &block_map)?;
                 let horizontal_layout_block = block_map.get_block(&horizontal_layout_id)?;
@@ -50,7 +77,7 @@ pub fn actualise_drop_block(
                 )?;
             } else {
                 create_new_horizontal_layout_block(
-                    drag_block,
+                    drag_blocks,
                     drop_block,
                     drop_parent,
                     drop_block_event,
@@ -71,12 +98,98 @@ pub fn actualise_drop_block(
     })
 }
 
+/// Drag blocks can come from different levels of the tree (non-contiguous selection).
+/// Blocks are sorted by their path of indexes from the root so their relative order is kept,
+/// and blocks that are descendants of another drag block are skipped as they move with their ancestor.
+/// Errors if the drop would put a block inside itself or somewhere the nesting schema doesn't allow
+fn get_drag_blocks_in_document_order(drop_block_event: &DropBlockEvent, block_map: &BlockMap) -> Result<Vec<StandardBlock>, StepError> {
+    if drop_block_event.drag_block_ids.len() == 0 {
+        return Err(StepError("Drop block event does not have any drag blocks".to_string()))
+    }
+    let drop_block = block_map.get_standard_block(&drop_block_event.drop_block_id)?;
+    let drops_as_child = drop_block_event.side_dropped == Side::FirstChild || drop_block_event.side_dropped == Side::LastChild;
+    if drops_as_child && !drop_block.content.can_have_children() {
+        return Err(StepError(format!("Block: {} cannot have children", drop_block.id())))
+    }
+    check_drop_is_allowed_by_schema(drop_block_event, &drop_block, block_map)?;
+    let drop_block_ancestors = get_ancestor_ids_incl_self(&drop_block_event.drop_block_id, block_map)?;
+    let mut drag_blocks_with_paths = vec![];
+    for drag_block_id in &drop_block_event.drag_block_ids {
+        if drop_block_ancestors.contains(drag_block_id) {
+            return Err(StepError(format!("Cannot drop block: {} onto itself or one of its descendants", drag_block_id)))
+        }
+        let ancestors = get_ancestor_ids_incl_self(drag_block_id, block_map)?;
+        let ancestor_is_dragged = ancestors[1..].iter().any(|id| drop_block_event.drag_block_ids.contains(id));
+        if !ancestor_is_dragged {
+            drag_blocks_with_paths.push((get_path_from_root(drag_block_id, block_map)?, block_map.get_standard_block(drag_block_id)?));
+        }
+    }
+    drag_blocks_with_paths.sort_by(|(a, _), (b, _)| a.cmp(b));
+    return Ok(drag_blocks_with_paths.into_iter().map(|(_, block)| block).collect())
+}
+
+/// -> first or last child: the drop block has to allow the drag blocks as children
+/// -> top or bottom: the drop block's parent has to allow them
+/// -> left or right: the drag blocks go into a layout column
+///     (when a new horizontal layout is created, the drop block moves into a column too & the layout goes in the drop block's parent)
+fn check_drop_is_allowed_by_schema(drop_block_event: &DropBlockEvent, drop_block: &StandardBlock, block_map: &BlockMap) -> Result<(), StepError> {
+    return with_nesting_schema(|schema| {
+        let drop_parent = drop_block.get_parent(block_map)?;
+        let creates_new_layout = !is_layout_block_or_is_inside_layout_block(drop_block, &drop_parent);
+        if creates_new_layout && (drop_block_event.side_dropped == Side::Left || drop_block_event.side_dropped == Side::Right) {
+            let horizontal_layout = StandardBlockType::Layout(LayoutBlock::with_equal_widths(true, 2));
+            if !schema.allows_in_layout_column(&drop_block.content) || !schema.allows_child(&drop_parent, &horizontal_layout) {
+                return Err(StepError(format!("Block: {} cannot be put in a layout", drop_block.id())))
+            }
+        }
+        for drag_block_id in &drop_block_event.drag_block_ids {
+            let drag_block = block_map.get_standard_block(drag_block_id)?;
+            let is_allowed = match drop_block_event.side_dropped {
+                Side::FirstChild | Side::LastChild => schema.allows_child(&Block::StandardBlock(drop_block.clone()), &drag_block.content),
+                Side::Top | Side::Bottom => schema.allows_child(&drop_parent, &drag_block.content),
+                Side::Left | Side::Right => schema.allows_in_layout_column(&drag_block.content)
+            };
+            if !is_allowed {
+                return Err(StepError(format!("Block: {} cannot be dropped there", drag_block_id)))
+            }
+        }
+        return Ok(())
+    })
+}
+
+/// eg: [1, 0] -> first child of the root's second child
+fn get_path_from_root(block_id: &String, block_map: &BlockMap) -> Result<Vec<usize>, StepError> {
+    let mut path = vec![];
+    let mut block = block_map.get_standard_block(block_id)?;
+    loop {
+        path.insert(0, block.index(block_map)?);
+        match block.get_parent(block_map)? {
+            Block::StandardBlock(parent) => block = parent,
+            _ => return Ok(path)
+        };
+    }
+}
+
+fn get_ancestor_ids_incl_self(block_id: &String, block_map: &BlockMap) -> Result<Vec<String>, StepError> {
+    let mut ids = vec![block_id.clone()];
+    let mut block = block_map.get_standard_block(block_id)?;
+    loop {
+        match block.get_parent(block_map)? {
+            Block::StandardBlock(parent) => {
+                ids.push(parent.id());
+                block = parent;
+            },
+            _ => return Ok(ids)
+        };
+    }
+}
+
 fn remove_drag_block_from_current_place(
     block_map: &mut BlockMap,
-    drop_block_event: &DropBlockEvent,
+    drag_block_id: &String,
     blocks_to_update: &mut Vec<String>
 ) -> Result<(), StepError> {
-    let drag_block = block_map.get_standard_block(&drop_block_event.drag_block_id)?;
+    let drag_block = block_map.get_standard_block(drag_block_id)?;
     let mut drag_parent = drag_block.get_parent(&block_map)?;
     drag_parent = drag_parent.remove_child_from_id(&drag_block._id)?;
     block_map.update_block(drag_parent, blocks_to_update)?;
@@ -101,15 +214,15 @@ fn is_layout_block_or_is_inside_layout_block(
     }
 }
 
-fn drop_block_top_or_bottom(
-    drag_block: StandardBlock,
+fn drop_blocks_top_or_bottom(
+    drag_blocks: &Vec<StandardBlock>,
     drop_block: StandardBlock,
     drop_parent: Block,
     drop_block_event: DropBlockEvent,
     block_map: &mut BlockMap,
     blocks_to_update: &mut Vec<String>
 ) -> Result<(), StepError> {
-    // add dragged block to new position
+    // add dragged blocks to new position
     let mut insertion_index = drop_block.index(&block_map)?;
     if drop_block_event.side_dropped == Side::Bottom {
         insertion_index += 1;
@@ -117,7 +230,7 @@ fn drop_block_top_or_bottom(
     update_state_tools::splice_children(
         drop_parent,
         insertion_index..insertion_index,
-        vec![drag_block.id()],
+        drag_blocks.iter().map(|block| block.id()).collect(),
         blocks_to_update,
         block_map
     )?;
@@ -126,7 +239,7 @@ fn drop_block_top_or_bottom(
 }
 
 fn create_new_horizontal_layout_block(
-    drag_block: StandardBlock,
+    drag_blocks: Vec<StandardBlock>,
     drop_block: StandardBlock,
     drop_parent: Block,
     drop_block_event: DropBlockEvent,
@@ -143,8 +256,8 @@ fn create_new_horizontal_layout_block(
         Side::Left => {
             create_horizontal_layout_children(
                 new_horizontal_layout_id.clone(),
-                drag_block,
-                drop_block,
+                drag_blocks,
+                vec![drop_block],
                 column_id1,
                 column_id2,
                 block_map,
@@ -154,8 +267,8 @@ fn create_new_horizontal_layout_block(
         Side::Right => {
             create_horizontal_layout_children(
                 new_horizontal_layout_id.clone(),
-                drop_block,
-                drag_block,
+                vec![drop_block],
+                drag_blocks,
                 column_id1,
                 column_id2,
                 block_map,
@@ -166,7 +279,7 @@ fn create_new_horizontal_layout_block(
     };
     let new_horizontal_layout_block = StandardBlock {
         _id: new_horizontal_layout_id,
-        content: StandardBlockType::Layout(LayoutBlock { horizontal: true }),
+        content: StandardBlockType::Layout(LayoutBlock::with_equal_widths(true, horizontal_layout_children.len())),
         children: horizontal_layout_children,
         parent: drop_parent.id(),
         marks: vec![]
@@ -187,30 +300,38 @@ fn create_new_horizontal_layout_block(
 
 fn create_horizontal_layout_children(
     horizontal_layout_id: String,
-    mut left_block: StandardBlock,
-    mut right_block: StandardBlock,
+    left_blocks: Vec<StandardBlock>,
+    right_blocks: Vec<StandardBlock>,
     left_column_id: String,
     right_column_id: String,
     block_map: &mut BlockMap,
     blocks_to_update: &mut Vec<String>
 ) -> Result<Vec<String>, StepError> {
-    left_block.parent = left_column_id.clone();
-    right_block.parent = right_column_id.clone();
-    let left_column = StandardBlock::new_layout_block(left_column_id.clone(), false, vec![left_block.id()], horizontal_layout_id.clone())?;
-    let right_column = StandardBlock::new_layout_block(right_column_id.clone(), false, vec![right_block.id()], horizontal_layout_id.clone())?;
-    block_map.update_blocks(vec![
-        Block::StandardBlock(left_block),
-        Block::StandardBlock(right_block),
-        Block::StandardBlock(left_column),
-        Block::StandardBlock(right_column),
-    ], blocks_to_update)?;
+    let left_column = StandardBlock::new_layout_block(
+        left_column_id.clone(), false, left_blocks.iter().map(|block| block.id()).collect(), horizontal_layout_id.clone()
+    )?;
+    let right_column = StandardBlock::new_layout_block(
+        right_column_id.clone(), false, right_blocks.iter().map(|block| block.id()).collect(), horizontal_layout_id.clone()
+    )?;
+    let mut blocks = vec![];
+    for mut block in left_blocks {
+        block.parent = left_column_id.clone();
+        blocks.push(Block::StandardBlock(block));
+    }
+    for mut block in right_blocks {
+        block.parent = right_column_id.clone();
+        blocks.push(Block::StandardBlock(block));
+    }
+    blocks.push(Block::StandardBlock(left_column));
+    blocks.push(Block::StandardBlock(right_column));
+    block_map.update_blocks(blocks, blocks_to_update)?;
     return Ok(vec![left_column_id, right_column_id])
 }
 
 fn get_horizontal_layout_id(drop_block: &StandardBlock, block_map: &BlockMap) -> Result<String, StepError> {
     match &drop_block.content {
-        StandardBlockType::Layout(LayoutBlock { horizontal: true }) => return Ok(drop_block.id()),
-        StandardBlockType::Layout(LayoutBlock { horizontal: false }) => return Ok(drop_block.parent.clone()),
+        StandardBlockType::Layout(LayoutBlock { horizontal: true, .. }) => return Ok(drop_block.id()),
+        StandardBlockType::Layout(LayoutBlock { horizontal: false, .. }) => return Ok(drop_block.parent.clone()),
         _ => {
             let parent = block_map.get_standard_block(&drop_block.parent)?;
             return Ok(parent.parent.clone())
@@ -221,14 +342,14 @@ fn get_horizontal_layout_id(drop_block: &StandardBlock, block_map: &BlockMap) ->
 /// 3 cases: horizontal layout, vertical layout (column), not a layout block (inside a layout block)
 fn get_index_of_new_layout_column(drop_block: &StandardBlock, side_dropped: &Side, block_map: &BlockMap) -> Result<usize, StepError> {
     return match &drop_block.content {
-        StandardBlockType::Layout(LayoutBlock { horizontal: true }) => {
+        StandardBlockType::Layout(LayoutBlock { horizontal: true, .. }) => {
             if *side_dropped == Side::Left {
                 Ok(0)
             } else {
                 Ok(drop_block.children.len())
             }
         },
-        StandardBlockType::Layout(LayoutBlock { horizontal: false }) => {
+        StandardBlockType::Layout(LayoutBlock { horizontal: false, .. }) => {
             if *side_dropped == Side::Left {
                 drop_block.index(&block_map)
             } else {
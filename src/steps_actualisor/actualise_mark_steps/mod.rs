@@ -1,5 +1,5 @@
 
-use crate::{step::{MarkStep}, blocks::{BlockMap, Block, inline_blocks::{InlineBlock}}, steps_generator::{StepError, selection::{Selection, SubSelection}}, new_ids::NewIds};
+use crate::{step::{MarkStep}, blocks::{BlockMap, Block, inline_blocks::{InlineBlock}}, steps_generator::{StepError, selection::{Selection, SubSelection}}};
 
 use self::actualise_across_std_blocks::actualise_mark_step_on_standard_blocks;
 
@@ -91,7 +91,7 @@ fn actualise_mark_step_on_inline_blocks(
     mut blocks_to_update: Vec<String>
 ) -> Result<UpdatedState, StepError> {
     if mark_step.from.block_id == mark_step.to.block_id {
-        let (before_block, middle_block, after_block) = create_before_middle_after_blocks_with_new_text_and_mark(from_block, new_ids, mark_step, add_mark)?;
+        let (before_block, middle_block, after_block) = create_before_middle_after_blocks_with_new_text_and_mark(from_block, mark_step, add_mark)?;
 
         let parent_block = block_map.get_standard_block(&before_block.parent)?;
         let original_block_index = parent_block.index_of(&before_block._id)?;
@@ -170,8 +170,7 @@ fn actualise_mark_step_on_inline_blocks(
 
 //This function appears to be used to create three new inline blocks based on a given input block.
 //It takes an InlineBlock called from_block,
-//a mutable reference to a NewIds struct which seems to be used to generate new unique IDs for blocks as needed,
-//a MarkStep struct which represents the mark to be applied and the range of blocks to which it should be applied,
+//a MarkStep struct (whose from_new_inline_id & to_new_inline_id are used for the middle & after blocks) which represents the mark to be applied and the range of blocks to which it should be applied,
 //and a boolean add_mark which indicates whether the mark should be added or removed.
 
 // The function first retrieves the text of the input block,
@@ -186,7 +185,6 @@ fn actualise_mark_step_on_inline_blocks(
 
 pub fn create_before_middle_after_blocks_with_new_text_and_mark(
     from_block: InlineBlock,
-    new_ids: &mut NewIds,
     mark_step: MarkStep,
     add_mark: bool
 ) -> Result<(InlineBlock, InlineBlock, InlineBlock), StepError> {
@@ -194,8 +192,8 @@ pub fn create_before_middle_after_blocks_with_new_text_and_mark(
     let (before_text, middle_text, after_text) = text.split_before_middle_after(mark_step.from.offset, mark_step.to.offset);
 
     let before_block = from_block.clone().update_text(before_text)?;
-    let mut middle_block = from_block.clone().to_new_block(new_ids)?.update_text(middle_text)?;
-    let after_block = from_block.to_new_block(new_ids)?.update_text(after_text)?;
+    let mut middle_block = InlineBlock { _id: mark_step.from_new_inline_id.clone(), ..from_block.clone() }.update_text(middle_text)?;
+    let after_block = InlineBlock { _id: mark_step.to_new_inline_id.clone(), ..from_block }.update_text(after_text)?;
 
     middle_block = middle_block.apply_mark(mark_step.mark, add_mark);
     return Ok((before_block, middle_block, after_block))
@@ -0,0 +1,43 @@
+use crate::{blocks::BlockMap, steps_generator::{StepError, selection::Selection}, step::MoveBlocksStep, utilities::update_state_tools};
+
+use super::UpdatedState;
+
+/// -> Remove the blocks from their current parent
+/// -> Insert them into the new parent at the child offset
+/// -> Selection stays on the moved text (block ids don't change)
+pub fn actualise_move_blocks(
+    move_blocks_step: MoveBlocksStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    for block_id in &move_blocks_step.block_ids {
+        let block = block_map.get_standard_block(block_id)?;
+        let parent = block.get_parent(&block_map)?;
+        let index = parent.index_of_child(block_id)?;
+        update_state_tools::splice_children(parent, index..index + 1, vec![], &mut blocks_to_update, &mut block_map)?;
+    }
+
+    let new_parent = block_map.get_block(&move_blocks_step.new_parent_id)?;
+    if move_blocks_step.child_offset > new_parent.children()?.len() {
+        return Err(StepError(format!(
+            "Cannot move blocks to offset {} of block with {} children",
+            move_blocks_step.child_offset,
+            new_parent.children()?.len()
+        )))
+    }
+    update_state_tools::splice_children(
+        new_parent,
+        move_blocks_step.child_offset..move_blocks_step.child_offset,
+        move_blocks_step.block_ids,
+        &mut blocks_to_update,
+        &mut block_map
+    )?;
+
+    return Ok(UpdatedState {
+        block_map,
+        selection: Some(Selection { anchor: move_blocks_step.from, head: move_blocks_step.to }),
+        blocks_to_update,
+        blocks_to_remove: vec![],
+        copy: None
+    })
+}
@@ -0,0 +1,167 @@
+use crate::{blocks::{BlockMap, Block}, step::MoveBlocksToPageStep, steps_generator::StepError, utilities::update_state_tools};
+
+use super::UpdatedState;
+
+/// Moves block subtrees out of their document into the root block of another page
+/// -> if the page's root block is in the same block map -> the blocks are moved into it
+/// -> otherwise only the source document is updated: the moved blocks are reported in blocks_to_remove
+/// and the backend applies the step to both documents with actualise_move_blocks_to_page_across_block_maps
+pub fn actualise_move_blocks_to_page(
+    move_step: MoveBlocksToPageStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    let (top_block_ids, moved_blocks) = remove_blocks_from_source(&move_step, &mut block_map, &mut blocks_to_update)?;
+
+    let mut blocks_to_remove = vec![];
+    if block_map.contains(&move_step.page_id) {
+        add_blocks_to_page(&move_step, top_block_ids, moved_blocks, &mut block_map, &mut blocks_to_update)?;
+    } else {
+        for block in moved_blocks {
+            if let BlockMap::Rust(_) = block_map {
+                block_map.remove_block(&block.id())?;
+            }
+            blocks_to_remove.push(block.id());
+        }
+        blocks_to_update.retain(|id| !blocks_to_remove.contains(id));
+    }
+
+    return Ok(UpdatedState {
+        block_map,
+        selection: None,
+        blocks_to_update,
+        blocks_to_remove,
+        copy: None
+    })
+}
+
+/// Applies the step to the document the blocks are moved from & the document of the page they are moved into
+/// -> returns (source updated state, target updated state)
+pub fn actualise_move_blocks_to_page_across_block_maps(
+    move_step: MoveBlocksToPageStep,
+    mut source_block_map: BlockMap,
+    mut target_block_map: BlockMap
+) -> Result<(UpdatedState, UpdatedState), StepError> {
+    let mut source_blocks_to_update = vec![];
+    let (top_block_ids, moved_blocks) = remove_blocks_from_source(&move_step, &mut source_block_map, &mut source_blocks_to_update)?;
+
+    let mut source_blocks_to_remove = vec![];
+    for block in &moved_blocks {
+        source_block_map.remove_block(&block.id())?;
+        source_blocks_to_remove.push(block.id());
+    }
+    source_blocks_to_update.retain(|id| !source_blocks_to_remove.contains(id));
+
+    let mut target_blocks_to_update = vec![];
+    add_blocks_to_page(&move_step, top_block_ids, moved_blocks, &mut target_block_map, &mut target_blocks_to_update)?;
+
+    return Ok((
+        UpdatedState {
+            block_map: source_block_map,
+            selection: None,
+            blocks_to_update: source_blocks_to_update,
+            blocks_to_remove: source_blocks_to_remove,
+            copy: None
+        },
+        UpdatedState {
+            block_map: target_block_map,
+            selection: None,
+            blocks_to_update: target_blocks_to_update,
+            blocks_to_remove: vec![],
+            copy: None
+        }
+    ))
+}
+
+/// Detaches the top blocks from their parents & returns them, plus every block in their subtrees
+/// (including inline blocks). Blocks that are already inside another moved block are moved with it.
+fn remove_blocks_from_source(
+    move_step: &MoveBlocksToPageStep,
+    block_map: &mut BlockMap,
+    blocks_to_update: &mut Vec<String>
+) -> Result<(Vec<String>, Vec<Block>), StepError> {
+    let mut top_block_ids = vec![];
+    for block_id in &move_step.block_ids {
+        if !has_ancestor_in(block_id, &move_step.block_ids, block_map)? {
+            top_block_ids.push(block_id.clone());
+        }
+    }
+
+    let mut moved_blocks = vec![];
+    for block_id in &top_block_ids {
+        let block = block_map.get_standard_block(block_id)?;
+        if &block.parent == &move_step.page_id {
+            return Err(StepError(format!("Block: {} is already in page: {}", block_id, move_step.page_id)))
+        }
+        let parent = block.get_parent(block_map)?;
+        let index = parent.index_of_child(block_id)?;
+        update_state_tools::splice_children(parent, index..index + 1, vec![], blocks_to_update, block_map)?;
+        collect_subtree(block_id, block_map, &mut moved_blocks)?;
+    }
+    return Ok((top_block_ids, moved_blocks))
+}
+
+fn add_blocks_to_page(
+    move_step: &MoveBlocksToPageStep,
+    top_block_ids: Vec<String>,
+    moved_blocks: Vec<Block>,
+    block_map: &mut BlockMap,
+    blocks_to_update: &mut Vec<String>
+) -> Result<(), StepError> {
+    let page_root = match block_map.get_block(&move_step.page_id)? {
+        Block::Root(root_block) => root_block,
+        _ => return Err(StepError(format!("Page id: {} should be the id of a root block", move_step.page_id)))
+    };
+    let child_offset = move_step.child_offset.unwrap_or(page_root.children.len());
+    if child_offset > page_root.children.len() {
+        return Err(StepError(format!(
+            "Cannot move blocks to offset {} of page with {} children",
+            child_offset,
+            page_root.children.len()
+        )))
+    }
+
+    for block in moved_blocks {
+        let block = match block {
+            Block::StandardBlock(mut block) if top_block_ids.contains(&block.id()) => {
+                block.parent = move_step.page_id.clone();
+                Block::StandardBlock(block)
+            },
+            block => block
+        };
+        block_map.update_block(block, blocks_to_update)?;
+    }
+    update_state_tools::splice_children(
+        Block::Root(page_root),
+        child_offset..child_offset,
+        top_block_ids,
+        blocks_to_update,
+        block_map
+    )?;
+    return Ok(())
+}
+
+fn collect_subtree(block_id: &str, block_map: &BlockMap, blocks: &mut Vec<Block>) -> Result<(), StepError> {
+    let block = block_map.get_standard_block(block_id)?;
+    if block.has_content() {
+        for inline_block in block.get_inline_blocks(block_map)? {
+            blocks.push(Block::InlineBlock(inline_block));
+        }
+    }
+    for child_id in &block.children {
+        collect_subtree(child_id, block_map, blocks)?;
+    }
+    blocks.push(Block::StandardBlock(block));
+    return Ok(())
+}
+
+fn has_ancestor_in(block_id: &str, ids: &Vec<String>, block_map: &BlockMap) -> Result<bool, StepError> {
+    let mut current = block_map.get_standard_block(block_id)?;
+    while !current.parent_is_root(block_map) {
+        if ids.contains(&current.parent) {
+            return Ok(true)
+        }
+        current = block_map.get_standard_block(&current.parent)?;
+    }
+    return Ok(false)
+}
@@ -1,4 +1,4 @@
-use crate::{blocks::{BlockMap, Block}, step::TurnToParent, steps_generator::StepError};
+use crate::{blocks::{BlockMap, Block, nesting_schema::with_nesting_schema}, step::TurnToParent, steps_generator::StepError};
 
 use super::UpdatedState;
 
@@ -34,12 +34,19 @@ pub fn actualise_parent_steps(
     let mut current_block = block_map.get_standard_block(&turn_to_parent_step.block_id)?;
 
     let mut previous_parent = block_map.get_standard_block(&current_block.parent)?;
+    let grandparent = block_map.get_block(&previous_parent.parent)?;
+    if !with_nesting_schema(|schema| schema.allows_child(&grandparent, &current_block.content)) {
+        return Err(StepError(format!("Block: {} cannot be a child of block: {}", current_block.id(), previous_parent.parent)))
+    }
     let current_block_index = current_block.index(&block_map)?;
     let first_half = &previous_parent.children[..current_block_index];
     let second_half = &previous_parent.children[current_block_index + 1..];
     current_block.children = vec![current_block.children, second_half.to_vec()].concat();
     for id in second_half {
         let mut block = block_map.get_standard_block(id)?;
+        if !with_nesting_schema(|schema| schema.allows_child(&Block::StandardBlock(current_block.clone()), &block.content)) {
+            return Err(StepError(format!("Block: {} cannot be a child of block: {}", block.id(), current_block.id())))
+        }
         block.parent = current_block.id();
         block_map.update_block(Block::StandardBlock(block), &mut blocks_to_update)?;
     }
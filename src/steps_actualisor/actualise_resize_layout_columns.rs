@@ -0,0 +1,28 @@
+use crate::{blocks::{BlockMap, standard_blocks::StandardBlockType, Block}, steps_generator::{StepError, event::ResizeLayoutColumnsEvent}};
+
+use super::UpdatedState;
+
+pub fn actualise_resize_layout_columns(
+    resize_event: ResizeLayoutColumnsEvent,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    let mut block = block_map.get_standard_block(&resize_event.layout_block_id)?;
+    return match block.content {
+        StandardBlockType::Layout(mut layout_block) => {
+            // resets widths that don't match the columns (eg: layouts created before widths existed)
+            layout_block.splice_widths(0..0, 0, block.children.len());
+            layout_block.resize_columns(resize_event.column_index, resize_event.ratio)?;
+            block.content = StandardBlockType::Layout(layout_block);
+            block_map.update_block(Block::StandardBlock(block), &mut blocks_to_update)?;
+            Ok(UpdatedState {
+                block_map,
+                selection: None,
+                blocks_to_update,
+                blocks_to_remove: vec![],
+                copy: None
+            })
+        },
+        t => Err(StepError(format!("Cannot resize columns of any block other than a layout block. Got block: {:#?}", t)))
+    }
+}
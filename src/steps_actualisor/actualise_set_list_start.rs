@@ -0,0 +1,28 @@
+use crate::{blocks::{BlockMap, standard_blocks::StandardBlockType, Block}, steps_generator::StepError, step::SetListStartStep};
+
+use super::UpdatedState;
+
+/// Sets (or clears) the number a numbered list restarts from.
+/// Numbering itself is never stored, it's computed by utilities::list_numbering.
+pub fn actualise_set_list_start(
+    set_list_start_step: SetListStartStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    let mut block = block_map.get_standard_block(&set_list_start_step.block_id)?;
+    return match block.content {
+        StandardBlockType::NumberedList(mut list_block) => {
+            list_block.start = set_list_start_step.start;
+            block.content = StandardBlockType::NumberedList(list_block);
+            block_map.update_block(Block::StandardBlock(block), &mut blocks_to_update)?;
+            Ok(UpdatedState {
+                block_map,
+                selection: None,
+                blocks_to_update,
+                blocks_to_remove: vec![],
+                copy: None
+            })
+        },
+        t => Err(StepError(format!("Cannot set list start on any block other than a numbered list. Got block: {:#?}", t)))
+    }
+}
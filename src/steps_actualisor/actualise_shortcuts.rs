@@ -1,4 +1,4 @@
-use crate::{utilities::{BlocksBetween, get_blocks_between, BlockStructure, update_state_tools}, custom_copy::CustomCopy, steps_generator::{StepError, selection::{SubSelection, Selection}}, blocks::{BlockMap, standard_blocks::{StandardBlock, }}, new_ids::NewIds, step::PasteStep};
+use crate::{utilities::{BlocksBetween, get_blocks_between, BlockStructure, update_state_tools}, custom_copy::CustomCopy, steps_generator::{StepError, selection::{SubSelection, Selection}}, blocks::{BlockMap, Block, standard_blocks::{StandardBlock, }, nesting_schema::with_nesting_schema}, new_ids::NewIds, step::PasteStep};
 
 use super::{UpdatedState, clean_block_after_transform};
 
@@ -34,6 +34,7 @@ pub fn actualise_copy(
 
 /// -> first blocks inline blocks get inserted at "from" selection standard block -> similar to inline case
 /// -> any children of first block are inserted at start of insertion block's children
+///     (or underneath it if the nesting schema doesn't allow them as children)
 /// -> Rest of the blocks get inserted into the "from" std block's parents underneath it
 pub fn actualise_paste(
     copy: CustomCopy,
@@ -67,20 +68,38 @@ pub fn actualise_paste(
             only_one_std_block,
             &mut selection
         )?;
-        update_state_tools::splice_children_on_std_block(
-            &mut insertion_std_block,
-            0..0,
-            copy_tree.top_blocks[0].children.clone(),
-            &mut blocks_to_update,
-            &mut block_map
-        )?;
+        let first_block_children = block_map.ids_to_blocks(&copy_tree.top_blocks[0].children)?;
+        let insertion_block_allows_children = with_nesting_schema(|schema| first_block_children.iter().all(|child| match child {
+            Block::StandardBlock(child) => schema.allows_child(&Block::StandardBlock(insertion_std_block.clone()), &child.content),
+            _ => false
+        }));
+        let mut sibling_ids = vec![];
+        if insertion_block_allows_children {
+            update_state_tools::splice_children_on_std_block(
+                &mut insertion_std_block,
+                0..0,
+                copy_tree.top_blocks[0].children.clone(),
+                &mut blocks_to_update,
+                &mut block_map
+            )?;
+        } else {
+            sibling_ids = copy_tree.top_blocks[0].children.clone();
+        }
         let parent = insertion_std_block.get_parent(&block_map)?;
         copy_tree.top_blocks.remove(0);
+        sibling_ids.extend(copy_tree.top_blocks.iter().map(|b| b._id.clone()));
+        for sibling in block_map.ids_to_blocks(&sibling_ids)? {
+            if let Block::StandardBlock(sibling) = sibling {
+                if !with_nesting_schema(|schema| schema.allows_child(&parent, &sibling.content)) {
+                    return Err(StepError(format!("Block: {} cannot be pasted into block: {}", sibling.id(), parent.id())))
+                }
+            }
+        }
 
         update_state_tools::splice_children(
             parent,
             insertion_std_block.index(&block_map)? + 1..insertion_std_block.index(&block_map)? + 1,
-            copy_tree.top_blocks.iter().map(|b| b._id.clone()).collect(),
+            sibling_ids,
             &mut blocks_to_update,
             &mut block_map
         )?;
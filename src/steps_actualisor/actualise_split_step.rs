@@ -1,9 +1,10 @@
-use crate::{step::SplitStep, blocks::{BlockMap, standard_blocks::{StandardBlockType, content_block::ContentBlock, list_block::ListBlock}, Block}, steps_generator::{StepError, selection::{Selection, SubSelection}}, new_ids::NewIds};
+use crate::{step::SplitStep, blocks::{BlockMap, standard_blocks::{StandardBlockType, content_block::ContentBlock, list_block::ListBlock, toggle_block::ToggleBlock, custom_block::{CustomBlock, EnterBehaviour}}, Block}, steps_generator::{StepError, selection::{Selection, SubSelection}}, new_ids::NewIds};
 
 use super::{UpdatedState, clean_block_after_transform};
 
 /// If current standard block is Paragraph, H1, H2, or H3 -> new block should be a paragraph
 /// If current standard block is some type of List block -> new block should be same type of list block
+/// If current standard block is a custom block -> depends on its EnterBehaviour
 
 /// -> split inline block at point of subselection -> creating a new inline block
 /// -> split parent's inline blocks after the index of the subselection index block
@@ -89,10 +90,15 @@ fn get_new_enter_block_type(block_type: &StandardBlockType) -> Result<StandardBl
     return match block_type {
         StandardBlockType::Paragraph(_) | StandardBlockType::H1(_) | StandardBlockType::H2(_) | StandardBlockType::H3(_)
             => Ok(StandardBlockType::Paragraph(ContentBlock { inline_blocks: vec![] })),
-        StandardBlockType::TodoList(_) => Ok(StandardBlockType::TodoList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false })),
-        StandardBlockType::DotPointList(_) => Ok(StandardBlockType::DotPointList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false })),
-        StandardBlockType::NumberedList(_) => Ok(StandardBlockType::NumberedList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false })),
-        StandardBlockType::ArrowList(_) => Ok(StandardBlockType::ArrowList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false })),
+        StandardBlockType::TodoList(_) => Ok(StandardBlockType::TodoList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false, start: None })),
+        StandardBlockType::DotPointList(_) => Ok(StandardBlockType::DotPointList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false, start: None })),
+        StandardBlockType::NumberedList(_) => Ok(StandardBlockType::NumberedList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false, start: None })),
+        StandardBlockType::ArrowList(_) => Ok(StandardBlockType::ArrowList(ListBlock { content: ContentBlock { inline_blocks: vec![] }, completed: false, start: None })),
+        StandardBlockType::ToggleList(_) => Ok(StandardBlockType::ToggleList(ToggleBlock { content: ContentBlock { inline_blocks: vec![] }, collapsed: false })),
+        StandardBlockType::Custom(custom_block) if custom_block.content.is_some() => match custom_block.block_type()?.enter_behaviour() {
+            EnterBehaviour::NewParagraph => Ok(StandardBlockType::Paragraph(ContentBlock { inline_blocks: vec![] })),
+            EnterBehaviour::NewBlockOfSameType => Ok(StandardBlockType::Custom(CustomBlock::new(&custom_block._type)?))
+        },
         block_type => return Err(StepError(format!("Cannot enter on block type {:?}", block_type)))
     }
 }
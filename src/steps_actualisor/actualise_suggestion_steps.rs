@@ -0,0 +1,134 @@
+use crate::{step::{SuggestStep, ResolveSuggestionStep, MarkStep}, blocks::{BlockMap, Block, inline_blocks::{InlineBlock, InlineBlockType, text_block::{TextBlock, StringUTF16}},
+    standard_blocks::content_block::ContentBlock}, mark::{Mark, SuggestionMark},
+    steps_generator::{StepError, selection::{Selection, SubSelection}}, utilities::suggestions::{get_inline_blocks_with_suggestion, is_suggestion_with_id}};
+
+use super::{UpdatedState, clean_block_after_transform, actualise_mark_steps::actualise_mark_step};
+
+/// -> mark the selected text as deleted
+/// -> insert the text after the selection, marked as inserted
+/// -> caret after the inserted text (or at the start of the deleted text)
+pub fn actualise_suggest(
+    step: SuggestStep,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    // raw offsets don't change when inline blocks are split or merged
+    let from_raw = step.from.to_raw_selection(&block_map)?;
+    let to_raw = step.to.to_raw_selection(&block_map)?;
+    if step.from != step.to {
+        let mark_step = MarkStep {
+            block_id: step.block_id,
+            from: step.from,
+            to: step.to,
+            mark: Mark::Deletion(step.suggestion.clone()),
+            from_new_inline_id: step.from_new_inline_id,
+            to_new_inline_id: step.to_new_inline_id.clone()
+        };
+        let updated_state = actualise_mark_step(mark_step, block_map, true, blocks_to_update)?;
+        block_map = updated_state.block_map;
+        blocks_to_update = updated_state.blocks_to_update;
+    }
+
+    let mut caret = from_raw;
+    if step.text.len() > 0 {
+        let text = StringUTF16::from_str(&step.text);
+        caret = SubSelection::from(to_raw.block_id.clone(), to_raw.offset + text.len(), None);
+        block_map = insert_suggested_text(&to_raw, text, step.suggestion, step.new_inline_id, step.to_new_inline_id, block_map, &mut blocks_to_update)?;
+    }
+
+    let caret = caret.real_selection_from_raw(&block_map)?;
+    return Ok(UpdatedState {
+        block_map,
+        selection: Some(Selection { anchor: caret.clone(), head: caret }),
+        blocks_to_update,
+        blocks_to_remove: vec![],
+        copy: None
+    })
+}
+
+/// Inserts a new inline block at the raw offset, splitting the inline block there if needed.
+/// It keeps the formatting of the text before it, but not its suggestion marks
+fn insert_suggested_text(
+    at: &SubSelection,
+    text: StringUTF16,
+    suggestion: SuggestionMark,
+    new_inline_id: String,
+    split_inline_id: String,
+    mut block_map: BlockMap,
+    blocks_to_update: &mut Vec<String>
+) -> Result<BlockMap, StepError> {
+    let std_block = block_map.get_standard_block(&at.block_id)?;
+    let mut inline_blocks = std_block.content_block()?.inline_blocks.clone();
+    let mut marks = vec![];
+    let mut index = inline_blocks.len();
+    let mut start = 0;
+    for i in 0..inline_blocks.len() {
+        let inline_block = block_map.get_inline_block(&inline_blocks[i])?;
+        let len = inline_block.text()?.len();
+        if at.offset <= start + len {
+            marks = inline_block.marks.clone();
+            if at.offset == start + len {
+                index = i + 1;
+            } else if at.offset == start {
+                index = i;
+            } else {
+                let (first_half, second_half) = inline_block.split(at.offset - start, split_inline_id)?;
+                inline_blocks.insert(i + 1, second_half.id());
+                block_map.update_blocks(vec![Block::InlineBlock(first_half), Block::InlineBlock(second_half)], blocks_to_update)?;
+                index = i + 1;
+            }
+            break;
+        }
+        start += len;
+    }
+
+    marks.retain(|mark| !mark.is_suggestion());
+    marks.push(Mark::Insertion(suggestion));
+    let new_inline_block = InlineBlock {
+        _id: new_inline_id,
+        content: InlineBlockType::TextBlock(TextBlock(text)),
+        marks,
+        parent: std_block.id()
+    };
+    inline_blocks.insert(index, new_inline_block.id());
+    let std_block = std_block.update_block_content(ContentBlock { inline_blocks })?;
+    block_map.update_blocks(vec![Block::InlineBlock(new_inline_block), Block::StandardBlock(std_block.clone())], blocks_to_update)?;
+    return clean_block_after_transform(std_block, block_map, blocks_to_update)
+}
+
+/// Accepting keeps the inserted text & removes the deleted text, rejecting does the opposite.
+/// The text that is kept loses the suggestion's marks
+pub fn actualise_resolve_suggestion(
+    step: ResolveSuggestionStep,
+    accept: bool,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    let mut parent_ids: Vec<String> = vec![];
+    for mut inline_block in get_inline_blocks_with_suggestion(&step.root_block_id, &step.suggestion_id, &block_map)? {
+        let is_insertion = inline_block.marks.iter()
+            .any(|mark| matches!(mark, Mark::Insertion(_)) && is_suggestion_with_id(mark, &step.suggestion_id));
+        if is_insertion == accept {
+            inline_block.marks.retain(|mark| !is_suggestion_with_id(mark, &step.suggestion_id));
+        } else {
+            inline_block = inline_block.update_text(StringUTF16::new())?;
+            inline_block.marks = vec![];
+        }
+        if !parent_ids.contains(&inline_block.parent) {
+            parent_ids.push(inline_block.parent.clone());
+        }
+        block_map.update_block(Block::InlineBlock(inline_block), &mut blocks_to_update)?;
+    }
+    for parent_id in parent_ids {
+        let block = block_map.get_standard_block(&parent_id)?;
+        block_map = clean_block_after_transform(block, block_map, &mut blocks_to_update)?;
+    }
+
+    return Ok(UpdatedState {
+        block_map,
+        selection: None,
+        blocks_to_update,
+        blocks_to_remove: vec![],
+        copy: None
+    })
+}
@@ -0,0 +1,28 @@
+use crate::{blocks::{BlockMap, standard_blocks::StandardBlockType, Block}, steps_generator::StepError};
+
+use super::UpdatedState;
+
+/// Flips the collapsed field of a toggle list block.
+/// The children are left untouched, they are only hidden from navigation & the frontend.
+pub fn actualise_toggle_collapsed(
+    _id: String,
+    mut block_map: BlockMap,
+    mut blocks_to_update: Vec<String>
+) -> Result<UpdatedState, StepError> {
+    let mut block = block_map.get_standard_block(&_id)?;
+    return match block.content {
+        StandardBlockType::ToggleList(mut toggle_block) => {
+            toggle_block.collapsed = !toggle_block.collapsed;
+            block.content = StandardBlockType::ToggleList(toggle_block);
+            block_map.update_block(Block::StandardBlock(block), &mut blocks_to_update)?;
+            Ok(UpdatedState {
+                block_map,
+                selection: None,
+                blocks_to_update,
+                blocks_to_remove: vec![],
+                copy: None
+            })
+        },
+        t => Err(StepError(format!("Cannot toggle collapsed on any block other than a toggle list. Got block: {:#?}", t)))
+    }
+}
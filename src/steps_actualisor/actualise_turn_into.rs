@@ -1,4 +1,4 @@
-use crate::{step::TurnInto, blocks::{BlockMap, Block, standard_blocks::StandardBlock}, steps_generator::{StepError, selection::{Selection, SubSelection}}};
+use crate::{step::TurnInto, blocks::{BlockMap, Block, standard_blocks::StandardBlock, nesting_schema::with_nesting_schema}, steps_generator::{StepError, selection::{Selection, SubSelection}}};
 
 use super::UpdatedState;
 
@@ -32,6 +32,13 @@ pub fn actualise_turn_into_step(
         Ok(content_block) => turn_into_step.new_block_type.update_block_content(content_block.clone())?,
         Err(_) => turn_into_step.new_block_type.clone()
     };
+    let parent = block_map.get_block(&block.parent)?;
+    let is_allowed = with_nesting_schema(|schema|
+        (block.children.is_empty() || schema.can_have_children(&new_block_content)) && schema.allows_child(&parent, &new_block_content)
+    );
+    if !is_allowed {
+        return Err(StepError(format!("Block: {} cannot be turned into: {}", block.id(), new_block_content._type_as_string())))
+    }
     let block = StandardBlock {
         _id: block._id,
         content: new_block_content,
@@ -15,6 +15,7 @@ use self::actualise_duplicate::actualise_duplicate;
 use self::actualise_replace_with_children::actualise_replace_with_children;
 use self::actualise_shortcuts::{actualise_copy, actualise_paste};
 use self::actualise_toggle_completed::actualise_toggle_completed;
+use self::actualise_toggle_collapsed::actualise_toggle_collapsed;
 use self::actualise_turn_into::actualise_turn_into_step;
 use self::actualise_add_block::actualise_add_block;
 use self::actualise_child_steps::actualise_child_steps;
@@ -22,6 +23,16 @@ use self::actualise_replace_steps::actualise_replace_step;
 use self::actualise_split_step::actualise_split_step;
 use crate::steps_actualisor::actualise_parent_steps::actualise_parent_steps;
 use crate::steps_actualisor::actualise_add_paragraph_at_bottom::actualise_add_paragraph_at_bottom;
+use crate::steps_actualisor::actualise_set_list_start::actualise_set_list_start;
+use crate::steps_actualisor::actualise_move_blocks::actualise_move_blocks;
+use crate::steps_actualisor::actualise_resize_layout_columns::actualise_resize_layout_columns;
+use crate::steps_actualisor::normalise_layouts::normalise_layouts;
+use crate::steps_actualisor::actualise_move_blocks_to_page::actualise_move_blocks_to_page;
+use crate::steps_actualisor::actualise_add_page::actualise_add_page;
+use crate::steps_actualisor::actualise_add_table_of_contents::actualise_add_table_of_contents;
+use crate::steps_actualisor::actualise_comment_steps::{actualise_resolve_comment, actualise_delete_comment};
+use crate::steps_actualisor::actualise_suggestion_steps::{actualise_suggest, actualise_resolve_suggestion};
+use crate::steps_actualisor::refresh_tables_of_contents::{refresh_tables_of_contents, headings_may_have_changed};
 
 pub mod actualise_replace_steps;
 pub mod actualise_mark_steps;
@@ -31,12 +42,23 @@ pub mod actualise_parent_steps;
 pub mod actualise_add_block;
 pub mod actualise_turn_into;
 pub mod actualise_toggle_completed;
+pub mod actualise_toggle_collapsed;
 pub mod actualise_shortcuts;
 pub mod actualise_drop_block;
 pub mod actualise_delete_block;
 pub mod actualise_duplicate;
 pub mod actualise_replace_with_children;
 pub mod actualise_add_paragraph_at_bottom;
+pub mod actualise_set_list_start;
+pub mod actualise_move_blocks;
+pub mod actualise_resize_layout_columns;
+pub mod normalise_layouts;
+pub mod actualise_move_blocks_to_page;
+pub mod actualise_add_page;
+pub mod actualise_add_table_of_contents;
+pub mod refresh_tables_of_contents;
+pub mod actualise_comment_steps;
+pub mod actualise_suggestion_steps;
 
 pub struct UpdatedState {
     pub block_map: BlockMap,
@@ -78,6 +100,12 @@ impl UpdatedState {
 // Step::ToggleCompleted(_id): calls actualise_toggle_completed to toggle the "completed" state of a to-do list block.
 // Step::Copy(from, to) and Step::Paste(from, to): currently not implemented.
 
+// If any of the steps could have taken blocks out of a layout,
+//normalise_layouts removes the empty / single column layouts that were left behind.
+
+// If the steps could have changed the page's headings,
+//refresh_tables_of_contents regenerates the table of contents blocks of the updated pages.
+
 // Finally, the function calls clean_block_after_transform to clean up the block map
 //after all the updates have been performed.
 //This function merges inline blocks with
@@ -86,7 +114,22 @@ impl UpdatedState {
 // - updates the block map with the cleaned blocks
 pub fn actualise_steps(steps: Vec<Step>, block_map: BlockMap, new_ids: &mut NewIds, mut copy: CustomCopy) -> Result<UpdatedState, StepError> {
     let mut updated_state = UpdatedState::new(block_map);
+    let mut should_normalise_layouts = false;
+    let mut should_refresh_tables_of_contents = false;
     for step in steps {
+        // steps that can take blocks out of a layout (drag & drop, delete, cut & backspace)
+        should_normalise_layouts |= match step {
+            Step::DropBlock(_) | Step::DeleteBlock(_) | Step::MoveBlocks(_) | Step::MoveBlocksToPage(_) | Step::ReplaceStep(_) | Step::TurnToParent(_) => true,
+            _ => false
+        };
+        // steps that only edit text / block settings are checked for edited headings after all steps are actualised
+        should_refresh_tables_of_contents |= match step {
+            Step::ReplaceStep(_) | Step::SplitStep(_) | Step::AddMarkStep(_) | Step::RemoveMarkStep(_) |
+            Step::ToggleCompleted(_) | Step::ToggleCollapsed(_) | Step::SetListStart(_) | Step::ResizeLayoutColumns(_) |
+            Step::Copy(_, _) | Step::ResolveComment(_) | Step::DeleteComment(_) |
+            Step::Suggest(_) | Step::AcceptSuggestion(_) | Step::RejectSuggestion(_) => false,
+            _ => true
+        };
         updated_state = match step {
             Step::ReplaceStep(replace_step) => actualise_replace_step(replace_step, updated_state.block_map, updated_state.selection, updated_state.blocks_to_update, new_ids)?,
             Step::SplitStep(split_step) => actualise_split_step(split_step, updated_state.block_map, updated_state.blocks_to_update)?,
@@ -97,6 +140,7 @@ pub fn actualise_steps(steps: Vec<Step>, block_map: BlockMap, new_ids: &mut NewI
             Step::AddBlock(add_block_step) => actualise_add_block(add_block_step, updated_state.block_map, updated_state.blocks_to_update)?,
             Step::TurnInto(turn_into_step) => actualise_turn_into_step(turn_into_step, updated_state.block_map, updated_state.blocks_to_update)?,
             Step::ToggleCompleted(_id) => actualise_toggle_completed(_id, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::ToggleCollapsed(_id) => actualise_toggle_collapsed(_id, updated_state.block_map, updated_state.blocks_to_update)?,
             Step::Copy(from, to) => {
                 updated_state = actualise_copy(copy, from, to, updated_state.block_map, new_ids, updated_state.blocks_to_update)?;
                 copy = updated_state.copy.unwrap();
@@ -109,8 +153,25 @@ pub fn actualise_steps(steps: Vec<Step>, block_map: BlockMap, new_ids: &mut NewI
             Step::Duplicate(block_id) => actualise_duplicate(block_id, updated_state.block_map, updated_state.blocks_to_update)?,
             Step::ReplaceWithChildren(replace_with_children_event) => actualise_replace_with_children(replace_with_children_event, updated_state.block_map, updated_state.blocks_to_update)?,
             Step::AddParagraphAtBottom(root_block_id) => actualise_add_paragraph_at_bottom(root_block_id, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::SetListStart(set_list_start_step) => actualise_set_list_start(set_list_start_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::MoveBlocks(move_blocks_step) => actualise_move_blocks(move_blocks_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::ResizeLayoutColumns(resize_event) => actualise_resize_layout_columns(resize_event, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::MoveBlocksToPage(move_step) => actualise_move_blocks_to_page(move_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::AddPage(add_page_step) => actualise_add_page(add_page_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::AddTableOfContents(add_table_of_contents_step) => actualise_add_table_of_contents(add_table_of_contents_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::ResolveComment(resolve_comment_step) => actualise_resolve_comment(resolve_comment_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::DeleteComment(delete_comment_step) => actualise_delete_comment(delete_comment_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::Suggest(suggest_step) => actualise_suggest(suggest_step, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::AcceptSuggestion(resolve_step) => actualise_resolve_suggestion(resolve_step, true, updated_state.block_map, updated_state.blocks_to_update)?,
+            Step::RejectSuggestion(resolve_step) => actualise_resolve_suggestion(resolve_step, false, updated_state.block_map, updated_state.blocks_to_update)?,
         };
     }
+    if should_normalise_layouts {
+        updated_state.block_map = normalise_layouts(updated_state.block_map, &mut updated_state.blocks_to_update, &mut updated_state.blocks_to_remove)?;
+    }
+    if should_refresh_tables_of_contents || headings_may_have_changed(&updated_state.blocks_to_update, &updated_state.block_map) {
+        updated_state.block_map = refresh_tables_of_contents(updated_state.block_map, &mut updated_state.blocks_to_update)?;
+    }
     updated_state.copy = Some(copy);
     return Ok(updated_state)
 }
@@ -0,0 +1,57 @@
+use crate::{blocks::{BlockMap, Block, standard_blocks::StandardBlockType}, steps_generator::StepError, utilities::update_state_tools};
+
+/// Cleans up layout blocks left behind after blocks are dragged, deleted, cut or backspaced out of them.
+/// Only layouts that were updated are checked.
+/// -> empty columns & empty horizontal layouts are removed
+/// -> a horizontal layout with a single column is replaced by that column's children
+/// Removed layout ids are added to blocks_to_remove (and taken out of blocks_to_update)
+pub fn normalise_layouts(
+    mut block_map: BlockMap,
+    blocks_to_update: &mut Vec<String>,
+    blocks_to_remove: &mut Vec<String>
+) -> Result<BlockMap, StepError> {
+    let mut pending = blocks_to_update.clone();
+    while let Some(id) = pending.pop() {
+        if blocks_to_remove.contains(&id) {
+            continue;
+        }
+        let block = match block_map.get_block(&id) {
+            Ok(Block::StandardBlock(block)) => block,
+            _ => continue
+        };
+        let horizontal = match &block.content {
+            StandardBlockType::Layout(layout_block) => layout_block.horizontal,
+            _ => continue
+        };
+        let parent = match block.get_parent(&block_map) {
+            Ok(parent) => parent,
+            Err(_) => continue
+        };
+        // layout has already been detached from the tree
+        let index = match parent.index_of_child(&id) {
+            Ok(index) => index,
+            Err(_) => continue
+        };
+        let parent_id = parent.id();
+
+        if block.children.len() == 0 {
+            update_state_tools::splice_children(parent, index..index + 1, vec![], blocks_to_update, &mut block_map)?;
+            blocks_to_remove.push(id);
+            pending.push(parent_id);
+        } else if horizontal && block.children.len() == 1 {
+            let column = block_map.get_standard_block(&block.children[0])?;
+            let lifted_children = match &column.content {
+                StandardBlockType::Layout(layout_block) if !layout_block.horizontal => {
+                    blocks_to_remove.push(column.id());
+                    column.children.clone()
+                },
+                _ => vec![column.id()]
+            };
+            update_state_tools::splice_children(parent, index..index + 1, lifted_children, blocks_to_update, &mut block_map)?;
+            blocks_to_remove.push(id);
+            pending.push(parent_id);
+        }
+    }
+    blocks_to_update.retain(|id| !blocks_to_remove.contains(id));
+    return Ok(block_map)
+}
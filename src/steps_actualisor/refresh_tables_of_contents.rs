@@ -0,0 +1,53 @@
+use crate::{blocks::{BlockMap, Block, standard_blocks::{StandardBlock, StandardBlockType, table_of_contents_block::TableOfContentsBlock}},
+    steps_generator::StepError, utilities::{find_root_block_id, get_standard_blocks_in_order, outline::{get_headings, heading_level}}};
+
+/// Regenerates the entries of every table of contents under the roots of the updated blocks.
+/// Only tables whose entries changed are updated (& added to blocks_to_update)
+pub fn refresh_tables_of_contents(
+    mut block_map: BlockMap,
+    blocks_to_update: &mut Vec<String>
+) -> Result<BlockMap, StepError> {
+    let mut root_block_ids: Vec<String> = vec![];
+    for id in blocks_to_update.iter() {
+        if let Some(root_block_id) = find_root_block_id(id, &block_map) {
+            if !root_block_ids.contains(&root_block_id) {
+                root_block_ids.push(root_block_id);
+            }
+        }
+    }
+
+    for root_block_id in root_block_ids {
+        let tables_of_contents: Vec<StandardBlock> = get_standard_blocks_in_order(&root_block_id, &block_map)?
+            .into_iter()
+            .filter(|block| matches!(block.content, StandardBlockType::TableOfContents(_)))
+            .collect();
+        if tables_of_contents.is_empty() {
+            continue;
+        }
+        let headings = get_headings(&root_block_id, &block_map)?;
+        for mut table_of_contents in tables_of_contents {
+            match &table_of_contents.content {
+                StandardBlockType::TableOfContents(block) if block.entries == headings => continue,
+                _ => {}
+            };
+            table_of_contents.content = StandardBlockType::TableOfContents(TableOfContentsBlock { entries: headings.clone() });
+            block_map.update_block(Block::StandardBlock(table_of_contents), blocks_to_update)?;
+        }
+    }
+    return Ok(block_map)
+}
+
+/// Text only steps can change a heading by:
+/// -> editing a heading (or it's inline blocks)
+/// -> removing blocks (which updates the root or the removed blocks' parent)
+pub fn headings_may_have_changed(blocks_to_update: &Vec<String>, block_map: &BlockMap) -> bool {
+    return blocks_to_update.iter().any(|id| match block_map.get_block(id) {
+        Ok(Block::Root(_)) => true,
+        Ok(Block::StandardBlock(block)) => heading_level(&block).is_some() || block.children.len() > 0,
+        Ok(Block::InlineBlock(inline_block)) => match block_map.get_standard_block(&inline_block.parent) {
+            Ok(block) => heading_level(&block).is_some(),
+            Err(_) => false
+        },
+        Err(_) => false
+    })
+}
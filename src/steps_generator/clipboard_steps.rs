@@ -1,20 +1,21 @@
-use crate::{step::{Step, PasteStep}, blocks::BlockMap, custom_copy::CustomCopy};
+use crate::{step::{Step, PasteStep}, blocks::BlockMap, custom_copy::CustomCopy, new_ids::NewIds, utilities::get_standard_blocks_in_order};
 
-use super::{selection::SubSelection, StepError, keypress_step_generator::backspace::generate_steps_for_backspace};
+use super::{selection::SubSelection, StepError, keypress_step_generator::backspace::generate_steps_for_backspace,
+    generate_replace_selected_steps::generate_suggest_replace_selected_steps};
 
 
-pub fn generate_cut_steps(from: SubSelection, to: SubSelection, block_map: &BlockMap) -> Result<Vec<Step>, StepError> {
+pub fn generate_cut_steps(from: SubSelection, to: SubSelection, block_map: &BlockMap, new_ids: &mut NewIds) -> Result<Vec<Step>, StepError> {
     if from == to {
         return Ok(vec![Step::Copy(from.clone(), to.clone())])
     } else {
         return Ok(vec![
             vec![Step::Copy(from.clone(), to.clone())],
-            generate_steps_for_backspace(block_map, from, to)?,
+            generate_steps_for_backspace(block_map, from, to, new_ids)?,
         ].into_iter().flatten().collect())
     }
 }
 
-pub fn generate_paste_steps(from: SubSelection, to: SubSelection, block_map: &BlockMap, copy: CustomCopy) -> Result<Vec<Step>, StepError> {
+pub fn generate_paste_steps(from: SubSelection, to: SubSelection, block_map: &BlockMap, copy: CustomCopy, new_ids: &mut NewIds) -> Result<Vec<Step>, StepError> {
     if from == to {
         return Ok(vec![Step::Paste(PasteStep {
             from,
@@ -23,7 +24,7 @@ pub fn generate_paste_steps(from: SubSelection, to: SubSelection, block_map: &Bl
         })])
     } else {
         return Ok(vec![
-            generate_steps_for_backspace(block_map, from.clone(), to.clone())?,
+            generate_steps_for_backspace(block_map, from.clone(), to.clone(), new_ids)?,
             vec![Step::Paste(PasteStep {
                 from,
                 to,
@@ -31,4 +32,37 @@ pub fn generate_paste_steps(from: SubSelection, to: SubSelection, block_map: &Bl
             })]
         ].into_iter().flatten().collect())
     }
-}
\ No newline at end of file
+}
+
+/// Suggestion mode: the cut text is suggested for deletion instead of being removed
+pub fn generate_suggested_cut_steps(from: SubSelection, to: SubSelection, block_map: &BlockMap, author: &String, new_ids: &mut NewIds) -> Result<Vec<Step>, StepError> {
+    return Ok(vec![
+        vec![Step::Copy(from.clone(), to.clone())],
+        generate_suggest_replace_selected_steps(block_map, from, to, "".to_string(), author, new_ids)?
+    ].into_iter().flatten().collect())
+}
+
+/// Suggestion mode: the copied blocks' text is suggested as an insertion (blocks are joined with a space)
+pub fn generate_suggested_paste_steps(
+    from: SubSelection,
+    to: SubSelection,
+    block_map: &BlockMap,
+    copy: CustomCopy,
+    author: &String,
+    new_ids: &mut NewIds
+) -> Result<Vec<Step>, StepError> {
+    let tree = copy.to_tree()?;
+    let mut texts = vec![];
+    for top_block in &tree.top_blocks {
+        let mut blocks = vec![top_block.clone()];
+        blocks.append(&mut get_standard_blocks_in_order(&top_block.id(), &tree.block_map)?);
+        for block in blocks.iter().filter(|block| block.has_content()) {
+            let mut text = String::new();
+            for inline_block in block.get_inline_blocks(&tree.block_map)? {
+                text.push_str(&inline_block.text()?.clone().to_string());
+            }
+            texts.push(text);
+        }
+    }
+    return generate_suggest_replace_selected_steps(block_map, from, to, texts.join(" "), author, new_ids)
+}
@@ -0,0 +1,32 @@
+use crate::{blocks::BlockMap, step::{Step, ResolveCommentStep, DeleteCommentStep}, mark::{Mark, CommentMark}, new_ids::NewIds};
+
+use super::{StepError, event::CommentEvent, selection::SubSelection, mark_steps::generate_mark_steps};
+
+pub fn generate_comment_steps(
+    comment_event: &CommentEvent,
+    from: SubSelection,
+    to: SubSelection,
+    block_map: &BlockMap,
+    new_ids: &mut NewIds
+) -> Result<Vec<Step>, StepError> {
+    return match comment_event {
+        CommentEvent::Add(comment_id) => {
+            if from == to {
+                return Err(StepError("Cannot add a comment without selecting text".to_string()))
+            }
+            let mark = Mark::Comment(CommentMark { id: comment_id.clone(), resolved: false });
+            generate_mark_steps(mark, from, to, block_map, new_ids)
+        },
+        CommentEvent::Resolve { comment_id, root_block_id } | CommentEvent::Reopen { comment_id, root_block_id } => {
+            Ok(vec![Step::ResolveComment(ResolveCommentStep {
+                root_block_id: root_block_id.clone(),
+                comment_id: comment_id.clone(),
+                resolved: matches!(comment_event, CommentEvent::Resolve { .. })
+            })])
+        },
+        CommentEvent::Delete { comment_id, root_block_id } => Ok(vec![Step::DeleteComment(DeleteCommentStep {
+            root_block_id: root_block_id.clone(),
+            comment_id: comment_id.clone()
+        })])
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{blocks::{BlockMap, Block, standard_blocks::{StandardBlock, StandardBlockType, content_block::ContentBlock},
+    inline_blocks::{InlineBlock, InlineBlockType, text_block::{TextBlock, StringUTF16}}},
+    step::{Step, ReplaceStep, ReplaceSlice, MarkStep, AddBlockStep, MoveBlocksStep, TurnInto, SetListStartStep},
+    steps_actualisor::{actualise_replace_steps::actualise_replace_step, actualise_mark_steps::actualise_mark_step},
+    mark::Mark, new_ids::NewIds, utilities::get_standard_blocks_in_order};
+
+use super::{StepError, selection::SubSelection};
+
+/// Steps that turn the old snapshot of a root's blocks into the new snapshot (standard blocks are matched by id)
+/// -> blocks that changed parent or position are moved (in the new snapshot's document order)
+/// -> blocks only in the new snapshot are added & blocks only in the old snapshot are deleted
+/// -> changed block settings become toggle / set list start / turn into steps
+/// -> changed text becomes a single replace step per block (the text between the common prefix & suffix),
+///    followed by mark steps for the characters whose marks are different
+/// Inline block ids aren't kept, as the actualisor splits & merges inline blocks when marks change
+pub fn generate_diff_steps(
+    root_block_id: &String,
+    old_block_map: &BlockMap,
+    new_block_map: &BlockMap,
+    new_ids: &mut NewIds
+) -> Result<Vec<Step>, StepError> {
+    let old_blocks = get_standard_blocks_in_order(root_block_id, old_block_map)?;
+    let new_blocks = get_standard_blocks_in_order(root_block_id, new_block_map)?;
+    let old_blocks_by_id: HashMap<String, &StandardBlock> = old_blocks.iter().map(|block| (block.id(), block)).collect();
+    let new_blocks_by_id: HashMap<String, &StandardBlock> = new_blocks.iter().map(|block| (block.id(), block)).collect();
+
+    // blocks that gained or lost their text can't be turned into each other, so they are deleted & added again
+    let replaced_ids: HashSet<String> = old_blocks.iter()
+        .filter(|block| match new_blocks_by_id.get(&block.id()) {
+            Some(new_block) => block.has_content() != new_block.has_content(),
+            None => false
+        })
+        .map(|block| block.id())
+        .collect();
+
+    let mut tree = SimulatedTree::from(root_block_id, old_block_map, &old_blocks)?;
+    let mut steps = vec![];
+
+    for block in &old_blocks {
+        if replaced_ids.contains(&block.id()) {
+            steps.extend(generate_remove_replaced_block_steps(&block.id(), &mut tree));
+        }
+    }
+
+    let mut added_inline_block_ids = HashMap::new();
+    let new_parents = std::iter::once((root_block_id.clone(), new_block_map.get_root_block(root_block_id)?.children))
+        .chain(new_blocks.iter().map(|block| (block.id(), block.children.clone())));
+    for (parent_id, children) in new_parents {
+        for (i, child_id) in children.iter().enumerate() {
+            if tree.children_of(&parent_id).get(i) == Some(child_id) {
+                continue;
+            }
+            match tree.contains(child_id) {
+                true => steps.push(Step::MoveBlocks(MoveBlocksStep {
+                    block_ids: vec![child_id.clone()],
+                    new_parent_id: parent_id.clone(),
+                    child_offset: i,
+                    from: SubSelection::from(child_id.clone(), 0, None),
+                    to: SubSelection::from(child_id.clone(), 0, None)
+                })),
+                false => {
+                    let new_block = new_blocks_by_id.get(child_id)
+                        .ok_or(StepError(format!("Block: {} is not in the new block map", child_id)))?;
+                    let new_inline_block_id = match new_block.content_block() {
+                        Ok(ContentBlock { inline_blocks }) if inline_blocks.len() > 0 => inline_blocks[0].clone(),
+                        _ => new_ids.get_id()?
+                    };
+                    added_inline_block_ids.insert(child_id.clone(), new_inline_block_id.clone());
+                    steps.push(Step::AddBlock(AddBlockStep {
+                        block_id: parent_id.clone(),
+                        new_std_block_id: child_id.clone(),
+                        new_inline_block_id,
+                        child_offset: i,
+                        block_type: new_block.content.clone(),
+                        focus_block_below: false
+                    }));
+                }
+            };
+            tree.remove(child_id);
+            tree.insert(child_id, &parent_id, i);
+        }
+    }
+
+    // descendants of deleted blocks are deleted with them
+    for block in &old_blocks {
+        if new_blocks_by_id.contains_key(&block.id()) || !tree.contains(&block.id()) {
+            continue;
+        }
+        let parent_id = tree.parent_of(&block.id());
+        if &parent_id == root_block_id || new_blocks_by_id.contains_key(&parent_id) {
+            steps.push(Step::DeleteBlock(block.id()));
+        }
+    }
+
+    let mut working_block_map = get_working_block_map(&old_blocks, old_block_map)?;
+    for new_block in &new_blocks {
+        match old_blocks_by_id.get(&new_block.id()) {
+            Some(old_block) if !replaced_ids.contains(&new_block.id()) => steps.extend(generate_block_setting_steps(old_block, new_block)),
+            _ => {}
+        };
+        if !new_block.has_content() {
+            continue;
+        }
+        if let Some(inline_block_id) = added_inline_block_ids.get(&new_block.id()) {
+            add_empty_block(new_block, inline_block_id, &mut working_block_map)?;
+        }
+        let (text_steps, updated_block_map) = generate_text_steps(new_block, new_block_map, working_block_map, new_ids)?;
+        steps.extend(text_steps);
+        working_block_map = updated_block_map;
+    }
+    return Ok(steps)
+}
+
+/// Parent -> children of the blocks as the steps are generated (so the child offsets stay correct)
+struct SimulatedTree {
+    children: HashMap<String, Vec<String>>,
+    parents: HashMap<String, String>
+}
+
+impl SimulatedTree {
+    fn from(root_block_id: &String, block_map: &BlockMap, blocks: &Vec<StandardBlock>) -> Result<Self, StepError> {
+        let mut children = HashMap::new();
+        let mut parents = HashMap::new();
+        children.insert(root_block_id.clone(), block_map.get_root_block(root_block_id)?.children);
+        for block in blocks {
+            children.insert(block.id(), block.children.clone());
+            parents.insert(block.id(), block.parent.clone());
+        }
+        return Ok(Self { children, parents })
+    }
+
+    fn contains(&self, id: &String) -> bool {
+        return self.parents.contains_key(id)
+    }
+
+    fn children_of(&self, id: &String) -> Vec<String> {
+        return self.children.get(id).cloned().unwrap_or(vec![])
+    }
+
+    fn parent_of(&self, id: &String) -> String {
+        return self.parents.get(id).cloned().unwrap_or_default()
+    }
+
+    fn remove(&mut self, id: &String) {
+        if let Some(parent_id) = self.parents.remove(id) {
+            if let Some(siblings) = self.children.get_mut(&parent_id) {
+                siblings.retain(|sibling_id| sibling_id != id);
+            }
+        }
+    }
+
+    fn insert(&mut self, id: &String, parent_id: &String, index: usize) {
+        self.children.entry(parent_id.clone()).or_insert(vec![]).insert(index, id.clone());
+        self.children.entry(id.clone()).or_insert(vec![]);
+        self.parents.insert(id.clone(), parent_id.clone());
+    }
+}
+
+/// Lifts the replaced block's children into it's parent (directly after it) & deletes it
+fn generate_remove_replaced_block_steps(id: &String, tree: &mut SimulatedTree) -> Vec<Step> {
+    let mut steps = vec![];
+    let parent_id = tree.parent_of(id);
+    let index = tree.children_of(&parent_id).iter().position(|sibling_id| sibling_id == id).unwrap_or(0);
+    let children = tree.children_of(id);
+    if children.len() > 0 {
+        steps.push(Step::MoveBlocks(MoveBlocksStep {
+            block_ids: children.clone(),
+            new_parent_id: parent_id.clone(),
+            child_offset: index + 1,
+            from: SubSelection::from(children[0].clone(), 0, None),
+            to: SubSelection::from(children[0].clone(), 0, None)
+        }));
+        for (i, child_id) in children.iter().enumerate() {
+            tree.remove(child_id);
+            tree.insert(child_id, &parent_id, index + 1 + i);
+        }
+    }
+    steps.push(Step::DeleteBlock(id.clone()));
+    tree.remove(id);
+    tree.children.remove(id);
+    return steps
+}
+
+fn generate_block_setting_steps(old_block: &StandardBlock, new_block: &StandardBlock) -> Vec<Step> {
+    let turn_into = Step::TurnInto(TurnInto { block_id: new_block.id(), new_block_type: new_block.content.clone() });
+    if old_block.content._type_as_string() != new_block.content._type_as_string() {
+        return vec![turn_into]
+    }
+    let mut steps = vec![];
+    match (&old_block.content, &new_block.content) {
+        (StandardBlockType::TodoList(old_list), StandardBlockType::TodoList(new_list)) |
+        (StandardBlockType::DotPointList(old_list), StandardBlockType::DotPointList(new_list)) |
+        (StandardBlockType::NumberedList(old_list), StandardBlockType::NumberedList(new_list)) |
+        (StandardBlockType::ArrowList(old_list), StandardBlockType::ArrowList(new_list)) => {
+            if old_list.completed != new_list.completed {
+                steps.push(Step::ToggleCompleted(new_block.id()));
+            }
+            if old_list.start != new_list.start {
+                steps.push(Step::SetListStart(SetListStartStep { block_id: new_block.id(), start: new_list.start }));
+            }
+        },
+        (StandardBlockType::ToggleList(old_toggle), StandardBlockType::ToggleList(new_toggle)) => {
+            if old_toggle.collapsed != new_toggle.collapsed {
+                steps.push(Step::ToggleCollapsed(new_block.id()));
+            }
+        },
+        (StandardBlockType::Paragraph(_), _) | (StandardBlockType::H1(_), _) |
+        (StandardBlockType::H2(_), _) | (StandardBlockType::H3(_), _) => {},
+        (old_content, new_content) => {
+            if old_content != new_content {
+                steps.push(turn_into);
+            }
+        }
+    };
+    return steps
+}
+
+/// Replace step for the changed text, then remove & add mark steps.
+/// Each step is actualised on the working block map so the next step can use the new inline block ids
+fn generate_text_steps(
+    new_block: &StandardBlock,
+    new_block_map: &BlockMap,
+    mut working_block_map: BlockMap,
+    new_ids: &mut NewIds
+) -> Result<(Vec<Step>, BlockMap), StepError> {
+    let mut steps = vec![];
+    let target = get_chars_with_marks(new_block, new_block_map)?;
+    let current = get_chars_with_marks(&working_block_map.get_standard_block(&new_block.id())?, &working_block_map)?;
+
+    let (prefix, suffix) = get_common_prefix_and_suffix(&current, &target);
+    if prefix + suffix < current.len() || prefix + suffix < target.len() {
+        let inserted_text: Vec<u16> = target[prefix..target.len() - suffix].iter().map(|(c, _)| *c).collect();
+        let replace_step = ReplaceStep {
+            block_id: new_block.id(),
+            from: SubSelection::from(new_block.id(), prefix, None).real_selection_from_raw(&working_block_map)?,
+            to: SubSelection::from(new_block.id(), current.len() - suffix, None).real_selection_from_raw(&working_block_map)?,
+            slice: ReplaceSlice::String(String::from_utf16_lossy(&inserted_text))
+        };
+        working_block_map = actualise_replace_step(replace_step.clone(), working_block_map, None, vec![], new_ids)?.block_map;
+        steps.push(Step::ReplaceStep(replace_step));
+    }
+
+    let current = get_chars_with_marks(&working_block_map.get_standard_block(&new_block.id())?, &working_block_map)?;
+    let mut marks: Vec<Mark> = vec![];
+    for (_, char_marks) in current.iter().chain(target.iter()) {
+        for mark in char_marks {
+            if !marks.contains(mark) {
+                marks.push(mark.clone());
+            }
+        }
+    }
+    // removing first means a mark with a different value (eg: colour) is replaced rather than added to
+    for add_mark in [false, true] {
+        for mark in &marks {
+            let needs_step = |i: usize| match add_mark {
+                true => target[i].1.contains(mark) && !current[i].1.contains(mark),
+                false => current[i].1.contains(mark) && !target[i].1.contains(mark)
+            };
+            let mut i = 0;
+            while i < target.len() {
+                if !needs_step(i) {
+                    i += 1;
+                    continue;
+                }
+                let start = i;
+                while i < target.len() && needs_step(i) {
+                    i += 1;
+                }
+                let mark_step = MarkStep {
+                    block_id: new_block.id(),
+                    from: get_mark_from(&new_block.id(), start, &working_block_map)?,
+                    to: SubSelection::from(new_block.id(), i, None).real_selection_from_raw(&working_block_map)?,
+                    mark: mark.clone(),
+                    from_new_inline_id: new_ids.get_id()?,
+                    to_new_inline_id: new_ids.get_id()?
+                };
+                working_block_map = actualise_mark_step(mark_step.clone(), working_block_map, add_mark, vec![])?.block_map;
+                steps.push(match add_mark {
+                    true => Step::AddMarkStep(mark_step),
+                    false => Step::RemoveMarkStep(mark_step)
+                });
+            }
+        }
+    }
+    return Ok((steps, working_block_map))
+}
+
+/// Common prefix & suffix lengths, without splitting a surrogate pair
+fn get_common_prefix_and_suffix(current: &Vec<(u16, Vec<Mark>)>, target: &Vec<(u16, Vec<Mark>)>) -> (usize, usize) {
+    let max_len = current.len().min(target.len());
+    let mut prefix = 0;
+    while prefix < max_len && current[prefix].0 == target[prefix].0 {
+        prefix += 1;
+    }
+    if prefix > 0 && prefix < max_len && is_high_surrogate(target[prefix - 1].0) {
+        prefix -= 1;
+    }
+    let mut suffix = 0;
+    while suffix < max_len - prefix && current[current.len() - 1 - suffix].0 == target[target.len() - 1 - suffix].0 {
+        suffix += 1;
+    }
+    if suffix > 0 && suffix < target.len() - prefix && is_low_surrogate(target[target.len() - suffix].0) {
+        suffix -= 1;
+    }
+    return (prefix, suffix)
+}
+
+fn is_high_surrogate(c: u16) -> bool {
+    return (0xD800..0xDC00).contains(&c)
+}
+
+fn is_low_surrogate(c: u16) -> bool {
+    return (0xDC00..0xE000).contains(&c)
+}
+
+/// Marks should start in the inline block after the offset, rather than at the end of the one before it
+fn get_mark_from(block_id: &String, raw_offset: usize, block_map: &BlockMap) -> Result<SubSelection, StepError> {
+    let sub_selection = SubSelection::from(block_id.clone(), raw_offset, None).real_selection_from_raw(block_map)?;
+    let inline_block = block_map.get_inline_block(&sub_selection.block_id)?;
+    if sub_selection.offset < inline_block.text()?.len() {
+        return Ok(sub_selection)
+    }
+    let inline_blocks = &block_map.get_standard_block(block_id)?.content_block()?.inline_blocks.clone();
+    return match inline_blocks.iter().position(|id| id == &sub_selection.block_id) {
+        Some(index) if index + 1 < inline_blocks.len() => Ok(SubSelection::from(inline_blocks[index + 1].clone(), 0, None)),
+        _ => Ok(sub_selection)
+    }
+}
+
+fn get_chars_with_marks(block: &StandardBlock, block_map: &BlockMap) -> Result<Vec<(u16, Vec<Mark>)>, StepError> {
+    let mut chars = vec![];
+    for inline_block in block.get_inline_blocks(block_map)? {
+        for c in inline_block.text()?.0.iter() {
+            chars.push((*c, inline_block.marks.clone()));
+        }
+    }
+    return Ok(chars)
+}
+
+/// Copy of the old standard blocks & their inline blocks, used to work out the inline block ids for each step
+fn get_working_block_map(old_blocks: &Vec<StandardBlock>, old_block_map: &BlockMap) -> Result<BlockMap, StepError> {
+    let mut working_block_map = BlockMap::Rust(HashMap::new());
+    for block in old_blocks {
+        if block.has_content() {
+            for inline_block in block.get_inline_blocks(old_block_map)? {
+                working_block_map.update_block(Block::InlineBlock(inline_block), &mut vec![])?;
+            }
+        }
+        working_block_map.update_block(Block::StandardBlock(block.clone()), &mut vec![])?;
+    }
+    return Ok(working_block_map)
+}
+
+/// Same as the block the add block step creates -> a single empty inline block
+fn add_empty_block(new_block: &StandardBlock, inline_block_id: &String, working_block_map: &mut BlockMap) -> Result<(), StepError> {
+    let inline_block = InlineBlock {
+        _id: inline_block_id.clone(),
+        content: InlineBlockType::TextBlock(TextBlock(StringUTF16::new())),
+        marks: vec![],
+        parent: new_block.id()
+    };
+    let block = StandardBlock {
+        _id: new_block.id(),
+        content: new_block.content.update_block_content(ContentBlock { inline_blocks: vec![inline_block_id.clone()] })?,
+        children: vec![],
+        parent: new_block.parent.clone(),
+        marks: vec![]
+    };
+    working_block_map.update_blocks(vec![Block::InlineBlock(inline_block), Block::StandardBlock(block)], &mut vec![])?;
+    return Ok(())
+}
+
+
@@ -0,0 +1,39 @@
+use crate::{blocks::{BlockMap, standard_blocks::StandardBlockType}, step::{Step, MoveBlocksToPageStep}};
+
+use super::{event::{DropBlockEvent, Side}, StepError};
+
+/// Dropping blocks as the first or last child of a page block moves them into that page
+/// -> every other drop is actualised as a drop block step
+pub fn generate_drop_block_steps(drop_block_event: &DropBlockEvent, block_map: &BlockMap) -> Result<Vec<Step>, StepError> {
+    let drop_block = block_map.get_standard_block(&drop_block_event.drop_block_id)?;
+    let page_block = match &drop_block.content {
+        StandardBlockType::InlinePage(page_block)
+        | StandardBlockType::SquarePage(page_block)
+        | StandardBlockType::LinkBlock(page_block) => page_block,
+        _ => return Ok(vec![Step::DropBlock(drop_block_event.clone())])
+    };
+    let child_offset = match drop_block_event.side_dropped {
+        Side::FirstChild => Some(0),
+        Side::LastChild => None,
+        _ => return Ok(vec![Step::DropBlock(drop_block_event.clone())])
+    };
+    if page_block.page_id.is_empty() {
+        return Err(StepError(format!("Page block: {} does not link to a page", drop_block.id())))
+    }
+    // the page block would end up inside its own page if it or one of its ancestors is dragged
+    let mut ancestor = drop_block.clone();
+    loop {
+        if drop_block_event.drag_block_ids.contains(&ancestor.id()) {
+            return Err(StepError(format!("Cannot drop page block: {} into its own page", drop_block.id())))
+        }
+        if ancestor.parent_is_root(block_map) {
+            break
+        }
+        ancestor = block_map.get_standard_block(&ancestor.parent)?;
+    }
+    return Ok(vec![Step::MoveBlocksToPage(MoveBlocksToPageStep {
+        block_ids: drop_block_event.drag_block_ids.clone(),
+        page_id: page_block.page_id.clone(),
+        child_offset
+    })])
+}
@@ -4,21 +4,29 @@ use serde::{Serialize, Deserialize};
 use serde_json::{Value, json};
 use wasm_bindgen::JsValue;
 
-use crate::{mark::{Color, Mark}, frontend_interface::{get_js_field_as_string, get_js_field, get_js_field_as_bool}, blocks::standard_blocks::{StandardBlockType, content_block::ContentBlock, list_block::ListBlock}};
+use crate::{mark::{Color, Mark, CustomMark, get_custom_mark_type}, frontend_interface::{get_js_field_as_string, get_js_field, get_js_field_as_bool, get_js_field_as_f64}, blocks::standard_blocks::{StandardBlockType, content_block::ContentBlock, list_block::ListBlock, toggle_block::ToggleBlock, custom_block::CustomBlock},
+backend_interface::{get_json_field_as_string, get_json_field_as_int}, utilities::snapshots::Snapshot};
 
-use super::StepError;
+use super::{StepError, find_replace::SearchOptions, smart_typography::Conversion};
 
 pub enum Event {
     KeyPress(KeyPress),
     FormatBar(FormatBarEvent),
     SlashScrim(SlashScrimEvent),
     ToggleCompleted(String), //block id
+    ToggleCollapsed(String), //block id
     ContextMenu(ContextMenuEvent),
     DropBlock(DropBlockEvent),
     DeleteBlock(String),
     Duplicate(String),
     ReplaceWithChildren(ReplaceWithChildrenEvent),
-    AddParagraphAtBottom(String) // (root block id)
+    AddParagraphAtBottom(String), // (root block id)
+    SetListStart(SetListStartEvent),
+    ResizeLayoutColumns(ResizeLayoutColumnsEvent),
+    FindReplace(FindReplaceEvent),
+    Comment(CommentEvent),
+    Suggestion(SuggestionEvent),
+    RestoreSnapshot(Snapshot)
 }
 
 impl Event {
@@ -29,12 +37,19 @@ impl Event {
             "formatbar" => Ok(Event::FormatBar(FormatBarEvent::from_js_obj(obj)?)),
             "slash_scrim" => Ok(Event::SlashScrim(SlashScrimEvent::from_js_obj(obj)?)),
             "toggle_completed" => Ok(Event::ToggleCompleted(get_js_field_as_string(&JsValue::from(&obj), "value")?)),
+            "toggle_collapsed" => Ok(Event::ToggleCollapsed(get_js_field_as_string(&JsValue::from(&obj), "value")?)),
             "context_menu" => Ok(Event::ContextMenu(ContextMenuEvent::from_js_obj(obj)?)),
             "drop_block" => Ok(Event::DropBlock(DropBlockEvent::from_js_obj(obj)?)),
             "delete_block" => Ok(Event::DeleteBlock(get_js_field_as_string(&obj, "value")?)),
             "replace_with_children" => Ok(Event::ReplaceWithChildren(ReplaceWithChildrenEvent::from_js_obj(obj)?)),
             "duplicate_block" => Ok(Event::Duplicate(get_js_field_as_string(&obj, "value")?)),
             "add_paragraph_at_bottom" => Ok(Event::AddParagraphAtBottom(get_js_field_as_string(&obj, "value")?)),
+            "set_list_start" => Ok(Event::SetListStart(SetListStartEvent::from_js_obj(obj)?)),
+            "resize_layout_columns" => Ok(Event::ResizeLayoutColumns(ResizeLayoutColumnsEvent::from_js_obj(obj)?)),
+            "find_replace" => Ok(Event::FindReplace(FindReplaceEvent::from_js_obj(obj)?)),
+            "comment" => Ok(Event::Comment(CommentEvent::from_js_obj(obj)?)),
+            "suggestion" => Ok(Event::Suggestion(SuggestionEvent::from_js_obj(obj)?)),
+            "restore_snapshot" => Ok(Event::RestoreSnapshot(Snapshot::from_js_obj(&get_js_field(&obj, "value")?)?)),
             _type => Err(StepError(format!("Expected event _type. Got: {}", _type)))
         }
     }
@@ -59,6 +74,9 @@ impl KeyPress {
                     meta_down: false,
                     ctrl_down: false,
                     alt_down: false,
+                    smart_typography: false,
+                    suggesting_as: None,
+                    last_conversion: None,
                 }
             }
         }
@@ -82,6 +100,12 @@ pub struct KeyPressMetadata {
     pub meta_down: bool,
     pub ctrl_down: bool,
     pub alt_down: bool,
+    /// Set by the frontend from the document's preference, defaults to false
+    pub smart_typography: bool,
+    /// Author id, set by the frontend when the document is in suggestion mode
+    pub suggesting_as: Option<String>,
+    /// The smart typography conversion returned with the previous event's result, if it made one
+    pub last_conversion: Option<Conversion>,
 }
 
 impl KeyPressMetadata {
@@ -90,8 +114,15 @@ impl KeyPressMetadata {
         let meta_down = get_js_field_as_bool(obj, "meta_down")?;
         let ctrl_down = get_js_field_as_bool(obj, "ctrl_down")?;
         let alt_down = get_js_field_as_bool(obj, "alt_down")?;
+        let smart_typography = get_js_field(obj, "smart_typography")?.as_bool().unwrap_or(false);
+        let suggesting_as = get_js_field(obj, "suggesting_as")?.as_string();
+        let last_conversion = get_js_field(obj, "last_conversion")?;
+        let last_conversion = match last_conversion.is_null() || last_conversion.is_undefined() {
+            true => None,
+            false => Some(Conversion::from_js_obj(&last_conversion)?)
+        };
 
-        return Ok(Self { shift_down, meta_down, ctrl_down, alt_down })
+        return Ok(Self { shift_down, meta_down, ctrl_down, alt_down, smart_typography, suggesting_as, last_conversion })
     }
 }
 
@@ -101,6 +132,8 @@ pub enum Key {
     Enter,
     Tab,
     Escape,
+    ArrowUp,
+    ArrowDown,
     Standard(char)
 }
 
@@ -112,6 +145,8 @@ impl Key {
             "Enter" => return Ok(Key::Enter),
             "Tab" => return Ok(Key::Tab),
             "Escape" => return Ok(Key::Escape),
+            "ArrowUp" => return Ok(Key::ArrowUp),
+            "ArrowDown" => return Ok(Key::ArrowDown),
             _ => {
                 let chars: Vec<char> = key.chars().collect();
                 if chars.len() > 1 {
@@ -131,7 +166,8 @@ pub enum FormatBarEvent {
     Strikethrough,
     ForeColor(Color),
     BackColor(Color),
-    TurnInto(StandardBlockType)
+    TurnInto(StandardBlockType),
+    Custom(CustomMark)
 }
 
 impl FormatBarEvent {
@@ -144,6 +180,7 @@ impl FormatBarEvent {
             "underline" => Ok(FormatBarEvent::Underline),
             "strikethrough" => Ok(FormatBarEvent::Strikethrough),
             value if value.contains("turn_into") => Ok(FormatBarEvent::TurnInto(parse_turn_into_str(value)?)),
+            value if get_custom_mark_type(CustomMark::name_from_str(value)).is_some() => Ok(FormatBarEvent::Custom(CustomMark::from_str(value)?)),
             value => {
                 let as_mark = Mark::color_mark_from_str(value)?;
                 match as_mark {
@@ -162,10 +199,18 @@ fn parse_turn_into_str(value: &str) -> Result<StandardBlockType, StepError> {
         "turn_into(heading 1)" => return Ok(StandardBlockType::H1(ContentBlock::new(vec![]))),
         "turn_into(heading 2)" => return Ok(StandardBlockType::H2(ContentBlock::new(vec![]))),
         "turn_into(heading 3)" => return Ok(StandardBlockType::H3(ContentBlock::new(vec![]))),
-        "turn_into(to-do list)" => return Ok(StandardBlockType::TodoList(ListBlock { content: ContentBlock::new(vec![]), completed: false } )),
-        "turn_into(dotpoint list)" => return Ok(StandardBlockType::DotPointList(ListBlock { content: ContentBlock::new(vec![]), completed: false } )),
-        "turn_into(numbered list)" => return Ok(StandardBlockType::NumberedList(ListBlock { content: ContentBlock::new(vec![]), completed: false } )),
-        "turn_into(arrow list)" => return Ok(StandardBlockType::ArrowList(ListBlock { content: ContentBlock::new(vec![]), completed: false } )),
+        "turn_into(to-do list)" => return Ok(StandardBlockType::TodoList(ListBlock { content: ContentBlock::new(vec![]), completed: false, start: None } )),
+        "turn_into(dotpoint list)" => return Ok(StandardBlockType::DotPointList(ListBlock { content: ContentBlock::new(vec![]), completed: false, start: None } )),
+        "turn_into(numbered list)" => return Ok(StandardBlockType::NumberedList(ListBlock { content: ContentBlock::new(vec![]), completed: false, start: None } )),
+        "turn_into(arrow list)" => return Ok(StandardBlockType::ArrowList(ListBlock { content: ContentBlock::new(vec![]), completed: false, start: None } )),
+        "turn_into(toggle list)" => return Ok(StandardBlockType::ToggleList(ToggleBlock::new())),
+        value if value.starts_with("turn_into(") && value.ends_with(")") => {
+            // registered custom block types, eg: "turn_into(callout)"
+            match CustomBlock::new(&value["turn_into(".len()..value.len() - 1]) {
+                Ok(custom_block) => return Ok(StandardBlockType::Custom(custom_block)),
+                Err(_) => return Err(StepError(format!("Not a valid turn into statement. Got: {}", value)))
+            }
+        },
         value => return Err(StepError(format!("Not a valid turn into statement. Got: {}", value)))
     }
 }
@@ -185,7 +230,8 @@ impl SlashScrimEvent {
 pub enum ContextMenuEvent {
     Copy,
     Cut,
-    Paste
+    Paste,
+    TurnIntoPage
 }
 impl ContextMenuEvent {
     pub fn from_js_obj(obj: js_sys::Object) -> Result<Self, StepError> {
@@ -193,6 +239,7 @@ impl ContextMenuEvent {
             "copy" => Ok(Self::Copy),
             "cut" => Ok(Self::Cut),
             "paste" => Ok(Self::Paste),
+            "turn_into_page" => Ok(Self::TurnIntoPage),
             value => Err(StepError(format!("Expected valid context menu event. Got: {}", value))),
         }
     }
@@ -200,19 +247,25 @@ impl ContextMenuEvent {
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct DropBlockEvent {
-    pub drag_block_id: String,
+    pub drag_block_ids: Vec<String>, // contiguous siblings or a non-contiguous block selection
     pub drop_block_id: String,
     pub side_dropped: Side
 }
 impl DropBlockEvent {
     pub fn from_js_obj(obj: js_sys::Object) -> Result<Self, StepError> {
         let value_obj = get_js_field(&obj, "value")?;
-        let drag_block_id = get_js_field_as_string(&value_obj, "drag_block_id")?;
+        let drag_block_ids_js = get_js_field(&value_obj, "drag_block_ids")?;
+        let drag_block_ids = match drag_block_ids_js.is_undefined() {
+            true => vec![get_js_field_as_string(&value_obj, "drag_block_id")?],
+            false => js_sys::Array::from(&drag_block_ids_js).iter()
+                .map(|id| id.as_string().ok_or(StepError("drag_block_ids should only contain strings".to_string())))
+                .collect::<Result<Vec<String>, StepError>>()?
+        };
         let drop_block_id = get_js_field_as_string(&value_obj, "drop_block_id")?;
         let side_dropped = get_js_field_as_string(&value_obj, "side_dropped")?;
         let side_dropped = Side::from_str(&side_dropped)?;
         return Ok(Self {
-            drag_block_id,
+            drag_block_ids,
             drop_block_id,
             side_dropped
         })
@@ -220,21 +273,29 @@ impl DropBlockEvent {
 
     pub fn to_json(self) -> Result<Value, StepError> {
         return Ok(json!({
-            "drag_block_id": self.drag_block_id,
+            "drag_block_ids": self.drag_block_ids,
             "drop_block_id": self.drop_block_id,
             "side_dropped": match self.side_dropped {
                 Side::Top => "top",
                 Side::Bottom => "bottom",
                 Side::Left => "left",
-                Side::Right => "right"
+                Side::Right => "right",
+                Side::FirstChild => "first_child",
+                Side::LastChild => "last_child"
             }
         }))
     }
 
     pub fn from_json(json: Value) -> Result<Self, StepError> {
-        let drag_block_id = json.get("drag_block_id")
-            .ok_or(StepError("Could not get drag_block_id from json".to_string()))?
-            .as_str().ok_or(StepError("Could not get drag_block_id as str".to_string()))?.to_string();
+        let drag_block_ids = match json.get("drag_block_ids") {
+            Some(ids) => ids.as_array().ok_or(StepError("Could not get drag_block_ids as array".to_string()))?
+                .iter()
+                .map(|id| id.as_str().map(|id| id.to_string()).ok_or(StepError("Could not get drag_block_id as str".to_string())))
+                .collect::<Result<Vec<String>, StepError>>()?,
+            None => vec![json.get("drag_block_id")
+                .ok_or(StepError("Could not get drag_block_ids from json".to_string()))?
+                .as_str().ok_or(StepError("Could not get drag_block_id as str".to_string()))?.to_string()]
+        };
         let drop_block_id = json.get("drop_block_id")
             .ok_or(StepError("Could not get drop_block_id from json".to_string()))?
             .as_str().ok_or(StepError("Could not get drop_block_id as str".to_string()))?.to_string();
@@ -243,7 +304,7 @@ impl DropBlockEvent {
             .as_str().ok_or(StepError("Could not get side_dropped as str".to_string()))?.to_string();
         let side_dropped = Side::from_str(&side_dropped)?;
         return Ok(Self {
-            drag_block_id,
+            drag_block_ids,
             drop_block_id,
             side_dropped
         })
@@ -255,7 +316,9 @@ pub enum Side {
     Top,
     Bottom,
     Left,
-    Right
+    Right,
+    FirstChild,
+    LastChild
 }
 
 impl Side {
@@ -265,6 +328,8 @@ impl Side {
             "left" => Ok(Self::Left),
             "right" => Ok(Self::Right),
             "bottom" => Ok(Self::Bottom),
+            "first_child" => Ok(Self::FirstChild),
+            "last_child" => Ok(Self::LastChild),
             side => Err(StepError(format!("Not a valid side: {}", side))),
         }
     }
@@ -297,4 +362,132 @@ impl ReplaceWithChildrenEvent {
             block_id
         })
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetListStartEvent {
+    pub block_id: String,
+    pub start: Option<usize>
+}
+
+impl SetListStartEvent {
+    pub fn from_js_obj(obj: js_sys::Object) -> Result<Self, StepError> {
+        let value_obj = get_js_field(&obj, "value")?;
+        let block_id = get_js_field_as_string(&value_obj, "block_id")?;
+        let start = get_js_field(&value_obj, "start")?.as_f64().map(|start| start as usize);
+        return Ok(Self {
+            block_id,
+            start
+        })
+    }
+}
+
+/// Moves the divider between column_index & column_index + 1 of a horizontal layout.
+/// ratio -> the left column's share of the two columns' combined width
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResizeLayoutColumnsEvent {
+    pub layout_block_id: String,
+    pub column_index: usize,
+    pub ratio: f64
+}
+
+impl ResizeLayoutColumnsEvent {
+    pub fn from_js_obj(obj: js_sys::Object) -> Result<Self, StepError> {
+        let value_obj = get_js_field(&obj, "value")?;
+        return Ok(Self {
+            layout_block_id: get_js_field_as_string(&value_obj, "layout_block_id")?,
+            column_index: get_js_field_as_f64(&value_obj, "column_index")? as usize,
+            ratio: get_js_field_as_f64(&value_obj, "ratio")?
+        })
+    }
+
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "layout_block_id": self.layout_block_id,
+            "column_index": self.column_index,
+            "ratio": self.ratio
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        let ratio = json.get("ratio")
+            .ok_or(StepError("Could not get ratio from json".to_string()))?
+            .as_f64().ok_or(StepError("Could not get ratio as f64".to_string()))?;
+        return Ok(Self {
+            layout_block_id: get_json_field_as_string(&json, "layout_block_id")?,
+            column_index: get_json_field_as_int(&json, "column_index")? as usize,
+            ratio
+        })
+    }
+}
+
+/// Replaces the next match of the query (or every match in the page if replace_all)
+pub struct FindReplaceEvent {
+    pub query: String,
+    pub replace_with: String,
+    pub options: SearchOptions,
+    pub replace_all: bool
+}
+
+impl FindReplaceEvent {
+    pub fn from_js_obj(obj: js_sys::Object) -> Result<Self, StepError> {
+        let value_obj = get_js_field(&obj, "value")?;
+        return Ok(Self {
+            query: get_js_field_as_string(&value_obj, "query")?,
+            replace_with: get_js_field_as_string(&value_obj, "replace_with")?,
+            options: SearchOptions {
+                case_sensitive: get_js_field(&value_obj, "case_sensitive")?.as_bool().unwrap_or(false),
+                whole_word: get_js_field(&value_obj, "whole_word")?.as_bool().unwrap_or(false),
+                regex: get_js_field(&value_obj, "regex")?.as_bool().unwrap_or(false)
+            },
+            replace_all: get_js_field(&value_obj, "replace_all")?.as_bool().unwrap_or(false)
+        })
+    }
+}
+
+/// Add -> anchors the comment to the selected text
+/// Resolve / Reopen / Delete -> updates every anchor of the comment under the root
+pub enum CommentEvent {
+    Add(String), // comment id
+    Resolve { comment_id: String, root_block_id: String },
+    Reopen { comment_id: String, root_block_id: String },
+    Delete { comment_id: String, root_block_id: String }
+}
+
+impl CommentEvent {
+    pub fn from_js_obj(obj: js_sys::Object) -> Result<Self, StepError> {
+        let value_obj = get_js_field(&obj, "value")?;
+        let action = get_js_field_as_string(&value_obj, "action")?;
+        let comment_id = get_js_field_as_string(&value_obj, "comment_id")?;
+        return match action.as_str() {
+            "add" => Ok(Self::Add(comment_id)),
+            "resolve" => Ok(Self::Resolve { comment_id, root_block_id: get_js_field_as_string(&value_obj, "root_block_id")? }),
+            "reopen" => Ok(Self::Reopen { comment_id, root_block_id: get_js_field_as_string(&value_obj, "root_block_id")? }),
+            "delete" => Ok(Self::Delete { comment_id, root_block_id: get_js_field_as_string(&value_obj, "root_block_id")? }),
+            action => Err(StepError(format!("Expected comment action. Got: {}", action)))
+        }
+    }
+}
+
+/// Accept or reject a suggestion made in suggestion mode
+pub struct SuggestionEvent {
+    pub accept: bool,
+    pub suggestion_id: String,
+    pub root_block_id: String
+}
+
+impl SuggestionEvent {
+    pub fn from_js_obj(obj: js_sys::Object) -> Result<Self, StepError> {
+        let value_obj = get_js_field(&obj, "value")?;
+        let accept = match get_js_field_as_string(&value_obj, "action")?.as_str() {
+            "accept" => true,
+            "reject" => false,
+            action => return Err(StepError(format!("Expected suggestion action. Got: {}", action)))
+        };
+        return Ok(Self {
+            accept,
+            suggestion_id: get_js_field_as_string(&value_obj, "suggestion_id")?,
+            root_block_id: get_js_field_as_string(&value_obj, "root_block_id")?
+        })
+    }
+}
@@ -0,0 +1,218 @@
+use std::ops::Range;
+
+use crate::{blocks::{BlockMap, Block, standard_blocks::StandardBlock}, step::{Step, ReplaceStep, ReplaceSlice}, utilities::{find_root_block_id, get_standard_blocks_in_order}};
+
+use super::{selection::{Selection, SubSelection}, event::FindReplaceEvent, regex_search::find_regex_matches, StepError};
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool // query is a regular expression & replace text can use "$1" for capture groups
+}
+
+/// The text of a standard block's inline blocks joined together,
+/// so matches can span several inline blocks with different marks
+pub struct BlockText {
+    pub block_id: String,
+    pub text: Vec<u16>,
+    /// (inline block id, offset of the inline block's text in text)
+    pub inline_blocks: Vec<(String, usize)>
+}
+
+impl BlockText {
+    pub fn from(block: &StandardBlock, block_map: &BlockMap) -> Result<Self, StepError> {
+        let mut text = vec![];
+        let mut inline_blocks = vec![];
+        for inline_block in block.get_inline_blocks(block_map)? {
+            inline_blocks.push((inline_block.id(), text.len()));
+            text.extend(inline_block.text()?.0.iter());
+        }
+        return Ok(Self { block_id: block.id(), text, inline_blocks })
+    }
+
+    /// Position of the char at raw_offset (in the inline block that contains the char)
+    pub fn position_of_char(&self, raw_offset: usize) -> SubSelection {
+        let (block_id, start) = self.inline_blocks.iter()
+            .enumerate()
+            .find(|(i, (_, start))| *start <= raw_offset && raw_offset < self.end_of_inline_block(*i))
+            .map(|(_, inline_block)| inline_block)
+            .unwrap_or(&self.inline_blocks[self.inline_blocks.len() - 1]);
+        return SubSelection { block_id: block_id.clone(), offset: raw_offset - start, subselection: None }
+    }
+
+    /// Position directly after the char before raw_offset (in the inline block that contains that char)
+    pub fn position_after_char(&self, raw_offset: usize) -> SubSelection {
+        let (block_id, start) = self.inline_blocks.iter()
+            .enumerate()
+            .find(|(i, (_, start))| *start < raw_offset && raw_offset <= self.end_of_inline_block(*i))
+            .map(|(_, inline_block)| inline_block)
+            .unwrap_or(&self.inline_blocks[0]);
+        return SubSelection { block_id: block_id.clone(), offset: raw_offset - start, subselection: None }
+    }
+
+    fn end_of_inline_block(&self, i: usize) -> usize {
+        return match self.inline_blocks.get(i + 1) {
+            Some((_, next_start)) => *next_start,
+            None => self.text.len()
+        }
+    }
+}
+
+/// A match found in the text of one standard block
+pub struct SearchMatch {
+    /// Index of the standard block in document order
+    pub block_index: usize,
+    /// Raw offsets in the text of all the standard block's inline blocks
+    pub range: Range<usize>,
+    pub selection: Selection,
+    /// Text the match is replaced with ("$n" & "${n}" capture groups are expanded for regex search)
+    pub replacement: String
+}
+
+/// Finds every match of the query in the page, in document order
+/// -> anchor is on the first char of the match & head is after the last char
+pub fn find_matches(query: &str, root_block_id: &str, options: &SearchOptions, block_map: &BlockMap) -> Result<Vec<Selection>, StepError> {
+    return Ok(find_search_matches(query, "", root_block_id, options, block_map)?
+        .into_iter()
+        .map(|search_match| search_match.selection)
+        .collect())
+}
+
+pub fn find_search_matches(
+    query: &str,
+    replace_with: &str,
+    root_block_id: &str,
+    options: &SearchOptions,
+    block_map: &BlockMap
+) -> Result<Vec<SearchMatch>, StepError> {
+    if options.regex {
+        return find_regex_matches(query, replace_with, root_block_id, options, block_map)
+    }
+    let query: Vec<u16> = query.encode_utf16().collect();
+    let mut matches = vec![];
+    if query.len() == 0 {
+        return Ok(matches)
+    }
+    for (block_index, block_text) in get_block_texts_in_order(root_block_id, block_map)?.iter().enumerate() {
+        for range in find_in_text(&block_text.text, &query, options) {
+            matches.push(SearchMatch {
+                block_index,
+                selection: Selection {
+                    anchor: block_text.position_of_char(range.start),
+                    head: block_text.position_after_char(range.end)
+                },
+                range,
+                replacement: replace_with.to_string()
+            });
+        }
+    }
+    return Ok(matches)
+}
+
+/// Replace text always goes in the inline block of the first replaced char, so it keeps that char's marks
+pub fn generate_replace_match_step(search_match: &Selection, replace_with: &str, block_map: &BlockMap) -> Result<Step, StepError> {
+    let inline_block = block_map.get_inline_block(&search_match.anchor.block_id)?;
+    return Ok(Step::ReplaceStep(ReplaceStep {
+        block_id: inline_block.parent,
+        from: search_match.anchor.clone(),
+        to: search_match.head.clone(),
+        slice: ReplaceSlice::String(replace_with.to_string())
+    }))
+}
+
+/// All the steps are generated from one event, so they're applied (& undone) together
+/// -> replace: the first match at or after the start of the selection (wrapping back to the top of the page)
+/// -> replace all: every match in the page, last match first, so the positions of earlier matches stay valid
+pub fn generate_find_replace_steps(event: &FindReplaceEvent, from: &SubSelection, block_map: &BlockMap) -> Result<Vec<Step>, StepError> {
+    let caret = from.get_deepest_subselection();
+    let root_block_id = find_root_block_id(&caret.block_id, block_map)
+        .ok_or(StepError(format!("Could not find the root of block: {}", caret.block_id)))?;
+    let matches = find_search_matches(&event.query, &event.replace_with, &root_block_id, &event.options, block_map)?;
+
+    if event.replace_all {
+        return matches.iter().rev()
+            .map(|search_match| generate_replace_match_step(&search_match.selection, &search_match.replacement, block_map))
+            .collect()
+    }
+
+    let (caret_block_index, caret_offset) = get_caret_position(caret, &root_block_id, block_map)?;
+    let next_match = matches.iter()
+        .find(|search_match| (search_match.block_index, search_match.range.start) >= (caret_block_index, caret_offset))
+        .or(matches.first());
+    return match next_match {
+        Some(search_match) => Ok(vec![generate_replace_match_step(&search_match.selection, &search_match.replacement, block_map)?]),
+        None => Ok(vec![])
+    }
+}
+
+/// Standard blocks with text, in document order
+pub fn get_block_texts_in_order(root_block_id: &str, block_map: &BlockMap) -> Result<Vec<BlockText>, StepError> {
+    let mut block_texts = vec![];
+    for block in get_standard_blocks_in_order(&root_block_id.to_string(), block_map)? {
+        if block.has_content() && block.content_block()?.inline_blocks.len() > 0 {
+            block_texts.push(BlockText::from(&block, block_map)?);
+        }
+    }
+    return Ok(block_texts)
+}
+
+/// Non overlapping matches, left to right
+fn find_in_text(text: &Vec<u16>, query: &Vec<u16>, options: &SearchOptions) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut i = 0;
+    while i + query.len() <= text.len() {
+        let is_match = text[i..i + query.len()].iter().zip(query.iter())
+            .all(|(a, b)| match options.case_sensitive {
+                true => a == b,
+                false => fold_case(*a) == fold_case(*b)
+            });
+        let is_whole_word = !options.whole_word || (
+            (i == 0 || !is_word_char(text[i - 1]))
+            && (i + query.len() == text.len() || !is_word_char(text[i + query.len()]))
+        );
+        if is_match && is_whole_word {
+            ranges.push(i..i + query.len());
+            i += query.len();
+        } else {
+            i += 1;
+        }
+    }
+    return ranges
+}
+
+/// Lowercase a utf16 code unit, if it's lowercase form is also one code unit
+fn fold_case(code_unit: u16) -> u16 {
+    let c = match char::from_u32(code_unit as u32) {
+        Some(c) => c,
+        None => return code_unit // part of a surrogate pair
+    };
+    let mut lowercase = c.to_lowercase();
+    return match (lowercase.next(), lowercase.next()) {
+        (Some(lower), None) if lower.len_utf16() == 1 => lower as u32 as u16,
+        _ => code_unit
+    }
+}
+
+fn is_word_char(code_unit: u16) -> bool {
+    return match char::from_u32(code_unit as u32) {
+        Some(c) => c.is_alphanumeric() || c == '_',
+        None => true
+    }
+}
+
+/// (index of the caret's standard block in document order, raw offset in the standard block's text)
+fn get_caret_position(caret: &SubSelection, root_block_id: &str, block_map: &BlockMap) -> Result<(usize, usize), StepError> {
+    let block_texts = get_block_texts_in_order(root_block_id, block_map)?;
+    let (block_id, raw_offset) = match block_map.get_block(&caret.block_id)? {
+        Block::InlineBlock(inline_block) => {
+            let raw_selection = caret.to_raw_selection(block_map)?;
+            (inline_block.parent, raw_selection.offset)
+        },
+        block => (block.id(), 0)
+    };
+    return Ok(match block_texts.iter().position(|block_text| block_text.block_id == block_id) {
+        Some(block_index) => (block_index, raw_offset),
+        None => (0, 0)
+    })
+}
@@ -1,13 +1,27 @@
-use crate::{blocks::{BlockMap, Block, standard_blocks::{StandardBlockType, content_block::ContentBlock}}, step::{Step, ReplaceStep, ReplaceSlice, TurnInto}};
+use crate::{blocks::{BlockMap, Block, standard_blocks::{StandardBlockType, content_block::ContentBlock}}, step::{Step, ReplaceStep, ReplaceSlice, TurnInto, SuggestStep},
+    mark::SuggestionMark, new_ids::NewIds, utilities::suggestions::{is_insertion_by, adjacent_suggestion_id}};
 
-use super::{selection::SubSelection, StepError};
+use super::{selection::SubSelection, StepError, input_rules::generate_input_rule_steps};
 
+/// A single typed character can complete an input rule, which replaces the plain replace step
 pub fn generate_replace_selected_steps(
     block_map: &BlockMap,
     from: SubSelection,
     to: SubSelection,
-    replace_with: String
+    replace_with: String,
+    new_ids: &mut NewIds
 ) -> Result<Vec<Step>, StepError> {
+    let mut chars = replace_with.chars();
+    if let (Some(key), None) = (chars.next(), chars.next()) {
+        if let Some(steps) = generate_input_rule_steps(block_map, &from, &to, key, new_ids)? {
+            return Ok(steps)
+        }
+    }
+    return replace_selected_steps(block_map, from, to, replace_with)
+}
+
+/// The plain replace, without input rules
+fn replace_selected_steps(block_map: &BlockMap, from: SubSelection, to: SubSelection, replace_with: String) -> Result<Vec<Step>, StepError> {
     return match block_map.get_block(&from.block_id)? {
         Block::InlineBlock(inline_block) => Ok(vec![
             Step::ReplaceStep(ReplaceStep {
@@ -39,3 +53,47 @@ pub fn generate_replace_selected_steps(
         Block::Root(_) => return Err(StepError("Cannot perform replace step on root".to_string()))
     }
 }
+
+/// Suggestion mode version of generate_replace_selected_steps:
+/// -> edits inside the author's own pending insertion change the text directly
+/// -> otherwise the selected text is suggested for deletion & the new text is suggested as an insertion,
+/// continuing the author's suggestion next to the selection if there is one
+/// Selections of whole blocks aren't text, so they are replaced directly
+/// Input rules don't run, since their steps would bypass the suggestion
+pub fn generate_suggest_replace_selected_steps(
+    block_map: &BlockMap,
+    from: SubSelection,
+    to: SubSelection,
+    replace_with: String,
+    author: &String,
+    new_ids: &mut NewIds
+) -> Result<Vec<Step>, StepError> {
+    let from_block = block_map.get_block(&from.get_deepest_subselection().block_id)?;
+    let from_inline_block = match from_block {
+        Block::InlineBlock(inline_block) => inline_block,
+        _ => return replace_selected_steps(block_map, from, to, replace_with)
+    };
+    if from.get_deepest_subselection().block_id == to.get_deepest_subselection().block_id && is_insertion_by(&from_inline_block, author) {
+        return replace_selected_steps(block_map, from, to, replace_with)
+    }
+    if from == to && replace_with.len() == 0 {
+        return Ok(vec![])
+    }
+
+    let suggestion_id = match adjacent_suggestion_id(&from, &to, author, block_map)? {
+        Some(suggestion_id) => suggestion_id,
+        None => new_ids.get_id()?
+    };
+    return Ok(vec![
+        Step::Suggest(SuggestStep {
+            block_id: block_map.get_block(&from.block_id)?.parent()?,
+            from,
+            to,
+            text: replace_with,
+            suggestion: SuggestionMark { id: suggestion_id, author: author.clone() },
+            from_new_inline_id: new_ids.get_id()?,
+            to_new_inline_id: new_ids.get_id()?,
+            new_inline_id: new_ids.get_id()?
+        })
+    ])
+}
@@ -0,0 +1,168 @@
+use crate::{blocks::{BlockMap, Block, inline_blocks::InlineBlock, standard_blocks::{StandardBlockType, content_block::ContentBlock, list_block::ListBlock}},
+    step::{Step, ReplaceStep, ReplaceSlice, TurnInto, MarkStep}, mark::Mark, new_ids::NewIds};
+
+use super::{selection::SubSelection, StepError};
+
+/// Markdown style shortcuts that are applied as the user types.
+/// Returns None if the key press does not complete an input rule
+/// -> "# ", "## ", "### " at the start of a paragraph -> heading 1, 2, 3
+/// -> "- ", "* " -> dotpoint list, "1. " -> numbered list, "[] " -> to-do list, "> " -> arrow list
+/// -> "**text**" -> bold, "*text*" -> italic, "~~text~~" -> strikethrough
+pub fn generate_input_rule_steps(
+    block_map: &BlockMap,
+    from: &SubSelection,
+    to: &SubSelection,
+    key: char,
+    new_ids: &mut NewIds
+) -> Result<Option<Vec<Step>>, StepError> {
+    if from != to {
+        return Ok(None)
+    }
+    let inline_block = match block_map.get_block(&from.block_id)? {
+        Block::InlineBlock(inline_block) => inline_block,
+        _ => return Ok(None)
+    };
+
+    if key == ' ' {
+        return generate_block_input_rule_steps(inline_block, from.offset, block_map)
+    }
+    return generate_mark_input_rule_steps(inline_block, from.offset, key, new_ids)
+}
+
+fn generate_block_input_rule_steps(inline_block: InlineBlock, offset: usize, block_map: &BlockMap) -> Result<Option<Vec<Step>>, StepError> {
+    let parent_block = block_map.get_standard_block(&inline_block.parent)?;
+    match parent_block.content {
+        StandardBlockType::Paragraph(_) => {},
+        _ => return Ok(None)
+    };
+    if parent_block.index_of(&inline_block._id)? != 0 {
+        return Ok(None)
+    }
+
+    let typed_prefix = inline_block.text()?.slice(0..offset).to_string();
+    let new_block_type = match block_type_for_prefix(&typed_prefix) {
+        Some(new_block_type) => new_block_type,
+        None => return Ok(None)
+    };
+
+    // Turn into first so the selection from the replace step leaves the caret at the start of the block
+    return Ok(Some(vec![
+        Step::TurnInto(TurnInto { block_id: parent_block.id(), new_block_type }),
+        Step::ReplaceStep(ReplaceStep {
+            block_id: parent_block.id(),
+            from: SubSelection { block_id: inline_block.id(), offset: 0, subselection: None },
+            to: SubSelection { block_id: inline_block.id(), offset, subselection: None },
+            slice: ReplaceSlice::String("".to_string())
+        })
+    ]))
+}
+
+fn block_type_for_prefix(prefix: &str) -> Option<StandardBlockType> {
+    let list_block = || ListBlock { content: ContentBlock::new(vec![]), completed: false, start: None };
+    return match prefix {
+        "#" => Some(StandardBlockType::H1(ContentBlock::new(vec![]))),
+        "##" => Some(StandardBlockType::H2(ContentBlock::new(vec![]))),
+        "###" => Some(StandardBlockType::H3(ContentBlock::new(vec![]))),
+        "-" | "*" => Some(StandardBlockType::DotPointList(list_block())),
+        "1." => Some(StandardBlockType::NumberedList(list_block())),
+        "[]" => Some(StandardBlockType::TodoList(list_block())),
+        ">" => Some(StandardBlockType::ArrowList(list_block())),
+        _ => None
+    }
+}
+
+/// The typed key is the last character of the closing delimiter, so it never gets inserted.
+/// -> delete the part of the closing delimiter that has already been typed
+/// -> delete the opening delimiter
+/// -> add the mark to the text between the delimiters
+/// -> place the caret at the end of the newly marked text
+fn generate_mark_input_rule_steps(inline_block: InlineBlock, offset: usize, key: char, new_ids: &mut NewIds) -> Result<Option<Vec<Step>>, StepError> {
+    let text = &inline_block.text()?.0;
+    let rules = [("**", Mark::Bold), ("~~", Mark::Strikethrough), ("*", Mark::Italic)];
+    for (delimiter, mark) in rules {
+        if !delimiter.ends_with(key) || inline_block.marks.iter().any(|m| m.is_same_type(&mark)) {
+            continue;
+        }
+        let (opening_index, closing_index) = match find_delimited_text(text, offset, delimiter) {
+            Some(indexes) => indexes,
+            None => continue
+        };
+        let delimiter_len = delimiter.len();
+        let marked_text_len = closing_index - opening_index - delimiter_len;
+        let from_new_inline_id = new_ids.get_id()?;
+        let to_new_inline_id = new_ids.get_id()?;
+        return Ok(Some(vec![
+            Step::ReplaceStep(ReplaceStep {
+                block_id: inline_block.parent.clone(),
+                from: SubSelection { block_id: inline_block.id(), offset: closing_index, subselection: None },
+                to: SubSelection { block_id: inline_block.id(), offset, subselection: None },
+                slice: ReplaceSlice::String("".to_string())
+            }),
+            Step::ReplaceStep(ReplaceStep {
+                block_id: inline_block.parent.clone(),
+                from: SubSelection { block_id: inline_block.id(), offset: opening_index, subselection: None },
+                to: SubSelection { block_id: inline_block.id(), offset: opening_index + delimiter_len, subselection: None },
+                slice: ReplaceSlice::String("".to_string())
+            }),
+            Step::AddMarkStep(MarkStep {
+                block_id: inline_block.parent.clone(),
+                from: SubSelection { block_id: inline_block.id(), offset: opening_index, subselection: None },
+                to: SubSelection { block_id: inline_block.id(), offset: opening_index + marked_text_len, subselection: None },
+                mark,
+                from_new_inline_id: from_new_inline_id.clone(),
+                to_new_inline_id
+            }),
+            Step::ReplaceStep(ReplaceStep {
+                block_id: inline_block.parent.clone(),
+                from: SubSelection { block_id: from_new_inline_id.clone(), offset: marked_text_len, subselection: None },
+                to: SubSelection { block_id: from_new_inline_id, offset: marked_text_len, subselection: None },
+                slice: ReplaceSlice::String("".to_string())
+            })
+        ]))
+    }
+    return Ok(None)
+}
+
+/// Returns the index of the opening delimiter & the index where the closing delimiter starts,
+/// assuming the last character of the delimiter is about to be typed at the offset.
+/// The text between the delimiters can't be empty or start / end with whitespace
+fn find_delimited_text(text: &Vec<u16>, offset: usize, delimiter: &str) -> Option<(usize, usize)> {
+    let delimiter: Vec<u16> = delimiter.encode_utf16().collect();
+    let delimiter_char = delimiter[0];
+    let already_typed = delimiter.len() - 1;
+    if offset < already_typed || offset > text.len() {
+        return None
+    }
+    let closing_index = offset - already_typed;
+    if text[closing_index..offset] != delimiter[..already_typed] {
+        return None
+    }
+    // Single character delimiters (ie. italic) shouldn't match part of a double delimiter
+    if delimiter.len() == 1 && closing_index > 0 && text[closing_index - 1] == delimiter_char {
+        return None
+    }
+    if closing_index < delimiter.len() + 1 {
+        return None
+    }
+
+    let mut i = closing_index - delimiter.len() - 1;
+    loop {
+        if text[i..i + delimiter.len()] == delimiter[..] {
+            let is_part_of_longer_delimiter = delimiter.len() == 1 && (
+                (i > 0 && text[i - 1] == delimiter_char) || text[i + 1] == delimiter_char
+            );
+            if !is_part_of_longer_delimiter {
+                let marked_text = &text[i + delimiter.len()..closing_index];
+                let is_whitespace = |c: &u16| *c == ' ' as u16;
+                if is_whitespace(&marked_text[0]) || is_whitespace(&marked_text[marked_text.len() - 1]) {
+                    return None
+                }
+                return Some((i, closing_index))
+            }
+        }
+        if i == 0 {
+            return None
+        }
+        i -= 1;
+    }
+}
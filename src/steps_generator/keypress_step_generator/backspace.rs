@@ -1,10 +1,12 @@
-use crate::{blocks::{BlockMap, Block, inline_blocks::InlineBlock, standard_blocks::{StandardBlock}}, steps_generator::{selection::SubSelection, StepError, generate_replace_selected_steps::generate_replace_selected_steps, turn_into::turn_into_paragraph_step}, step::{Step, ReplaceStep, ReplaceSlice, TurnToParent}};
+use crate::{blocks::{BlockMap, Block, inline_blocks::InlineBlock, standard_blocks::{StandardBlock, StandardBlockType, custom_block::BackspaceBehaviour}}, steps_generator::{selection::SubSelection, StepError, generate_replace_selected_steps::{generate_replace_selected_steps, generate_suggest_replace_selected_steps}, turn_into::turn_into_paragraph_step}, step::{Step, ReplaceStep, ReplaceSlice, TurnToParent},
+    new_ids::NewIds, utilities::suggestions::closest_undeleted_char};
 
 
 pub fn generate_steps_for_backspace(
     block_map: &BlockMap,
     mut from: SubSelection,
-    mut to: SubSelection
+    mut to: SubSelection,
+    new_ids: &mut NewIds
 ) -> Result<Vec<Step>, StepError> {
     let from_block = block_map.get_block(&from.block_id)?;
     match from_block {
@@ -13,7 +15,11 @@ pub fn generate_steps_for_backspace(
                 if from.offset == 0 { // at start of block
                     if from_block.index(block_map)? == 0 { // caret at start of standard block
                         let std_block = block_map.get_inline_block(&from.block_id)?.get_parent(block_map)?;
-                        if std_block.is_list() {
+                        let backspaces_like_a_list = match &std_block.content {
+                            StandardBlockType::Custom(custom_block) => custom_block.block_type()?.backspace_behaviour() == BackspaceBehaviour::TurnIntoParagraph,
+                            _ => std_block.is_list()
+                        };
+                        if backspaces_like_a_list {
                             return turn_into_paragraph_step(std_block.id())
                         } else {
                             return caret_at_start_of_parent_block_steps(from_block, block_map)
@@ -32,9 +38,9 @@ pub fn generate_steps_for_backspace(
                     from.offset -= 1;
                 }
             }
-            return generate_replace_selected_steps(block_map, from, to, "".to_string())
+            return generate_replace_selected_steps(block_map, from, to, "".to_string(), new_ids)
         },
-        Block::StandardBlock(_) => return generate_replace_selected_steps(block_map, from, to, "".to_string()),
+        Block::StandardBlock(_) => return generate_replace_selected_steps(block_map, from, to, "".to_string(), new_ids),
         Block::Root(_) => return Err(StepError("Cannot perform a backspace operation on a root block".to_string()))
     }
 }
@@ -67,4 +73,31 @@ fn caret_at_start_of_parent_block_steps(from_block: InlineBlock, block_map: &Blo
             None => Ok(vec![])
         }
     }
-}
\ No newline at end of file
+}
+
+/// Suggestion mode backspace: suggests deleting the closest character before the caret that isn't already deleted.
+/// Joining blocks isn't suggested, so this does nothing at the start of a block
+pub fn generate_steps_for_suggested_backspace(
+    block_map: &BlockMap,
+    from: SubSelection,
+    to: SubSelection,
+    author: &String,
+    new_ids: &mut NewIds
+) -> Result<Vec<Step>, StepError> {
+    if from != to {
+        return generate_suggest_replace_selected_steps(block_map, from, to, "".to_string(), author, new_ids)
+    }
+    let raw_selection = from.to_raw_selection(block_map)?;
+    let std_block = block_map.get_standard_block(&raw_selection.block_id)?;
+    return match closest_undeleted_char(&std_block, raw_selection.offset, true, block_map)? {
+        Some((inline_block, offset)) => generate_suggest_replace_selected_steps(
+            block_map,
+            SubSelection::from(inline_block.id(), offset, None),
+            SubSelection::from(inline_block.id(), offset + 1, None),
+            "".to_string(),
+            author,
+            new_ids
+        ),
+        None => Ok(vec![])
+    }
+}
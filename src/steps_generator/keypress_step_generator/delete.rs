@@ -1,11 +1,13 @@
-use crate::{blocks::{BlockMap, Block}, steps_generator::{selection::SubSelection, StepError, generate_replace_selected_steps::generate_replace_selected_steps}, step::Step};
+use crate::{blocks::{BlockMap, Block}, steps_generator::{selection::SubSelection, StepError, generate_replace_selected_steps::{generate_replace_selected_steps, generate_suggest_replace_selected_steps}}, step::Step,
+    new_ids::NewIds, utilities::suggestions::closest_undeleted_char};
 
 use super::backspace::generate_steps_for_backspace;
 
 pub fn generate_steps_for_delete(
     block_map: &BlockMap,
     mut from: SubSelection,
-    mut to: SubSelection
+    mut to: SubSelection,
+    new_ids: &mut NewIds
 ) -> Result<Vec<Step>, StepError>{
     let from_block = block_map.get_block(&from.block_id)?;
     match from_block {
@@ -26,7 +28,7 @@ pub fn generate_steps_for_delete(
                         offset: 0,
                         subselection: None
                     };
-                    return generate_steps_for_backspace(block_map, new_subselection.clone(), new_subselection)
+                    return generate_steps_for_backspace(block_map, new_subselection.clone(), new_subselection, new_ids)
                 } else { // caret at end of inline block that is not the last inline in it's parent
                     let next_inline_block = from_block.next_block(block_map)?;
                     from = SubSelection {
@@ -40,9 +42,36 @@ pub fn generate_steps_for_delete(
             } else { // somewhere inside block
                 to.offset += 1;
             }
-            return generate_replace_selected_steps(block_map, from, to, "".to_string())
+            return generate_replace_selected_steps(block_map, from, to, "".to_string(), new_ids)
         },
-        Block::StandardBlock(_) => return generate_replace_selected_steps(block_map, from, to, "".to_string()),
+        Block::StandardBlock(_) => return generate_replace_selected_steps(block_map, from, to, "".to_string(), new_ids),
         Block::Root(_) => return Err(StepError("Cannot perform a delete operation on a root block".to_string()))
     }
-}
\ No newline at end of file
+}
+
+/// Suggestion mode delete: suggests deleting the closest character after the caret that isn't already deleted.
+/// Joining blocks isn't suggested, so this does nothing at the end of a block
+pub fn generate_steps_for_suggested_delete(
+    block_map: &BlockMap,
+    from: SubSelection,
+    to: SubSelection,
+    author: &String,
+    new_ids: &mut NewIds
+) -> Result<Vec<Step>, StepError> {
+    if from != to {
+        return generate_suggest_replace_selected_steps(block_map, from, to, "".to_string(), author, new_ids)
+    }
+    let raw_selection = from.to_raw_selection(block_map)?;
+    let std_block = block_map.get_standard_block(&raw_selection.block_id)?;
+    return match closest_undeleted_char(&std_block, raw_selection.offset, false, block_map)? {
+        Some((inline_block, offset)) => generate_suggest_replace_selected_steps(
+            block_map,
+            SubSelection::from(inline_block.id(), offset, None),
+            SubSelection::from(inline_block.id(), offset + 1, None),
+            "".to_string(),
+            author,
+            new_ids
+        ),
+        None => Ok(vec![])
+    }
+}
@@ -2,19 +2,35 @@
 
 
 
-use crate::{step::{Step, SplitStep, AddBlockStep}, blocks::{BlockMap, standard_blocks::{StandardBlockType, content_block::ContentBlock}}, steps_generator::{selection::SubSelection, StepError, generate_replace_selected_steps::generate_replace_selected_steps, turn_into::turn_into_paragraph_step}, utilities::caret_is_at_start_of_block, new_ids::NewIds};
+use crate::{step::{Step, SplitStep, AddBlockStep}, blocks::{BlockMap, standard_blocks::{StandardBlockType, content_block::ContentBlock, toggle_block::ToggleBlock, custom_block::EnterBehaviour}}, steps_generator::{selection::SubSelection, StepError, generate_replace_selected_steps::generate_replace_selected_steps, turn_into::turn_into_paragraph_step}, utilities::{caret_is_at_start_of_block, caret_is_at_end_of_block}, new_ids::NewIds};
 
 pub fn generate_steps_for_enter(block_map: &BlockMap, from: SubSelection, to: SubSelection, new_ids: &mut NewIds) -> Result<Vec<Step>, StepError> {
     let mut steps = vec![];
     if from != to {
-        let delete_selected_steps = generate_replace_selected_steps(block_map, from.clone(), to, "".to_string())?;
+        let delete_selected_steps = generate_replace_selected_steps(block_map, from.clone(), to, "".to_string(), new_ids)?;
         for step in delete_selected_steps {
             steps.push(step);
         }
     }  else {
         let std_block = block_map.get_inline_block(&from.block_id)?.get_parent(block_map)?;
-        if std_block.is_list() && std_block.text_is_empty(block_map)? {
+        let enters_like_a_list = match &std_block.content {
+            StandardBlockType::Custom(custom_block) => custom_block.block_type()?.enter_behaviour() == EnterBehaviour::NewBlockOfSameType,
+            _ => std_block.is_list()
+        };
+        if enters_like_a_list && std_block.text_is_empty(block_map)? {
             return turn_into_paragraph_step(std_block.id())
+        } else if std_block.is_collapsed() && caret_is_at_end_of_block(&from, &to, block_map)? {
+            // hidden children stay with the toggle -> new toggle gets added below as a sibling
+            return Ok(vec![
+                Step::AddBlock(AddBlockStep {
+                    block_id: std_block.parent(),
+                    child_offset: std_block.index(block_map)? + 1,
+                    block_type: StandardBlockType::ToggleList(ToggleBlock::new()),
+                    focus_block_below: false,
+                    new_std_block_id: new_ids.get_id()?,
+                    new_inline_block_id: new_ids.get_id()?
+                })
+            ])
         } else if caret_is_at_start_of_block(&from, &to, block_map)? {
             let inline_block = block_map.get_inline_block(&from.block_id)?;
             let std_block = inline_block.get_parent(block_map)?;
@@ -1,14 +1,15 @@
 
 use crate::{blocks::{BlockMap}, step::Step, mark::Mark, custom_copy::CustomCopy, new_ids::NewIds};
 
-use self::{backspace::generate_steps_for_backspace, enter::generate_steps_for_enter, tab::generate_steps_for_tab, delete::generate_steps_for_delete, };
+use self::{backspace::{generate_steps_for_backspace, generate_steps_for_suggested_backspace}, enter::generate_steps_for_enter, tab::generate_steps_for_tab, delete::{generate_steps_for_delete, generate_steps_for_suggested_delete}, move_block::generate_steps_for_move_block};
 
-use super::{event::{KeyPress, Key}, selection::{SubSelection}, StepError, mark_steps::generate_mark_steps, generate_replace_selected_steps::generate_replace_selected_steps, clipboard_steps::{generate_cut_steps, generate_paste_steps}};
+use super::{event::{KeyPress, Key}, selection::{SubSelection}, StepError, mark_steps::generate_mark_steps, generate_replace_selected_steps::{generate_replace_selected_steps, generate_suggest_replace_selected_steps}, smart_typography::{generate_smart_typography_steps, generate_revert_smart_typography_steps}, clipboard_steps::{generate_cut_steps, generate_paste_steps, generate_suggested_cut_steps, generate_suggested_paste_steps}};
 
 pub mod backspace;
 pub mod enter;
 pub mod tab;
 pub mod delete;
+pub mod move_block;
 
 pub fn generate_keyboard_event_steps(
     key_press: &KeyPress,
@@ -18,6 +19,20 @@ pub fn generate_keyboard_event_steps(
     copy: &CustomCopy,
     new_ids: &mut NewIds
 ) -> Result<Vec<Step>, StepError> {
+    // in suggestion mode typing, deleting, cutting & pasting text become suggestions,
+    // enter would split the block so it does nothing, everything else is unchanged
+    if let Some(author) = &key_press.metadata.suggesting_as {
+        let is_shortcut = key_press.metadata.ctrl_down || key_press.metadata.meta_down;
+        match key_press.key {
+            Key::Standard('x') | Key::Standard('X') if is_shortcut => return generate_suggested_cut_steps(from, to, block_map, author, new_ids),
+            Key::Standard('v') | Key::Standard('V') if is_shortcut => return generate_suggested_paste_steps(from, to, block_map, copy.clone(), author, new_ids),
+            Key::Standard(key) if !is_shortcut => return generate_suggest_replace_selected_steps(block_map, from, to, key.to_string(), author, new_ids),
+            Key::Backspace => return generate_steps_for_suggested_backspace(block_map, from, to, author, new_ids),
+            Key::Delete => return generate_steps_for_suggested_delete(block_map, from, to, author, new_ids),
+            Key::Enter => return Ok(vec![]),
+            _ => {}
+        };
+    }
     return match key_press.key {
         //Shortcuts
         Key::Standard('b') | Key::Standard('B') if key_press.metadata.ctrl_down || key_press.metadata.meta_down =>
@@ -29,17 +44,29 @@ pub fn generate_keyboard_event_steps(
         Key::Standard('c') | Key::Standard('C') if key_press.metadata.ctrl_down || key_press.metadata.meta_down =>
             Ok(vec![Step::Copy(from, to)]),
         Key::Standard('x') | Key::Standard('X') if key_press.metadata.ctrl_down || key_press.metadata.meta_down =>
-            generate_cut_steps(from, to, block_map),
+            generate_cut_steps(from, to, block_map, new_ids),
         Key::Standard('v') | Key::Standard('V') if key_press.metadata.ctrl_down || key_press.metadata.meta_down =>
-            generate_paste_steps(from, to, block_map, copy.clone()),
+            generate_paste_steps(from, to, block_map, copy.clone(), new_ids),
         Key::Standard('z') | Key::Standard('Z') if key_press.metadata.ctrl_down || key_press.metadata.meta_down =>
             unimplemented!(),
         //standard press
-        Key::Standard(key) => generate_replace_selected_steps(block_map, from, to, key.to_string()),
-        Key::Backspace => generate_steps_for_backspace(block_map, from, to),
-        Key::Delete => generate_steps_for_delete(block_map, from, to),
+        Key::Standard(key) if key_press.metadata.smart_typography => match generate_smart_typography_steps(block_map, &from, &to, key)? {
+            Some(steps) => Ok(steps),
+            None => generate_replace_selected_steps(block_map, from, to, key.to_string(), new_ids)
+        },
+        Key::Standard(key) => generate_replace_selected_steps(block_map, from, to, key.to_string(), new_ids),
+        Key::Backspace if key_press.metadata.smart_typography => match generate_revert_smart_typography_steps(block_map, &from, &to, key_press.metadata.last_conversion.clone())? {
+            Some(steps) => Ok(steps),
+            None => generate_steps_for_backspace(block_map, from, to, new_ids)
+        },
+        Key::Backspace => generate_steps_for_backspace(block_map, from, to, new_ids),
+        Key::Delete => generate_steps_for_delete(block_map, from, to, new_ids),
         Key::Enter => generate_steps_for_enter(block_map, from, to, new_ids),
         Key::Tab => generate_steps_for_tab(block_map, from, to, key_press.metadata.clone()),
+        Key::ArrowUp | Key::ArrowDown if key_press.metadata.shift_down && (key_press.metadata.alt_down || key_press.metadata.meta_down) =>
+            generate_steps_for_move_block(block_map, from, to, matches!(key_press.key, Key::ArrowUp)),
+        // caret movement is left to the browser
+        Key::ArrowUp | Key::ArrowDown => Ok(vec![]),
         _ => unimplemented!(),
     }
 }
@@ -0,0 +1,83 @@
+use crate::{blocks::{BlockMap, Block, standard_blocks::{StandardBlock, StandardBlockType}}, steps_generator::{selection::SubSelection, StepError}, step::{Step, MoveBlocksStep}};
+
+/// Moves the standard block at the caret (or the range of selected sibling blocks) up or down
+/// -> swap with the previous / next sibling (children move with their parent)
+/// -> if already the first / last child -> escape into the parent's level, above / below the parent
+/// -> if already at the top / bottom of the root -> no steps
+pub fn generate_steps_for_move_block(
+    block_map: &BlockMap,
+    from: SubSelection,
+    to: SubSelection,
+    move_up: bool
+) -> Result<Vec<Step>, StepError> {
+    let from_block = block_map.get_nearest_ancestor_standard_block_incl_self(&from.get_deepest_subselection().block_id)?;
+    let to_block = block_map.get_nearest_ancestor_standard_block_incl_self(&to.get_deepest_subselection().block_id)?;
+    let (first_block, last_block) = get_sibling_range(from_block, to_block, block_map)?;
+
+    let parent = first_block.get_parent(block_map)?;
+    let first_index = first_block.index(block_map)?;
+    let last_index = last_block.index(block_map)?;
+    let block_ids = parent.children()?[first_index..last_index + 1].to_vec();
+
+    let (new_parent_id, child_offset) = if move_up && first_index > 0 {
+        (parent.id(), first_index - 1)
+    } else if !move_up && last_index + 1 < parent.children()?.len() {
+        // offset is after the blocks being moved have been removed from the parent
+        (parent.id(), first_index + 1)
+    } else {
+        match parent {
+            Block::StandardBlock(parent) => match parent.content {
+                StandardBlockType::Layout(_) => return Ok(vec![]),
+                _ => {
+                    let parent_index = parent.index(block_map)?;
+                    match move_up {
+                        true => (parent.parent.clone(), parent_index),
+                        false => (parent.parent.clone(), parent_index + 1)
+                    }
+                }
+            },
+            _ => return Ok(vec![])
+        }
+    };
+
+    return Ok(vec![Step::MoveBlocks(MoveBlocksStep {
+        block_ids,
+        new_parent_id,
+        child_offset,
+        from: from.get_deepest_subselection().clone(),
+        to: to.get_deepest_subselection().clone()
+    })])
+}
+
+/// Lifts the from & to blocks to the deepest level where they are siblings
+pub fn get_sibling_range(
+    from_block: StandardBlock,
+    to_block: StandardBlock,
+    block_map: &BlockMap
+) -> Result<(StandardBlock, StandardBlock), StepError> {
+    let to_ancestors = get_ancestors_incl_self(to_block, block_map)?;
+    let mut from_block = from_block;
+    loop {
+        for to_ancestor in &to_ancestors {
+            if to_ancestor.parent == from_block.parent {
+                return Ok((from_block, to_ancestor.clone()))
+            }
+        }
+        from_block = match from_block.parent_is_root(block_map) {
+            true => return Err(StepError("Could not find a common parent for the selected blocks".to_string())),
+            false => block_map.get_standard_block(&from_block.parent)?
+        };
+    }
+}
+
+fn get_ancestors_incl_self(block: StandardBlock, block_map: &BlockMap) -> Result<Vec<StandardBlock>, StepError> {
+    let mut ancestors = vec![block];
+    loop {
+        let youngest = &ancestors[ancestors.len() - 1];
+        if youngest.parent_is_root(block_map) {
+            return Ok(ancestors)
+        }
+        let parent = block_map.get_standard_block(&youngest.parent)?;
+        ancestors.push(parent);
+    }
+}
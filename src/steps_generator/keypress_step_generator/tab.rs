@@ -1,5 +1,5 @@
 
-use crate::{step::{Step, TurnToChild, TurnToParent}, steps_generator::{StepError, selection::SubSelection, event::{KeyPressMetadata}}, blocks::{BlockMap, Block, standard_blocks::StandardBlock}};
+use crate::{step::{Step, TurnToChild, TurnToParent}, steps_generator::{StepError, selection::SubSelection, event::{KeyPressMetadata}}, blocks::{BlockMap, Block, standard_blocks::StandardBlock, nesting_schema::with_nesting_schema}};
 
 
 pub fn generate_steps_for_tab(block_map: &BlockMap, from: SubSelection, to: SubSelection, key_press_metadata: KeyPressMetadata) -> Result<Vec<Step>, StepError> {
@@ -8,7 +8,7 @@ pub fn generate_steps_for_tab(block_map: &BlockMap, from: SubSelection, to: SubS
         Block::InlineBlock(inline_block) => {
             let parent_block = inline_block.get_parent(block_map)?;
             if key_press_metadata.shift_down {
-                return turn_to_parent_step_generator(parent_block.parent_is_root(block_map), parent_block)
+                return turn_to_parent_step_generator(block_map, parent_block)
             } else {
                 return turn_to_child_step_generator(block_map, parent_block)
             }
@@ -24,7 +24,7 @@ pub fn generate_steps_for_tab(block_map: &BlockMap, from: SubSelection, to: SubS
                 let std_block = block_map.get_standard_block(&parents_children[i])?;
                 let option_step;
                 if key_press_metadata.shift_down {
-                    option_step = turn_to_parent_step_generator(std_block.parent_is_root(block_map), std_block)?;
+                    option_step = turn_to_parent_step_generator(block_map, std_block)?;
                 } else {
                     option_step = turn_to_child_step_generator(block_map, std_block)?;
                 }
@@ -40,18 +40,27 @@ pub fn generate_steps_for_tab(block_map: &BlockMap, from: SubSelection, to: SubS
     }
 }
 
+/// No step if the block above can't have the block as a child (see NestingSchema)
 fn turn_to_child_step_generator(block_map: &BlockMap, std_block: StandardBlock) -> Result<Vec<Step>, StepError> {
-    if std_block.index(block_map)? == 0 {
+    let block_above = match std_block.get_previous(block_map)? {
+        Some(block_above) => block_above,
+        None => return Ok(vec![])
+    };
+    if !with_nesting_schema(|schema| schema.allows_child(&Block::StandardBlock(block_above), &std_block.content)) {
         return Ok(vec![])
-    } else {
-        return Ok(vec![
-            Step::TurnToChild(TurnToChild { block_id: std_block.id() })
-        ])
     }
+    return Ok(vec![
+        Step::TurnToChild(TurnToChild { block_id: std_block.id() })
+    ])
 }
 
-fn turn_to_parent_step_generator(parent_is_root: bool, std_block: StandardBlock) -> Result<Vec<Step>, StepError> {
-    if parent_is_root {
+/// No step if the grandparent can't have the block as a child (see NestingSchema)
+fn turn_to_parent_step_generator(block_map: &BlockMap, std_block: StandardBlock) -> Result<Vec<Step>, StepError> {
+    if std_block.parent_is_root(block_map) {
+        return Ok(vec![])
+    }
+    let grandparent = block_map.get_block(&std_block.get_parent(block_map)?.parent()?)?;
+    if !with_nesting_schema(|schema| schema.allows_child(&grandparent, &std_block.content)) {
         return Ok(vec![])
     } else {
         return Ok(vec![
@@ -1,16 +1,24 @@
 
-use crate::{blocks::{BlockMap}, step::{Step, DuplicateStep, AddParagraphAtBottomStep}, mark::Mark, custom_copy::CustomCopy, new_ids::NewIds};
+use crate::{blocks::{BlockMap}, step::{Step, DuplicateStep, AddParagraphAtBottomStep, SetListStartStep, ResolveSuggestionStep}, mark::Mark, custom_copy::CustomCopy, new_ids::NewIds};
 
-use self::{event::{Event, FormatBarEvent, ContextMenuEvent}, keypress_step_generator::{generate_keyboard_event_steps}, selection::{Selection}, mark_steps::generate_mark_steps, slash_scrim::generate_slash_scrim_steps, turn_into::generate_turn_into_step, clipboard_steps::{generate_cut_steps, generate_paste_steps}};
+use self::{event::{Event, FormatBarEvent, ContextMenuEvent}, keypress_step_generator::{generate_keyboard_event_steps}, selection::{Selection}, mark_steps::generate_mark_steps, slash_scrim::generate_slash_scrim_steps, sub_page::generate_turn_into_page_steps, find_replace::generate_find_replace_steps, turn_into::generate_turn_into_step, clipboard_steps::{generate_cut_steps, generate_paste_steps}, drop_block::generate_drop_block_steps, comments::generate_comment_steps, document_diff::generate_diff_steps};
 
 pub mod keypress_step_generator;
 pub mod selection;
 pub mod event;
 pub mod generate_replace_selected_steps;
+pub mod input_rules;
+pub mod smart_typography;
 pub mod mark_steps;
 pub mod slash_scrim;
+pub mod sub_page;
+pub mod find_replace;
+pub mod regex_search;
 pub mod turn_into;
 pub mod clipboard_steps;
+pub mod drop_block;
+pub mod document_diff;
+pub mod comments;
 
 #[derive(Debug, PartialEq)]
 pub struct StepError (pub String);
@@ -27,15 +35,18 @@ pub fn generate_steps(event: &Event, block_map: &BlockMap, selection: Selection,
             FormatBarEvent::ForeColor(color) => generate_mark_steps(Mark::ForeColor(color.clone()), from, to, block_map, new_ids),
             FormatBarEvent::BackColor(color) => generate_mark_steps(Mark::BackColor(color.clone()), from, to, block_map, new_ids),
             FormatBarEvent::TurnInto(new_block_type) => generate_turn_into_step(new_block_type, from, block_map),
+            FormatBarEvent::Custom(custom_mark) => generate_mark_steps(Mark::Custom(custom_mark.clone()), from, to, block_map, new_ids),
         },
         Event::ContextMenu(context_menu_event) => match context_menu_event {
             ContextMenuEvent::Copy => Ok(vec![Step::Copy(from, to)]),
-            ContextMenuEvent::Cut => generate_cut_steps(from, to, block_map),
-            ContextMenuEvent::Paste => generate_paste_steps(from, to, block_map, copy.clone()),
+            ContextMenuEvent::Cut => generate_cut_steps(from, to, block_map, new_ids),
+            ContextMenuEvent::Paste => generate_paste_steps(from, to, block_map, copy.clone(), new_ids),
+            ContextMenuEvent::TurnIntoPage => generate_turn_into_page_steps(from, to, block_map, new_ids),
         },
         Event::SlashScrim(slash_scrim_event) => generate_slash_scrim_steps(slash_scrim_event, from, to, block_map, new_ids),
         Event::ToggleCompleted(_id) => Ok(vec![Step::ToggleCompleted(_id.clone())]),
-        Event::DropBlock(drop_block_event) => Ok(vec![Step::DropBlock(drop_block_event.clone())]),
+        Event::ToggleCollapsed(_id) => Ok(vec![Step::ToggleCollapsed(_id.clone())]),
+        Event::DropBlock(drop_block_event) => generate_drop_block_steps(drop_block_event, block_map),
         Event::DeleteBlock(block_id) => Ok(vec![Step::DeleteBlock(block_id.clone())]),
         Event::Duplicate(block_id) => Ok(vec![Step::Duplicate(DuplicateStep {
             duplicate_block_id: block_id.clone(),
@@ -45,7 +56,26 @@ pub fn generate_steps(event: &Event, block_map: &BlockMap, selection: Selection,
         Event::AddParagraphAtBottom(root_block_id) => Ok(vec![Step::AddParagraphAtBottom(AddParagraphAtBottomStep {
             root_block_id: root_block_id.clone(),
             new_block_id: new_ids.get_id()?
-        })])
+        })]),
+        Event::SetListStart(set_list_start_event) => Ok(vec![Step::SetListStart(SetListStartStep {
+            block_id: set_list_start_event.block_id.clone(),
+            start: set_list_start_event.start
+        })]),
+        Event::ResizeLayoutColumns(resize_event) => Ok(vec![Step::ResizeLayoutColumns(resize_event.clone())]),
+        Event::FindReplace(find_replace_event) => generate_find_replace_steps(find_replace_event, &from, block_map),
+        Event::Comment(comment_event) => generate_comment_steps(comment_event, from, to, block_map, new_ids),
+        Event::Suggestion(suggestion_event) => {
+            let resolve_step = ResolveSuggestionStep {
+                root_block_id: suggestion_event.root_block_id.clone(),
+                suggestion_id: suggestion_event.suggestion_id.clone()
+            };
+            Ok(vec![match suggestion_event.accept {
+                true => Step::AcceptSuggestion(resolve_step),
+                false => Step::RejectSuggestion(resolve_step)
+            }])
+        },
+        // the snapshot's blocks are the target document, so ids that still exist are kept
+        Event::RestoreSnapshot(snapshot) => generate_diff_steps(&snapshot.root_block_id, block_map, &snapshot.block_map, new_ids)
     }
 }
 
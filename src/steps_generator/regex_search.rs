@@ -0,0 +1,72 @@
+use regex::RegexBuilder;
+
+use crate::blocks::BlockMap;
+
+use super::{find_replace::{SearchOptions, SearchMatch, BlockText, get_block_texts_in_order}, selection::{Selection, SubSelection}, StepError};
+
+/// Finds the matches of a regular expression in each standard block's text (all its inline blocks joined together)
+/// -> byte ranges are converted to raw utf16 offsets on the standard block (like to_raw_selection),
+/// then mapped back to inline block sub selections with real_selection_from_raw
+/// -> empty matches are ignored
+/// -> replace_with is expanded with each match's capture groups ("$1", "${name}", "$$" for a "$")
+pub fn find_regex_matches(
+    pattern: &str,
+    replace_with: &str,
+    root_block_id: &str,
+    options: &SearchOptions,
+    block_map: &BlockMap
+) -> Result<Vec<SearchMatch>, StepError> {
+    let pattern = match options.whole_word {
+        true => format!("\\b(?:{})\\b", pattern),
+        false => pattern.to_string()
+    };
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|err| StepError(format!("Invalid regular expression: {}", err)))?;
+
+    let mut matches = vec![];
+    for (block_index, block_text) in get_block_texts_in_order(root_block_id, block_map)?.iter().enumerate() {
+        // unpaired surrogates become U+FFFD, which is also 1 utf16 unit so offsets stay the same
+        let text = String::from_utf16_lossy(&block_text.text);
+        for captures in regex.captures_iter(&text) {
+            let whole_match = captures.get(0).unwrap();
+            if whole_match.start() == whole_match.end() {
+                continue
+            }
+            let range = utf16_offset(&text, whole_match.start())..utf16_offset(&text, whole_match.end());
+            let anchor = SubSelection { block_id: block_text.block_id.clone(), offset: range.start, subselection: None }
+                .real_selection_from_raw(block_map)?;
+            let head = SubSelection { block_id: block_text.block_id.clone(), offset: range.end, subselection: None }
+                .real_selection_from_raw(block_map)?;
+            let mut replacement = String::new();
+            captures.expand(replace_with, &mut replacement);
+            matches.push(SearchMatch {
+                block_index,
+                selection: Selection { anchor: move_to_next_inline_block_if_at_end(anchor, block_text), head },
+                replacement,
+                range
+            });
+        }
+    }
+    return Ok(matches)
+}
+
+fn utf16_offset(text: &str, byte_offset: usize) -> usize {
+    return text[..byte_offset].encode_utf16().count()
+}
+
+/// real_selection_from_raw puts an offset between two inline blocks at the end of the first one,
+/// but the start of a match should be in the inline block of its first char (so replacements keep that char's marks)
+fn move_to_next_inline_block_if_at_end(sub_selection: SubSelection, block_text: &BlockText) -> SubSelection {
+    let index = match block_text.inline_blocks.iter().position(|(id, _)| id == &sub_selection.block_id) {
+        Some(index) => index,
+        None => return sub_selection
+    };
+    return match block_text.inline_blocks.get(index + 1) {
+        Some((next_id, next_start)) if block_text.inline_blocks[index].1 + sub_selection.offset == *next_start => {
+            SubSelection { block_id: next_id.clone(), offset: 0, subselection: None }
+        },
+        _ => sub_selection
+    }
+}
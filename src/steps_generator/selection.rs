@@ -156,6 +156,22 @@ impl Selection {
         js_sys::Reflect::set(&obj, &JsValue::from_str("head"), &JsValue::from(self.head.to_js_obj()?)).unwrap();
         return Ok(obj.into())
     }
+
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "anchor": self.anchor.to_json()?,
+            "head": self.head.to_json()?
+        }))
+    }
+
+    pub fn from_json(json: Value) -> Result<Self, StepError> {
+        let anchor = json.get("anchor").ok_or(StepError(format!("Selection does not have anchor field: {}", json)))?.clone();
+        let head = json.get("head").ok_or(StepError(format!("Selection does not have head field: {}", json)))?.clone();
+        return Ok(Self {
+            anchor: SubSelection::from_json(anchor)?,
+            head: SubSelection::from_json(head)?
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -310,7 +326,7 @@ impl SubSelection {
         standard_block: &StandardBlock,
         block_map: &BlockMap,
     ) -> Result<SubSelection, StepError> {
-        match standard_block.children.len() > 0 {
+        match standard_block.visible_children().len() > 0 {
             true => {
                 let youngest_child = block_map.get_standard_block(
                     &standard_block.children[standard_block.children.len() - 1],
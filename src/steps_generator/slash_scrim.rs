@@ -1,8 +1,111 @@
-use crate::{step::{Step, ReplaceStep, ReplaceSlice, AddBlockStep}, blocks::{BlockMap, Block, standard_blocks::{StandardBlockType, content_block::ContentBlock, list_block::ListBlock, StandardBlock, page_block::PageBlock}}, new_ids::NewIds};
+use crate::{step::{Step, ReplaceStep, ReplaceSlice, AddBlockStep, AddTableOfContentsStep}, blocks::{BlockMap, Block, standard_blocks::{StandardBlockType, content_block::ContentBlock, list_block::ListBlock, StandardBlock, page_block::PageBlock, toggle_block::ToggleBlock, table_of_contents_block::TableOfContentsBlock}, inline_blocks::InlineBlock}, new_ids::NewIds};
 
-use super::{StepError, event::SlashScrimEvent, selection::SubSelection};
+use super::{StepError, event::SlashScrimEvent, selection::SubSelection, sub_page::generate_add_sub_page_step};
 
 
+pub struct SlashCommand {
+    /// Matches the block_type sent in the slash scrim event
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub description: &'static str,
+    pub block_type: fn() -> StandardBlockType,
+}
+
+pub static SLASH_COMMANDS: [SlashCommand; 12] = [
+    SlashCommand { name: "paragraph", aliases: &["text", "p"], description: "Plain text", block_type: || StandardBlockType::Paragraph(ContentBlock::new(vec![])) },
+    SlashCommand { name: "heading 1", aliases: &["h1", "title"], description: "Large section heading", block_type: || StandardBlockType::H1(ContentBlock::new(vec![])) },
+    SlashCommand { name: "heading 2", aliases: &["h2", "subtitle"], description: "Medium section heading", block_type: || StandardBlockType::H2(ContentBlock::new(vec![])) },
+    SlashCommand { name: "heading 3", aliases: &["h3"], description: "Small section heading", block_type: || StandardBlockType::H3(ContentBlock::new(vec![])) },
+    SlashCommand { name: "to-do list", aliases: &["todo", "checkbox", "[]"], description: "Track tasks with a to-do list", block_type: || StandardBlockType::TodoList(ListBlock::new()) },
+    SlashCommand { name: "numbered list", aliases: &["ordered list", "ol", "1."], description: "Create a list with numbering", block_type: || StandardBlockType::NumberedList(ListBlock::new()) },
+    SlashCommand { name: "dotpoint list", aliases: &["bullet list", "ul", "-"], description: "Create a simple bulleted list", block_type: || StandardBlockType::DotPointList(ListBlock::new()) },
+    SlashCommand { name: "toggle list", aliases: &["toggle", "collapsible"], description: "Hide content inside a collapsible list", block_type: || StandardBlockType::ToggleList(ToggleBlock::new()) },
+    SlashCommand { name: "inline page", aliases: &["page", "sub-page"], description: "Embed a sub-page inside this page", block_type: || StandardBlockType::InlinePage(PageBlock::new()) },
+    SlashCommand { name: "square page", aliases: &["page card"], description: "Embed a sub-page as a square card", block_type: || StandardBlockType::SquarePage(PageBlock::new()) },
+    SlashCommand { name: "link page", aliases: &["link", "page link"], description: "Link to an existing page", block_type: || StandardBlockType::LinkBlock(PageBlock::new()) },
+    SlashCommand { name: "table of contents", aliases: &["toc", "outline", "contents"], description: "List the headings on this page", block_type: || StandardBlockType::TableOfContents(TableOfContentsBlock::new()) },
+];
+
+impl SlashCommand {
+    pub fn from_name(name: &str) -> Result<&'static SlashCommand, StepError> {
+        return SLASH_COMMANDS.iter()
+            .find(|command| command.name == name)
+            .ok_or(StepError(format!("There is no valid block type: {}", name)))
+    }
+
+    /// Best score of the name & aliases, None if none of them match the query
+    fn score(&self, query: &str) -> Option<usize> {
+        return std::iter::once(&self.name).chain(self.aliases.iter())
+            .filter_map(|target| fuzzy_score(query, target))
+            .max()
+    }
+}
+
+/// Returns the slash commands matching the text typed after the "/", best match first
+pub fn query_slash_commands(query: &str) -> Vec<&'static SlashCommand> {
+    let query = query.trim().to_lowercase();
+    let mut candidates: Vec<(usize, &'static SlashCommand)> = SLASH_COMMANDS.iter()
+        .filter_map(|command| command.score(&query).map(|score| (score, command)))
+        .collect();
+    // stable sort keeps the registry order for equal scores
+    candidates.sort_by(|(a, _), (b, _)| b.cmp(a));
+    return candidates.into_iter().map(|(_, command)| command).collect()
+}
+
+/// Finds the text typed between the "/" and the caret and returns the matching slash commands
+pub fn get_slash_scrim_candidates(to: &SubSelection, block_map: &BlockMap) -> Result<Vec<&'static SlashCommand>, StepError> {
+    let inline_block = block_map.get_inline_block(&to.block_id)?;
+    let slash_offset = find_slash_offset(&inline_block, to.offset)?;
+    let query = inline_block.text()?.slice(slash_offset + 1..to.offset).to_string();
+    return Ok(query_slash_commands(&query))
+}
+
+/// -> exact match
+/// -> target starts with query
+/// -> a word in target starts with query
+/// -> target contains query
+/// -> query chars appear in order in target (fewer gaps score higher)
+fn fuzzy_score(query: &str, target: &str) -> Option<usize> {
+    if query.is_empty() {
+        return Some(0)
+    }
+    if target == query {
+        return Some(1000)
+    } else if target.starts_with(query) {
+        return Some(800)
+    } else if target.split(|c: char| c == ' ' || c == '-').any(|word| word.starts_with(query)) {
+        return Some(600)
+    } else if target.contains(query) {
+        return Some(400)
+    }
+
+    let mut gaps = 0;
+    let mut target_chars = target.chars();
+    for query_char in query.chars() {
+        loop {
+            match target_chars.next() {
+                Some(target_char) if target_char == query_char => break,
+                Some(_) => gaps += 1,
+                None => return None
+            }
+        }
+    }
+    return Some(200usize.saturating_sub(gaps))
+}
+
+/// Scans backwards from the caret to find the "/" that opened the slash scrim
+fn find_slash_offset(inline_block: &InlineBlock, offset: usize) -> Result<usize, StepError> {
+    let text = inline_block.text()?;
+    let mut i = offset;
+    while i > 0 {
+        i -= 1;
+        if text.0[i] == '/' as u16 {
+            return Ok(i)
+        }
+    }
+    return Err(StepError(format!("Could not find '/' before offset {} in block: {}", offset, inline_block.id())))
+}
+
 pub fn generate_slash_scrim_steps(
     slash_scrim_event: &SlashScrimEvent,
     from: SubSelection,
@@ -14,44 +117,29 @@ pub fn generate_slash_scrim_steps(
     let from_block = block_map.get_block(&from.block_id)?;
     match from_block {
         Block::InlineBlock(inline_block) => {
-            let text = inline_block.text()?;
-            let mut i = to.offset;
-            let mut char = "".to_string();
-            while &char != "/" {
-                char = String::from_utf16(&[text.0[i - 1]]).unwrap();
-                i -= 1;
-                replace_slash_scrim_text_step = Some(ReplaceStep {
-                    block_id: inline_block.parent.clone(),
-                    from: SubSelection { block_id: inline_block.id(), offset: i, subselection: None },
-                    to: SubSelection { block_id: inline_block.id(), offset: to.offset, subselection: None },
-                    slice: ReplaceSlice::String("".to_string())
-                });
-            }
+            replace_slash_scrim_text_step = Some(ReplaceStep {
+                block_id: inline_block.parent.clone(),
+                from: SubSelection { block_id: inline_block.id(), offset: find_slash_offset(&inline_block, to.offset)?, subselection: None },
+                to: SubSelection { block_id: inline_block.id(), offset: to.offset, subselection: None },
+                slice: ReplaceSlice::String("".to_string())
+            });
         },
         Block::StandardBlock(_) => {},
         Block::Root(_) => return Err(StepError("Cannot perform slash scrim event directly on root block".to_string()))
     }
 
-    let new_block_type = match slash_scrim_event.block_type.as_str() {
-        "paragraph" => StandardBlockType::Paragraph(ContentBlock::new(vec![])),
-        "heading 1" => StandardBlockType::H1(ContentBlock::new(vec![])),
-        "heading 2" => StandardBlockType::H2(ContentBlock::new(vec![])),
-        "heading 3" => StandardBlockType::H3(ContentBlock::new(vec![])),
-        "to-do list" => StandardBlockType::TodoList(ListBlock::new()),
-        "numbered list" => StandardBlockType::NumberedList(ListBlock::new()),
-        "dotpoint list" => StandardBlockType::DotPointList(ListBlock::new()),
-        "inline page" => StandardBlockType::InlinePage(PageBlock::new()),
-        "square page" => StandardBlockType::SquarePage(PageBlock::new()),
-        "link page" => StandardBlockType::LinkBlock(PageBlock::new()),
-        block_type => return Err(StepError(format!("There is no valid block type: {}", block_type)))
-    };
+    let new_block_type = (SlashCommand::from_name(&slash_scrim_event.block_type)?.block_type)();
+    let (new_block_type, add_sub_page_step) = generate_add_sub_page_step(new_block_type, new_ids)?;
 
     let mut steps = vec![];
+    if let Some(add_sub_page_step) = add_sub_page_step {
+        steps.push(add_sub_page_step);
+    }
     let nearest_standard_block = block_map.get_nearest_ancestor_standard_block_incl_self(&from.block_id)?;
     if replace_slash_scrim_text_step.is_some() {
         let replace_step = replace_slash_scrim_text_step.unwrap();
         if block_is_empty_other_than_slash_and_search(&nearest_standard_block, block_map, &replace_step)? && new_block_type.has_content()  {
-            return Ok(vec![
+            steps.extend(vec![
                 Step::DeleteBlock(nearest_standard_block.id()),
                 Step::AddBlock(AddBlockStep {
                     block_id: nearest_standard_block.parent(),
@@ -61,18 +149,12 @@ pub fn generate_slash_scrim_steps(
                     new_std_block_id: new_ids.get_id()?,
                     new_inline_block_id: new_ids.get_id()?
                 })
-            ])
+            ]);
+            return Ok(steps)
         } else if block_is_empty_other_than_slash_and_search(&nearest_standard_block, block_map, &replace_step)? {
-            return Ok(vec![
+            steps.extend(vec![
                 Step::DeleteBlock(nearest_standard_block.id()),
-                Step::AddBlock(AddBlockStep {
-                    block_id:  nearest_standard_block.parent(),
-                    child_offset: nearest_standard_block.index(block_map)?,
-                    block_type: new_block_type,
-                    focus_block_below: false,
-                    new_std_block_id: new_ids.get_id()?,
-                    new_inline_block_id: new_ids.get_id()?
-                }),
+                add_new_block_step(nearest_standard_block.parent(), nearest_standard_block.index(block_map)?, new_block_type, new_ids)?,
                 Step::AddBlock(AddBlockStep {
                     block_id:  nearest_standard_block.parent(),
                     child_offset: nearest_standard_block.index(block_map)? + 1,
@@ -81,7 +163,8 @@ pub fn generate_slash_scrim_steps(
                     new_std_block_id: new_ids.get_id()?,
                     new_inline_block_id: new_ids.get_id()?
                 })
-            ])
+            ]);
+            return Ok(steps)
         } else {
             steps.push(Step::ReplaceStep(replace_step));
         }
@@ -90,14 +173,7 @@ pub fn generate_slash_scrim_steps(
     let add_paragraph_block_below_new_block = !new_block_type.has_content();
 
     let offset_to_add_at = nearest_standard_block.index(block_map)? + 1;
-    steps.push(Step::AddBlock(AddBlockStep {
-        block_id: nearest_standard_block.parent.clone(),
-        child_offset: offset_to_add_at,
-        block_type: new_block_type,
-        focus_block_below: false,
-        new_std_block_id: new_ids.get_id()?,
-        new_inline_block_id: new_ids.get_id()?
-    }));
+    steps.push(add_new_block_step(nearest_standard_block.parent.clone(), offset_to_add_at, new_block_type, new_ids)?);
     if add_paragraph_block_below_new_block {
         steps.push(Step::AddBlock(AddBlockStep {
             block_id: nearest_standard_block.parent.clone(),
@@ -112,6 +188,25 @@ pub fn generate_slash_scrim_steps(
     return Ok(steps)
 }
 
+/// Table of contents blocks are added with their own step, so their entries are generated when it's actualised
+fn add_new_block_step(block_id: String, child_offset: usize, block_type: StandardBlockType, new_ids: &mut NewIds) -> Result<Step, StepError> {
+    return Ok(match block_type {
+        StandardBlockType::TableOfContents(_) => Step::AddTableOfContents(AddTableOfContentsStep {
+            block_id,
+            new_block_id: new_ids.get_id()?,
+            child_offset
+        }),
+        block_type => Step::AddBlock(AddBlockStep {
+            block_id,
+            child_offset,
+            block_type,
+            focus_block_below: false,
+            new_std_block_id: new_ids.get_id()?,
+            new_inline_block_id: new_ids.get_id()?
+        })
+    })
+}
+
 fn block_is_empty_other_than_slash_and_search(
     nearest_standard_block: &StandardBlock,
     block_map: &BlockMap,
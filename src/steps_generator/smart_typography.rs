@@ -0,0 +1,166 @@
+use serde::{Serialize, Deserialize};
+use wasm_bindgen::JsValue;
+
+use crate::{blocks::{BlockMap, Block, inline_blocks::InlineBlock}, step::{Step, ReplaceStep, ReplaceSlice}, frontend_interface::{get_js_field_as_string, get_js_field_as_f64}};
+
+use super::{selection::SubSelection, StepError, event::{KeyPress, Key}};
+
+/// (text that gets replaced, replacement), quotes are converted depending on the character before them
+const CONVERSIONS: [(&str, &str); 3] = [
+    ("--", "—"),
+    ("...", "…"),
+    ("->", "→"),
+];
+
+/// A conversion made by a key press. The frontend gets it back with the key press's result
+/// & sends it in the next key press's metadata, so only the backspace straight after it can undo it
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Conversion {
+    pub block_id: String,
+    /// Offset of the caret directly after the converted character
+    pub offset: usize,
+    /// The text that was typed
+    pub source: String
+}
+
+impl Conversion {
+    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let fields = [
+            ("block_id", JsValue::from_str(&self.block_id)),
+            ("offset", JsValue::from_f64(self.offset as f64)),
+            ("source", JsValue::from_str(&self.source))
+        ];
+        for (field, value) in fields {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(field), &value)
+                .map_err(|_| StepError(format!("Failed to set {} on conversion js obj", field)))?;
+        }
+        return Ok(JsValue::from(obj))
+    }
+
+    pub fn from_js_obj(obj: &JsValue) -> Result<Self, StepError> {
+        return Ok(Self {
+            block_id: get_js_field_as_string(obj, "block_id")?,
+            offset: get_js_field_as_f64(obj, "offset")? as usize,
+            source: get_js_field_as_string(obj, "source")?
+        })
+    }
+}
+
+/// The conversion the key press makes (see generate_smart_typography_steps), None if it doesn't make one
+pub fn get_smart_typography_conversion(
+    key_press: &KeyPress,
+    block_map: &BlockMap,
+    from: &SubSelection,
+    to: &SubSelection
+) -> Result<Option<Conversion>, StepError> {
+    let metadata = &key_press.metadata;
+    let key = match key_press.key {
+        Key::Standard(key) if metadata.smart_typography && metadata.suggesting_as.is_none() && !metadata.ctrl_down && !metadata.meta_down => key,
+        _ => return Ok(None)
+    };
+    return Ok(find_conversion(block_map, from, to, key)?.map(|(inline_block, chars_before_caret, source, _)| Conversion {
+        block_id: inline_block.id(),
+        offset: from.offset - chars_before_caret + 1,
+        source: source.to_string()
+    }))
+}
+
+/// Replaces the typed key (and the characters before it) with it's typographic equivalent
+/// -> "--" -> em dash, "..." -> ellipsis, "->" -> arrow
+/// -> straight quotes -> opening or closing curly quotes depending on the character before the caret
+/// Only looks at the caret's inline block, so the new character keeps the marks at the caret
+pub fn generate_smart_typography_steps(
+    block_map: &BlockMap,
+    from: &SubSelection,
+    to: &SubSelection,
+    key: char
+) -> Result<Option<Vec<Step>>, StepError> {
+    return Ok(find_conversion(block_map, from, to, key)?.map(|(inline_block, chars_before_caret, _, replacement)| vec![
+        Step::ReplaceStep(ReplaceStep {
+            block_id: inline_block.parent.clone(),
+            from: SubSelection { block_id: inline_block.id(), offset: from.offset - chars_before_caret, subselection: None },
+            to: to.clone(),
+            slice: ReplaceSlice::String(replacement.to_string())
+        })
+    ]))
+}
+
+/// -> (caret's inline block, typed characters before the caret that are replaced, typed text, replacement)
+fn find_conversion(
+    block_map: &BlockMap,
+    from: &SubSelection,
+    to: &SubSelection,
+    key: char
+) -> Result<Option<(InlineBlock, usize, &'static str, &'static str)>, StepError> {
+    if from != to {
+        return Ok(None)
+    }
+    let inline_block = match block_map.get_block(&from.block_id)? {
+        Block::InlineBlock(inline_block) => inline_block,
+        _ => return Ok(None)
+    };
+    let text = inline_block.text()?.slice(0..from.offset).to_string();
+
+    return Ok(match key {
+        '"' | '\'' => {
+            let previous_char = match text.chars().last() {
+                Some(c) => Some(c),
+                None => last_char_of_previous_inline_block(&inline_block, block_map)?
+            };
+            let is_opening = match previous_char {
+                Some(c) => c.is_whitespace() || "([{“‘—".contains(c),
+                None => true
+            };
+            match (key, is_opening) {
+                ('"', true) => Some((0, "\"", "“")),
+                ('"', false) => Some((0, "\"", "”")),
+                (_, true) => Some((0, "'", "‘")),
+                (_, false) => Some((0, "'", "’")),
+            }
+        },
+        key => {
+            let typed = format!("{}{}", text, key);
+            CONVERSIONS.iter()
+                .find(|(source, _)| typed.ends_with(source))
+                .map(|(source, replacement)| (source.encode_utf16().count() - 1, *source, *replacement))
+        }
+    }.map(|(chars_before_caret, source, replacement)| (inline_block, chars_before_caret, source, replacement)))
+}
+
+/// Backspace directly after the conversion made by the previous key press (sent back in its metadata) restores the text that was typed.
+/// Typographic characters that were typed, pasted or saved earlier are deleted like any other character
+pub fn generate_revert_smart_typography_steps(
+    block_map: &BlockMap,
+    from: &SubSelection,
+    to: &SubSelection,
+    last_conversion: Option<Conversion>
+) -> Result<Option<Vec<Step>>, StepError> {
+    let conversion = match last_conversion {
+        Some(conversion) => conversion,
+        None => return Ok(None)
+    };
+    if from != to || from.block_id != conversion.block_id || from.offset != conversion.offset {
+        return Ok(None)
+    }
+    let inline_block = match block_map.get_block(&from.block_id)? {
+        Block::InlineBlock(inline_block) => inline_block,
+        _ => return Ok(None)
+    };
+    return Ok(Some(vec![
+        Step::ReplaceStep(ReplaceStep {
+            block_id: inline_block.parent.clone(),
+            from: SubSelection { block_id: inline_block.id(), offset: from.offset - 1, subselection: None },
+            to: to.clone(),
+            slice: ReplaceSlice::String(conversion.source)
+        })
+    ]))
+}
+
+fn last_char_of_previous_inline_block(inline_block: &InlineBlock, block_map: &BlockMap) -> Result<Option<char>, StepError> {
+    if inline_block.index(block_map)? == 0 {
+        return Ok(None)
+    }
+    let previous_block = inline_block.previous_block(block_map)?;
+    return Ok(previous_block.text()?.clone().to_string().chars().last())
+}
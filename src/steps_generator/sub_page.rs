@@ -0,0 +1,65 @@
+use crate::{blocks::{BlockMap, standard_blocks::{StandardBlockType, page_block::PageBlock}}, step::{Step, AddPageStep, AddBlockStep, MoveBlocksToPageStep}, new_ids::NewIds};
+
+use super::{selection::SubSelection, StepError, keypress_step_generator::move_block::get_sibling_range};
+
+/// Inline & square page blocks created from the slash scrim get a new sub-page with an empty paragraph
+/// -> returns the block type linked to the new page, and the step that creates the page
+/// -> link pages link to an existing page, so they don't get a sub-page
+pub fn generate_add_sub_page_step(
+    block_type: StandardBlockType,
+    new_ids: &mut NewIds
+) -> Result<(StandardBlockType, Option<Step>), StepError> {
+    let block_type = match block_type {
+        StandardBlockType::InlinePage(_) | StandardBlockType::SquarePage(_) => block_type,
+        block_type => return Ok((block_type, None))
+    };
+    let root_block_id = new_ids.get_id()?;
+    let add_page_step = Step::AddPage(AddPageStep {
+        root_block_id: root_block_id.clone(),
+        initial_paragraph: Some((new_ids.get_id()?, new_ids.get_id()?))
+    });
+    let page_block = PageBlock { page_id: root_block_id };
+    return Ok(match block_type {
+        StandardBlockType::SquarePage(_) => (StandardBlockType::SquarePage(page_block), Some(add_page_step)),
+        _ => (StandardBlockType::InlinePage(page_block), Some(add_page_step))
+    })
+}
+
+/// Moves the selected blocks (& their children) into a new sub-page
+/// -> an inline page block linking to the new page takes the place of the first selected block
+pub fn generate_turn_into_page_steps(
+    from: SubSelection,
+    to: SubSelection,
+    block_map: &BlockMap,
+    new_ids: &mut NewIds
+) -> Result<Vec<Step>, StepError> {
+    let from_block = block_map.get_nearest_ancestor_standard_block_incl_self(&from.get_deepest_subselection().block_id)?;
+    let to_block = block_map.get_nearest_ancestor_standard_block_incl_self(&to.get_deepest_subselection().block_id)?;
+    let (first_block, last_block) = get_sibling_range(from_block, to_block, block_map)?;
+
+    let parent = first_block.get_parent(block_map)?;
+    let first_index = first_block.index(block_map)?;
+    let last_index = last_block.index(block_map)?;
+    let block_ids = parent.children()?[first_index..last_index + 1].to_vec();
+
+    let root_block_id = new_ids.get_id()?;
+    return Ok(vec![
+        Step::AddPage(AddPageStep {
+            root_block_id: root_block_id.clone(),
+            initial_paragraph: None
+        }),
+        Step::AddBlock(AddBlockStep {
+            block_id: parent.id(),
+            new_std_block_id: new_ids.get_id()?,
+            new_inline_block_id: new_ids.get_id()?,
+            child_offset: first_index,
+            block_type: StandardBlockType::InlinePage(PageBlock { page_id: root_block_id.clone() }),
+            focus_block_below: false
+        }),
+        Step::MoveBlocksToPage(MoveBlocksToPageStep {
+            block_ids,
+            page_id: root_block_id,
+            child_offset: Some(0)
+        })
+    ])
+}
@@ -0,0 +1,85 @@
+use wasm_bindgen::JsValue;
+
+use crate::{blocks::{BlockMap, inline_blocks::InlineBlock}, mark::Mark,
+    steps_generator::{selection::{Selection, SubSelection}, StepError}};
+
+use super::get_standard_blocks_in_order;
+
+/// Where a comment currently is in the document
+#[derive(Debug, PartialEq, Clone)]
+pub struct CommentAnchor {
+    pub comment_id: String,
+    pub resolved: bool,
+    /// From the start of the first marked inline block to the end of the last one
+    pub selection: Selection,
+    /// Marked text, with a new line between standard blocks
+    pub text: String
+}
+
+impl CommentAnchor {
+    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let fields = [
+            ("comment_id", JsValue::from_str(&self.comment_id)),
+            ("resolved", JsValue::from(self.resolved)),
+            ("selection", self.selection.to_js_obj()?),
+            ("text", JsValue::from_str(&self.text))
+        ];
+        for (field, value) in fields {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(field), &value)
+                .map_err(|_| StepError(format!("Failed to set {} on comment js obj", field)))?;
+        }
+        return Ok(JsValue::from(obj))
+    }
+}
+
+/// Every comment under the root, ordered by where they start in the document
+pub fn get_comments(root_block_id: &String, block_map: &BlockMap) -> Result<Vec<CommentAnchor>, StepError> {
+    let mut comments: Vec<CommentAnchor> = vec![];
+    let mut last_block_ids: Vec<String> = vec![]; // std block the comment was last seen in
+    for block in get_standard_blocks_in_order(root_block_id, block_map)?.into_iter().filter(|block| block.has_content()) {
+        for inline_block in block.get_inline_blocks(block_map)? {
+            let text = inline_block.text()?.clone().to_string();
+            let end = SubSelection::from(inline_block.id(), inline_block.text()?.len(), None);
+            for mark in &inline_block.marks {
+                let comment = match mark {
+                    Mark::Comment(comment) => comment,
+                    _ => continue
+                };
+                match comments.iter().position(|anchor| anchor.comment_id == comment.id) {
+                    Some(i) => {
+                        if last_block_ids[i] != block.id() {
+                            comments[i].text.push('\n');
+                            last_block_ids[i] = block.id();
+                        }
+                        comments[i].text.push_str(&text);
+                        comments[i].selection.head = end.clone();
+                    },
+                    None => {
+                        comments.push(CommentAnchor {
+                            comment_id: comment.id.clone(),
+                            resolved: comment.resolved,
+                            selection: Selection::from(SubSelection::from(inline_block.id(), 0, None), end.clone()),
+                            text: text.clone()
+                        });
+                        last_block_ids.push(block.id());
+                    }
+                };
+            }
+        }
+    }
+    return Ok(comments)
+}
+
+/// Inline blocks under the root that have a comment mark with the id
+pub fn get_inline_blocks_with_comment(root_block_id: &String, comment_id: &str, block_map: &BlockMap) -> Result<Vec<InlineBlock>, StepError> {
+    let mut inline_blocks = vec![];
+    for block in get_standard_blocks_in_order(root_block_id, block_map)?.into_iter().filter(|block| block.has_content()) {
+        for inline_block in block.get_inline_blocks(block_map)? {
+            if inline_block.marks.iter().any(|mark| matches!(mark, Mark::Comment(comment) if comment.id == comment_id)) {
+                inline_blocks.push(inline_block);
+            }
+        }
+    }
+    return Ok(inline_blocks)
+}
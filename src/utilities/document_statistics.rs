@@ -0,0 +1,147 @@
+use wasm_bindgen::JsValue;
+
+use crate::{blocks::{BlockMap, Block, standard_blocks::{StandardBlock, StandardBlockType}},
+    steps_generator::{selection::{Selection, SubSelection}, StepError}};
+
+use super::{find_root_block_id, get_standard_blocks_in_order};
+
+pub const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct DocumentStatistics {
+    pub characters: usize,
+    pub characters_excluding_spaces: usize,
+    pub words: usize,
+    pub sentences: usize,
+    pub blocks: usize,
+    pub headings: usize,
+    pub todos: usize,
+    pub completed_todos: usize,
+    /// Rounded up, 0 for an empty document
+    pub reading_time_minutes: usize
+}
+
+impl DocumentStatistics {
+    pub fn to_js_obj(&self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let fields = [
+            ("characters", self.characters),
+            ("characters_excluding_spaces", self.characters_excluding_spaces),
+            ("words", self.words),
+            ("sentences", self.sentences),
+            ("blocks", self.blocks),
+            ("headings", self.headings),
+            ("todos", self.todos),
+            ("completed_todos", self.completed_todos),
+            ("reading_time_minutes", self.reading_time_minutes)
+        ];
+        for (field, value) in fields {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(field), &JsValue::from(value as f64))
+                .map_err(|_| StepError(format!("Failed to set {} on statistics js obj", field)))?;
+        }
+        return Ok(JsValue::from(obj))
+    }
+
+    fn add_block(&mut self, block: &StandardBlock, text: &str) {
+        self.blocks += 1;
+        match &block.content {
+            StandardBlockType::H1(_) | StandardBlockType::H2(_) | StandardBlockType::H3(_) => self.headings += 1,
+            StandardBlockType::TodoList(list_block) => {
+                self.todos += 1;
+                if list_block.completed {
+                    self.completed_todos += 1;
+                }
+            },
+            _ => {}
+        };
+        self.characters += text.chars().count();
+        self.characters_excluding_spaces += text.chars().filter(|c| !c.is_whitespace()).count();
+        self.words += text.split_whitespace().count();
+        self.sentences += count_sentences(text);
+    }
+
+    fn finish(mut self) -> Self {
+        self.reading_time_minutes = (self.words + WORDS_PER_MINUTE - 1) / WORDS_PER_MINUTE;
+        return self
+    }
+}
+
+/// Counts for every standard block under the root (including children of collapsed toggles)
+pub fn get_document_statistics(root_block_id: &String, block_map: &BlockMap) -> Result<DocumentStatistics, StepError> {
+    let mut statistics = DocumentStatistics::default();
+    for block in get_counted_blocks_in_order(root_block_id, block_map)? {
+        let text = get_block_text(&block, block_map)?;
+        statistics.add_block(&block, &String::from_utf16_lossy(&text));
+    }
+    return Ok(statistics.finish())
+}
+
+/// Counts for the selected text -> the first & last blocks only count their selected text
+/// (but are still counted as blocks)
+pub fn get_selection_statistics(selection: &Selection, block_map: &BlockMap) -> Result<DocumentStatistics, StepError> {
+    let root_block_id = find_root_block_id(&selection.anchor.block_id, block_map)
+        .ok_or(StepError(format!("Could not find the root of block: {}", selection.anchor.block_id)))?;
+    let blocks = get_counted_blocks_in_order(&root_block_id, block_map)?;
+    let anchor = get_position(&selection.anchor, &blocks, block_map)?;
+    let head = get_position(&selection.head, &blocks, block_map)?;
+    let ((from_index, from_offset), (to_index, to_offset)) = match anchor <= head {
+        true => (anchor, head),
+        false => (head, anchor)
+    };
+
+    let mut statistics = DocumentStatistics::default();
+    for (i, block) in blocks[from_index..to_index + 1].iter().enumerate() {
+        let text = get_block_text(block, block_map)?;
+        let start = match i == 0 {
+            true => from_offset.min(text.len()),
+            false => 0
+        };
+        let end = match from_index + i == to_index {
+            true => to_offset.min(text.len()).max(start),
+            false => text.len()
+        };
+        statistics.add_block(block, &String::from_utf16_lossy(&text[start..end]));
+    }
+    return Ok(statistics.finish())
+}
+
+/// A sentence is text ending in ".", "!", "?" or "…" (or the end of the block) that has a letter or number in it
+fn count_sentences(text: &str) -> usize {
+    return text.split(|c| c == '.' || c == '!' || c == '?' || c == '…')
+        .filter(|sentence| sentence.chars().any(|c| c.is_alphanumeric()))
+        .count()
+}
+
+/// Layouts only arrange their children, so they aren't counted as blocks
+fn get_counted_blocks_in_order(root_block_id: &String, block_map: &BlockMap) -> Result<Vec<StandardBlock>, StepError> {
+    return Ok(get_standard_blocks_in_order(root_block_id, block_map)?
+        .into_iter()
+        .filter(|block| !matches!(block.content, StandardBlockType::Layout(_)))
+        .collect())
+}
+
+fn get_block_text(block: &StandardBlock, block_map: &BlockMap) -> Result<Vec<u16>, StepError> {
+    let mut text = vec![];
+    if block.has_content() {
+        for inline_block in block.get_inline_blocks(block_map)? {
+            text.extend(inline_block.text()?.0.iter());
+        }
+    }
+    return Ok(text)
+}
+
+/// (index of the standard block in document order, raw offset in the standard block's text)
+fn get_position(sub_selection: &SubSelection, blocks: &Vec<StandardBlock>, block_map: &BlockMap) -> Result<(usize, usize), StepError> {
+    let deepest = sub_selection.get_deepest_subselection();
+    let (block_id, offset) = match block_map.get_block(&deepest.block_id)? {
+        Block::InlineBlock(_) => {
+            let raw_selection = deepest.to_raw_selection(block_map)?;
+            (raw_selection.block_id, raw_selection.offset)
+        },
+        block => (block.id(), 0)
+    };
+    return match blocks.iter().position(|block| block.id() == block_id) {
+        Some(index) => Ok((index, offset)),
+        None => Err(StepError(format!("Block: {} is not in the document", block_id)))
+    }
+}
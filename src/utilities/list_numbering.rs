@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use crate::{blocks::{BlockMap, standard_blocks::StandardBlockType}, steps_generator::StepError};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum NumberingStyle {
+    Decimal, // 1.
+    Alphabetic, // a.
+    Roman // i.
+}
+
+impl NumberingStyle {
+    /// Nested numbered lists cycle through decimal -> alphabetic -> roman
+    pub fn from_level(level: usize) -> Self {
+        return match level % 3 {
+            0 => Self::Decimal,
+            1 => Self::Alphabetic,
+            _ => Self::Roman
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ListNumber {
+    pub number: usize,
+    pub level: usize
+}
+
+impl ListNumber {
+    pub fn style(&self) -> NumberingStyle {
+        return NumberingStyle::from_level(self.level)
+    }
+
+    /// eg: "3." || "c." || "iii."
+    pub fn label(&self) -> String {
+        let number = match self.style() {
+            NumberingStyle::Decimal => self.number.to_string(),
+            NumberingStyle::Alphabetic => to_alphabetic(self.number),
+            NumberingStyle::Roman => to_roman(self.number)
+        };
+        return format!("{}.", number)
+    }
+}
+
+/// Walks every block under the root and numbers each numbered list block.
+///
+/// -> a run of numbered list siblings counts up from 1
+/// -> any other block type between them interrupts the run, so the next numbered list starts at 1 again
+/// -> a numbered list with a "start" value restarts the count at that value
+/// -> children of a numbered list are numbered one level deeper (1. -> a. -> i.)
+pub fn get_list_numbering(root_block_id: &String, block_map: &BlockMap) -> Result<HashMap<String, ListNumber>, StepError> {
+    let root_block = block_map.get_root_block(root_block_id)?;
+    let mut numbering = HashMap::new();
+    number_children(&root_block.children, 0, block_map, &mut numbering)?;
+    return Ok(numbering)
+}
+
+fn number_children(
+    children: &Vec<String>,
+    level: usize,
+    block_map: &BlockMap,
+    numbering: &mut HashMap<String, ListNumber>
+) -> Result<(), StepError> {
+    let mut current_number = 0;
+    for id in children {
+        let block = block_map.get_standard_block(id)?;
+        match &block.content {
+            StandardBlockType::NumberedList(list_block) => {
+                current_number = match list_block.start {
+                    Some(start) => start,
+                    None => current_number + 1
+                };
+                numbering.insert(block.id(), ListNumber { number: current_number, level });
+                number_children(&block.children, level + 1, block_map, numbering)?;
+            },
+            _ => {
+                current_number = 0;
+                number_children(&block.children, level, block_map, numbering)?;
+            }
+        };
+    }
+    return Ok(())
+}
+
+/// 1 -> a, 26 -> z, 27 -> aa
+fn to_alphabetic(mut number: usize) -> String {
+    if number == 0 {
+        return "0".to_string()
+    }
+    let mut chars = vec![];
+    while number > 0 {
+        number -= 1;
+        chars.push((b'a' + (number % 26) as u8) as char);
+        number /= 26;
+    }
+    return chars.into_iter().rev().collect()
+}
+
+fn to_roman(mut number: usize) -> String {
+    if number == 0 {
+        return "0".to_string()
+    }
+    let numerals = [
+        (1000, "m"), (900, "cm"), (500, "d"), (400, "cd"),
+        (100, "c"), (90, "xc"), (50, "l"), (40, "xl"),
+        (10, "x"), (9, "ix"), (5, "v"), (4, "iv"), (1, "i")
+    ];
+    let mut roman = String::new();
+    for (value, numeral) in numerals {
+        while number >= value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+    return roman
+}
@@ -5,6 +5,14 @@ use crate::{steps_generator::{selection::SubSelection, StepError},
     inline_blocks::{InlineBlock}},
     new_ids::NewIds};
 pub mod update_state_tools;
+pub mod list_numbering;
+pub mod document_statistics;
+pub mod outline;
+pub mod comments;
+pub mod suggestions;
+pub mod snapshots;
+pub mod position_mapping;
+pub mod presence;
 
 #[derive(PartialEq)]
 pub enum BlockStructure {
@@ -126,6 +134,10 @@ pub fn get_blocks_between(
 
         if block_structure == BlockStructure::Tree {
             add_block_and_inline_blocks_to_new_block_map(block_map, &mut new_block_map, current_node.clone())?;
+            if current_node.is_collapsed() && current_node.children.len() > 0 {
+                // hidden children are skipped while walking the tree, but still belong to the copied block
+                add_hidden_descendants_to_new_block_map(block_map, &mut new_block_map, &current_node)?;
+            }
         }
 
         if should_add_block(&current_node, &block_structure, block_map, depth_from_root)? {
@@ -167,8 +179,9 @@ pub fn get_blocks_between(
     }
 }
 
+/// Children of a collapsed toggle block are skipped
 pub fn get_next_block_in_tree(current_node: &StandardBlock, block_map: &BlockMap, depth_from_root: &mut usize) -> Result<StandardBlock, StepError> {
-    if current_node.children.len() > 0 { // has children
+    if current_node.visible_children().len() > 0 { // has visible children
         return block_map.get_standard_block(&current_node.children[0])
     } else if current_node.next_sibling(block_map)?.is_some() {
         return Ok(current_node.next_sibling(block_map)?.unwrap())
@@ -217,6 +230,20 @@ fn add_block_and_inline_blocks_to_new_block_map(block_map: &BlockMap, new_block_
     return Ok(())
 }
 
+fn add_hidden_descendants_to_new_block_map(block_map: &BlockMap, new_block_map: &mut BlockMap, collapsed_block: &StandardBlock) -> Result<(), StepError> {
+    let children = block_map.ids_to_blocks(&collapsed_block.children)?.into_iter().map(|block| match block {
+        Block::StandardBlock(block) => Ok(block),
+        _ => Err(StepError("Children of a standard block should be standard blocks".to_string()))
+    }).collect::<Result<Vec<StandardBlock>, StepError>>()?;
+    for descendant in get_all_blocks(&children, block_map)? {
+        match descendant.has_content() {
+            true => add_block_and_inline_blocks_to_new_block_map(block_map, new_block_map, descendant)?,
+            false => { new_block_map.update_block(Block::StandardBlock(descendant), &mut Vec::new())?; }
+        };
+    }
+    return Ok(())
+}
+
 fn split_edge_block_inline_blocks(
     for_from: bool,
     subselection: &SubSelection,
@@ -241,6 +268,40 @@ fn split_edge_block_inline_blocks(
     return Ok(current_node.update_block_content(ContentBlock { inline_blocks })?)
 }
 
+/// None if the block (or one of it's ancestors) is no longer in the block map
+pub fn find_root_block_id(block_id: &str, block_map: &BlockMap) -> Option<String> {
+    let mut block = block_map.get_block(block_id).ok()?;
+    loop {
+        block = match block {
+            Block::Root(root_block) => return Some(root_block.id()),
+            block => block_map.get_block(&block.parent().ok()?).ok()?
+        };
+    }
+}
+
+/// Every standard block under the root (or under a standard block), in document order
+/// -> each block comes before its children, which come before its next sibling
+pub fn get_standard_blocks_in_order(root_block_id: &String, block_map: &BlockMap) -> Result<Vec<StandardBlock>, StepError> {
+    let children = match block_map.get_block(root_block_id)? {
+        Block::Root(root_block) => root_block.children,
+        Block::StandardBlock(block) => block.children,
+        Block::InlineBlock(_) => return Err(StepError(format!("Block: {} is an inline block", root_block_id)))
+    };
+    let mut blocks = vec![];
+    add_blocks_in_order(&children, block_map, &mut blocks)?;
+    return Ok(blocks)
+}
+
+fn add_blocks_in_order(children: &Vec<String>, block_map: &BlockMap, blocks: &mut Vec<StandardBlock>) -> Result<(), StepError> {
+    for id in children {
+        let block = block_map.get_standard_block(id)?;
+        let children = block.children.clone();
+        blocks.push(block);
+        add_blocks_in_order(&children, block_map, blocks)?;
+    }
+    return Ok(())
+}
+
 /// If the top blocks are all the blocks on the root, this will get you every block in the tree,
 /// flat and in order.
 ///
@@ -351,6 +412,20 @@ pub fn caret_is_at_start_of_block(from: &SubSelection, to: &SubSelection, block_
     }
 }
 
+pub fn caret_is_at_end_of_block(from: &SubSelection, to: &SubSelection, block_map: &BlockMap) -> Result<bool, StepError> {
+    if from != to {
+        return Ok(false)
+    }
+
+    let block = block_map.get_inline_block(&from.block_id);
+    if block.is_err() {
+        return Ok(false)
+    }
+
+    let block = block.unwrap();
+    return Ok(from.offset == block.text()?.len() && block.is_last_inline_block(block_map)?)
+}
+
 pub fn reassign_ids(
     blocks: Vec<StandardBlock>,
     top_blocks: &mut Vec<StandardBlock>,
@@ -394,6 +469,8 @@ pub fn reassign_ids(
             let mut inline_block = block_map.get_inline_block(old_inline_id)?;
             inline_block._id = new_inline_block_id.clone();
             inline_block.parent = new_std_block_id.clone();
+            // a comment stays anchored to the original text, not to pasted / duplicated copies of it
+            inline_block.marks.retain(|mark| !mark.is_comment());
             new_inline_blocks.push(new_inline_block_id);
             new_blocks.insert(old_inline_id.clone(), Block::InlineBlock(inline_block));
         }
@@ -421,7 +498,7 @@ pub fn reassign_ids(
 pub fn get_previous_block_in_tree(current_node: &StandardBlock, block_map: &BlockMap, _depth_from_root: &mut usize) -> Result<StandardBlock, StepError> {
     return match current_node.get_previous(block_map)? {
         Some(sibling_above) => {
-            if sibling_above.children.len() > 0 {
+            if sibling_above.visible_children().len() > 0 {
                 sibling_above.get_youngest_descendant(block_map)
             } else {
                 Ok(sibling_above)
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use wasm_bindgen::JsValue;
+
+use crate::{blocks::{BlockMap, standard_blocks::{StandardBlock, StandardBlockType, table_of_contents_block::TableOfContentsEntry}},
+    steps_generator::StepError};
+
+use super::get_standard_blocks_in_order;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct OutlineItem {
+    pub block_id: String,
+    pub text: String,
+    pub level: usize,
+    pub anchor: String,
+    pub children: Vec<OutlineItem>
+}
+
+impl OutlineItem {
+    pub fn to_js_obj(&self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let children = js_sys::Array::new();
+        for child in &self.children {
+            children.push(&child.to_js_obj()?);
+        }
+        let fields = [
+            ("block_id", JsValue::from_str(&self.block_id)),
+            ("text", JsValue::from_str(&self.text)),
+            ("level", JsValue::from_f64(self.level as f64)),
+            ("anchor", JsValue::from_str(&self.anchor)),
+            ("children", JsValue::from(children))
+        ];
+        for (field, value) in fields {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(field), &value)
+                .map_err(|_| StepError(format!("Failed to set {} on outline js obj", field)))?;
+        }
+        return Ok(JsValue::from(obj))
+    }
+}
+
+/// Nested outline of the H1 / H2 / H3 blocks under the root (in document order)
+/// -> a heading is nested under the closest heading above it with a smaller level
+/// -> eg: an H3 directly after an H1 is a child of the H1
+pub fn get_outline(root_block_id: &String, block_map: &BlockMap) -> Result<Vec<OutlineItem>, StepError> {
+    let headings = get_headings(root_block_id, block_map)?;
+    let mut i = 0;
+    return Ok(nest_headings(&headings, &mut i, 0))
+}
+
+/// Flat list of the headings under the root (in document order) with unique anchors
+pub fn get_headings(root_block_id: &String, block_map: &BlockMap) -> Result<Vec<TableOfContentsEntry>, StepError> {
+    let mut headings = vec![];
+    for block in get_standard_blocks_in_order(root_block_id, block_map)? {
+        if let Some(level) = heading_level(&block) {
+            headings.push(TableOfContentsEntry {
+                block_id: block.id(),
+                text: get_text(&block, block_map)?,
+                level,
+                anchor: String::new()
+            });
+        }
+    }
+
+    let mut used_anchors = HashSet::new();
+    for heading in headings.iter_mut() {
+        heading.anchor = unique_anchor(slugify(&heading.text), &mut used_anchors);
+    }
+    return Ok(headings)
+}
+
+/// Lowercase, letters & numbers kept, whitespace / dashes / underscores collapsed into a single "-"
+/// -> "section" if nothing is left (eg: an empty heading)
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || c == '-' || c == '_') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    return match slug.is_empty() {
+        true => "section".to_string(),
+        false => slug
+    }
+}
+
+/// Repeated anchors get "-1", "-2", ... appended
+fn unique_anchor(slug: String, used_anchors: &mut HashSet<String>) -> String {
+    let mut anchor = slug.clone();
+    let mut i = 1;
+    while used_anchors.contains(&anchor) {
+        anchor = format!("{}-{}", slug, i);
+        i += 1;
+    }
+    used_anchors.insert(anchor.clone());
+    return anchor
+}
+
+fn nest_headings(headings: &Vec<TableOfContentsEntry>, i: &mut usize, parent_level: usize) -> Vec<OutlineItem> {
+    let mut items = vec![];
+    while *i < headings.len() && headings[*i].level > parent_level {
+        let heading = &headings[*i];
+        *i += 1;
+        items.push(OutlineItem {
+            block_id: heading.block_id.clone(),
+            text: heading.text.clone(),
+            level: heading.level,
+            anchor: heading.anchor.clone(),
+            children: nest_headings(headings, i, heading.level)
+        });
+    }
+    return items
+}
+
+pub fn heading_level(block: &StandardBlock) -> Option<usize> {
+    return match block.content {
+        StandardBlockType::H1(_) => Some(1),
+        StandardBlockType::H2(_) => Some(2),
+        StandardBlockType::H3(_) => Some(3),
+        _ => None
+    }
+}
+
+fn get_text(block: &StandardBlock, block_map: &BlockMap) -> Result<String, StepError> {
+    let mut text = String::new();
+    for inline_block in block.get_inline_blocks(block_map)? {
+        text.push_str(&inline_block.text()?.clone().to_string());
+    }
+    return Ok(text)
+}
@@ -0,0 +1,198 @@
+use crate::{blocks::{BlockMap, Block, standard_blocks::StandardBlock, inline_blocks::text_block::StringUTF16}, step::{Step, ReplaceStep, ReplaceSlice, SplitStep},
+    steps_generator::{StepError, selection::{Selection, SubSelection}}};
+
+use super::{get_standard_blocks_in_order, find_root_block_id};
+
+/// Where a position in the old block map is after the step was applied (giving the new block map).
+/// Positions are compared as raw selections (standard block id & offset in its text),
+/// so splitting / merging inline blocks (eg: clean_block_after_transform) doesn't move them.
+/// -> replace: positions after the replaced text shift, positions inside it go to the end of the new text
+/// -> split: positions after the split go to the new block
+/// -> text otherwise changed: positions are mapped around the changed text
+/// -> block removed from the page: end of the closest previous block that is still in the page
+/// Returns None if none of the page's text is left
+pub fn map_position(
+    position: &SubSelection,
+    step: &Step,
+    old_block_map: &BlockMap,
+    new_block_map: &BlockMap
+) -> Result<Option<SubSelection>, StepError> {
+    if let Some(mapped_position) = map_position_in_remaining_blocks(position, step, old_block_map, new_block_map)? {
+        return Ok(Some(mapped_position))
+    }
+    let std_block_id = match old_block_map.get_block(&position.get_deepest_subselection().block_id)? {
+        Block::InlineBlock(inline_block) => inline_block.parent,
+        block => block.id()
+    };
+    return closest_remaining_position(&std_block_id, old_block_map, new_block_map)
+}
+
+/// Same as map_position, but None when the position's block was removed from the page
+pub fn map_position_in_remaining_blocks(
+    position: &SubSelection,
+    step: &Step,
+    old_block_map: &BlockMap,
+    new_block_map: &BlockMap
+) -> Result<Option<SubSelection>, StepError> {
+    let deepest_position = position.get_deepest_subselection();
+    let raw_position = match old_block_map.get_block(&deepest_position.block_id)? {
+        Block::InlineBlock(_) => deepest_position.to_raw_selection(old_block_map)?,
+        _ => return Ok(match is_in_document(&deepest_position.block_id, new_block_map) {
+            true => Some(position.clone()),
+            false => None
+        })
+    };
+
+    let mapped_position = match step {
+        Step::ReplaceStep(replace_step) => map_through_replace(&raw_position, replace_step, old_block_map, new_block_map)?,
+        Step::SplitStep(split_step) => map_through_split(&raw_position, split_step, old_block_map)?,
+        _ => None
+    };
+    let mapped_position = match mapped_position {
+        Some(mapped_position) => Some(mapped_position),
+        None => map_through_text_changes(&raw_position, old_block_map, new_block_map)?
+    };
+    return match mapped_position {
+        Some(mapped_position) => Ok(Some(mapped_position.real_selection_from_raw(new_block_map)?)),
+        None => Ok(None)
+    }
+}
+
+pub fn map_selection(
+    selection: &Selection,
+    step: &Step,
+    old_block_map: &BlockMap,
+    new_block_map: &BlockMap
+) -> Result<Option<Selection>, StepError> {
+    let anchor = map_position(&selection.anchor, step, old_block_map, new_block_map)?;
+    let head = map_position(&selection.head, step, old_block_map, new_block_map)?;
+    return Ok(match (anchor, head) {
+        (Some(anchor), Some(head)) => Some(Selection { anchor, head }),
+        _ => None
+    })
+}
+
+/// Text replaces from -> to. When they are in different standard blocks, the rest of the "to" block is joined onto the "from" block
+fn map_through_replace(
+    raw_position: &SubSelection,
+    replace_step: &ReplaceStep,
+    old_block_map: &BlockMap,
+    new_block_map: &BlockMap
+) -> Result<Option<SubSelection>, StepError> {
+    let text = match &replace_step.slice {
+        ReplaceSlice::String(text) => StringUTF16::from_str(text),
+        ReplaceSlice::Blocks(_) => return Ok(None)
+    };
+    let from_is_text = matches!(old_block_map.get_block(&replace_step.from.get_deepest_subselection().block_id)?, Block::InlineBlock(_));
+    let to_is_text = matches!(old_block_map.get_block(&replace_step.to.get_deepest_subselection().block_id)?, Block::InlineBlock(_));
+    if !from_is_text || !to_is_text {
+        return Ok(None)
+    }
+    let from = replace_step.from.to_raw_selection(old_block_map)?;
+    let to = replace_step.to.to_raw_selection(old_block_map)?;
+    let end_of_new_text = SubSelection::from(from.block_id.clone(), from.offset + text.len(), None);
+
+    if raw_position.block_id == to.block_id && raw_position.offset >= to.offset {
+        return Ok(Some(SubSelection::from(from.block_id, end_of_new_text.offset + raw_position.offset - to.offset, None)))
+    } else if raw_position.block_id == from.block_id && raw_position.offset < from.offset {
+        return Ok(Some(raw_position.clone()))
+    } else if raw_position.block_id == from.block_id || raw_position.block_id == to.block_id
+        || !is_in_document(&raw_position.block_id, new_block_map) {
+        return Ok(Some(end_of_new_text))
+    }
+    return Ok(None)
+}
+
+fn map_through_split(raw_position: &SubSelection, split_step: &SplitStep, old_block_map: &BlockMap) -> Result<Option<SubSelection>, StepError> {
+    let split_at = split_step.subselection.to_raw_selection(old_block_map)?;
+    if raw_position.block_id == split_at.block_id && raw_position.offset >= split_at.offset {
+        return Ok(Some(SubSelection::from(split_step.new_std_block_id.clone(), raw_position.offset - split_at.offset, None)))
+    }
+    return Ok(None)
+}
+
+/// Compares the block's text before & after, keeping the position in the unchanged start or end of the text.
+/// Positions in the changed text go to the end of the new text
+fn map_through_text_changes(raw_position: &SubSelection, old_block_map: &BlockMap, new_block_map: &BlockMap) -> Result<Option<SubSelection>, StepError> {
+    if !is_in_document(&raw_position.block_id, new_block_map) {
+        return Ok(None)
+    }
+    let new_block = new_block_map.get_standard_block(&raw_position.block_id)?;
+    if !new_block.has_content() {
+        return Ok(None)
+    }
+    let old_text = get_text(&raw_position.block_id, old_block_map)?;
+    let new_text = get_text(&raw_position.block_id, new_block_map)?;
+    let mut prefix = 0;
+    while prefix < old_text.len() && prefix < new_text.len() && old_text[prefix] == new_text[prefix] {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while suffix < old_text.len() - prefix && suffix < new_text.len() - prefix
+        && old_text[old_text.len() - 1 - suffix] == new_text[new_text.len() - 1 - suffix] {
+        suffix += 1;
+    }
+
+    let offset = if raw_position.offset <= prefix {
+        raw_position.offset
+    } else if raw_position.offset >= old_text.len() - suffix {
+        raw_position.offset + new_text.len() - old_text.len()
+    } else {
+        new_text.len() - suffix
+    };
+    return Ok(Some(SubSelection::from(raw_position.block_id.clone(), offset, None)))
+}
+
+fn closest_remaining_position(block_id: &String, old_block_map: &BlockMap, new_block_map: &BlockMap) -> Result<Option<SubSelection>, StepError> {
+    let root_block_id = match find_root_block_id(block_id, old_block_map) {
+        Some(root_block_id) => root_block_id,
+        None => return Ok(None)
+    };
+    let blocks: Vec<StandardBlock> = get_standard_blocks_in_order(&root_block_id, old_block_map)?
+        .into_iter()
+        .filter(|block| block.has_content())
+        .collect();
+    let index = blocks.iter().position(|block| &block.id() == block_id).unwrap_or(blocks.len());
+    for block in blocks[..index].iter().rev() {
+        if is_in_document(&block.id(), new_block_map) && new_block_map.get_standard_block(&block.id())?.has_content() {
+            let len = get_text(&block.id(), new_block_map)?.len();
+            return Ok(Some(SubSelection::from(block.id(), len, None).real_selection_from_raw(new_block_map)?))
+        }
+    }
+    for block in blocks.iter().skip(index + 1) {
+        if is_in_document(&block.id(), new_block_map) && new_block_map.get_standard_block(&block.id())?.has_content() {
+            return Ok(Some(SubSelection::from(block.id(), 0, None).real_selection_from_raw(new_block_map)?))
+        }
+    }
+    return Ok(None)
+}
+
+/// Removed blocks can stay in the block map, so check each parent still has the block as a child
+fn is_in_document(block_id: &str, block_map: &BlockMap) -> bool {
+    let block = match block_map.get_block(block_id) {
+        Ok(block) => block,
+        Err(_) => return false
+    };
+    let parent_id = match block {
+        Block::Root(_) => return true,
+        block => match block.parent() {
+            Ok(parent_id) => parent_id,
+            Err(_) => return false
+        }
+    };
+    let is_child_of_parent = match block_map.get_block(&parent_id) {
+        Ok(Block::StandardBlock(parent)) => parent.children.contains(&block_id.to_string())
+            || parent.content_block().map(|content| content.inline_blocks.contains(&block_id.to_string())).unwrap_or(false),
+        Ok(Block::Root(parent)) => parent.children.contains(&block_id.to_string()),
+        _ => false
+    };
+    return is_child_of_parent && is_in_document(&parent_id, block_map)
+}
+
+fn get_text(std_block_id: &str, block_map: &BlockMap) -> Result<Vec<u16>, StepError> {
+    let mut text = vec![];
+    for inline_block in block_map.get_standard_block(std_block_id)?.get_inline_blocks(block_map)? {
+        text.extend(inline_block.text()?.0.iter());
+    }
+    return Ok(text)
+}
@@ -0,0 +1,98 @@
+use serde_json::{Value, json};
+use wasm_bindgen::JsValue;
+
+use crate::{blocks::BlockMap, mark::Color, step::Step,
+    steps_generator::{StepError, selection::Selection},
+    frontend_interface::{get_js_field, get_js_field_as_string},
+    backend_interface::get_json_field_as_string};
+
+use super::position_mapping::map_position_in_remaining_blocks;
+
+/// Another user's selection on the page, shown as a remote cursor
+#[derive(Debug, PartialEq, Clone)]
+pub struct RemotePresence {
+    pub user_id: String,
+    pub color: Color,
+    pub selection: Selection
+}
+
+impl RemotePresence {
+    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let fields = [
+            ("user_id", JsValue::from_str(&self.user_id)),
+            ("color", JsValue::from_str(&self.color.to_string())),
+            ("selection", self.selection.to_js_obj()?)
+        ];
+        for (field, value) in fields {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(field), &value)
+                .map_err(|_| StepError(format!("Failed to set {} on presence js obj", field)))?;
+        }
+        return Ok(JsValue::from(obj))
+    }
+
+    pub fn from_js_obj(obj: &JsValue) -> Result<Self, StepError> {
+        let selection = get_js_field(obj, "selection")?;
+        return Ok(Self {
+            user_id: get_js_field_as_string(obj, "user_id")?,
+            color: Color::from_str(&get_js_field_as_string(obj, "color")?)?,
+            selection: Selection::from_js_obj(js_sys::Object::from(selection))?
+        })
+    }
+
+    pub fn to_json(self) -> Result<Value, StepError> {
+        return Ok(json!({
+            "user_id": self.user_id,
+            "color": self.color.to_string(),
+            "selection": self.selection.to_json()?
+        }))
+    }
+
+    pub fn from_json(json: &Value) -> Result<Self, StepError> {
+        let selection = json.get("selection").ok_or(StepError(format!("Presence does not have selection field: {}", json)))?.clone();
+        return Ok(Self {
+            user_id: get_json_field_as_string(json, "user_id")?,
+            color: Color::from_str(&get_json_field_as_string(json, "color")?)?,
+            selection: Selection::from_json(selection)?
+        })
+    }
+
+    /// None when the anchor or head's block was removed from the page
+    pub fn map_through_step(self, step: &Step, old_block_map: &BlockMap, new_block_map: &BlockMap) -> Result<Option<Self>, StepError> {
+        let anchor = map_position_in_remaining_blocks(&self.selection.anchor, step, old_block_map, new_block_map)?;
+        let head = map_position_in_remaining_blocks(&self.selection.head, step, old_block_map, new_block_map)?;
+        return Ok(match (anchor, head) {
+            (Some(anchor), Some(head)) => Some(Self { selection: Selection { anchor, head }, ..self }),
+            _ => None
+        })
+    }
+}
+
+/// Replaces the user's presence if they already have one
+pub fn update_presence(presences: &mut Vec<RemotePresence>, presence: RemotePresence) {
+    match presences.iter().position(|existing| existing.user_id == presence.user_id) {
+        Some(i) => presences[i] = presence,
+        None => presences.push(presence)
+    };
+}
+
+pub fn remove_presence(presences: &mut Vec<RemotePresence>, user_id: &str) {
+    presences.retain(|presence| presence.user_id != user_id);
+}
+
+/// Remaps every presence through a step (applied locally or received from another user),
+/// dropping presences whose blocks were removed or can't be mapped
+pub fn map_presences_through_step(
+    presences: Vec<RemotePresence>,
+    step: &Step,
+    old_block_map: &BlockMap,
+    new_block_map: &BlockMap
+) -> Vec<RemotePresence> {
+    let mut mapped_presences = vec![];
+    for presence in presences {
+        if let Ok(Some(presence)) = presence.map_through_step(step, old_block_map, new_block_map) {
+            mapped_presences.push(presence);
+        }
+    }
+    return mapped_presences
+}
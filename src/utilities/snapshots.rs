@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::JsValue;
+
+use crate::{blocks::{BlockMap, Block}, steps_generator::StepError, new_ids::NewIds,
+    frontend_interface::{get_js_field, get_js_field_as_string, get_js_field_as_f64}};
+
+use super::get_standard_blocks_in_order;
+
+/// A named copy of a whole page at a point in time.
+/// Restoring it diffs the current page against the copy (see generate_diff_steps),
+/// so blocks that still exist keep their ids
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub id: String,
+    pub name: String,
+    pub timestamp: f64, // ms since epoch
+    pub root_block_id: String,
+    pub block_map: BlockMap
+}
+
+impl Snapshot {
+    pub fn summary(&self) -> SnapshotSummary {
+        return SnapshotSummary {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            timestamp: self.timestamp,
+            root_block_id: self.root_block_id.clone()
+        }
+    }
+
+    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::from(self.summary().to_js_obj()?);
+        js_sys::Reflect::set(&obj, &JsValue::from_str("block_map"), &JsValue::from(self.block_map.to_js_map()?))
+            .map_err(|_| StepError("Failed to set block_map on snapshot js obj".to_string()))?;
+        return Ok(JsValue::from(obj))
+    }
+
+    pub fn from_js_obj(obj: &JsValue) -> Result<Self, StepError> {
+        let block_map = get_js_field(obj, "block_map")?;
+        return Ok(Self {
+            id: get_js_field_as_string(obj, "id")?,
+            name: get_js_field_as_string(obj, "name")?,
+            timestamp: get_js_field_as_f64(obj, "timestamp")?,
+            root_block_id: get_js_field_as_string(obj, "root_block_id")?,
+            block_map: BlockMap::Js(js_sys::Map::from(block_map))
+        })
+    }
+}
+
+/// A snapshot without its blocks, for listing
+#[derive(Debug, PartialEq, Clone)]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub name: String,
+    pub timestamp: f64,
+    pub root_block_id: String
+}
+
+impl SnapshotSummary {
+    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let fields = [
+            ("id", JsValue::from_str(&self.id)),
+            ("name", JsValue::from_str(&self.name)),
+            ("timestamp", JsValue::from_f64(self.timestamp)),
+            ("root_block_id", JsValue::from_str(&self.root_block_id))
+        ];
+        for (field, value) in fields {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(field), &value)
+                .map_err(|_| StepError(format!("Failed to set {} on snapshot js obj", field)))?;
+        }
+        return Ok(JsValue::from(obj))
+    }
+}
+
+/// Copies the root and every block under it (children & inline blocks) into a new block map,
+/// so later edits to the page don't change the snapshot
+pub fn capture_snapshot(
+    name: String,
+    timestamp: f64,
+    root_block_id: &String,
+    block_map: &BlockMap,
+    new_ids: &mut NewIds
+) -> Result<Snapshot, StepError> {
+    let mut blocks: HashMap<String, String> = HashMap::new();
+    let root_block = block_map.get_root_block(root_block_id)?;
+    blocks.insert(root_block.id(), Block::Root(root_block.clone()).to_json()?.to_string());
+    for block in get_standard_blocks_in_order(&root_block.id(), block_map)? {
+        if block.has_content() {
+            for inline_block in block.get_inline_blocks(block_map)? {
+                blocks.insert(inline_block.id(), Block::InlineBlock(inline_block).to_json()?.to_string());
+            }
+        }
+        blocks.insert(block.id(), Block::StandardBlock(block).to_json()?.to_string());
+    }
+    return Ok(Snapshot {
+        id: new_ids.get_id()?,
+        name,
+        timestamp,
+        root_block_id: root_block_id.clone(),
+        block_map: BlockMap::Rust(blocks)
+    })
+}
+
+/// The page's snapshots, newest first
+pub fn list_snapshots(root_block_id: &String, snapshots: &Vec<Snapshot>) -> Vec<SnapshotSummary> {
+    let mut summaries: Vec<SnapshotSummary> = snapshots.iter()
+        .filter(|snapshot| &snapshot.root_block_id == root_block_id)
+        .map(|snapshot| snapshot.summary())
+        .collect();
+    summaries.sort_by(|a, b| b.timestamp.total_cmp(&a.timestamp));
+    return summaries
+}
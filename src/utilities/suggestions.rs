@@ -0,0 +1,154 @@
+use wasm_bindgen::JsValue;
+
+use crate::{blocks::{BlockMap, inline_blocks::InlineBlock, standard_blocks::StandardBlock}, mark::Mark, steps_generator::{StepError, selection::SubSelection}};
+
+use super::get_standard_blocks_in_order;
+
+/// A pending suggestion & the text it would insert and / or delete
+#[derive(Debug, PartialEq, Clone)]
+pub struct Suggestion {
+    pub suggestion_id: String,
+    pub author: String,
+    pub inserted_text: String,
+    pub deleted_text: String
+}
+
+impl Suggestion {
+    pub fn to_js_obj(self) -> Result<JsValue, StepError> {
+        let obj = js_sys::Object::new();
+        let fields = [
+            ("suggestion_id", JsValue::from_str(&self.suggestion_id)),
+            ("author", JsValue::from_str(&self.author)),
+            ("inserted_text", JsValue::from_str(&self.inserted_text)),
+            ("deleted_text", JsValue::from_str(&self.deleted_text))
+        ];
+        for (field, value) in fields {
+            js_sys::Reflect::set(&obj, &JsValue::from_str(field), &value)
+                .map_err(|_| StepError(format!("Failed to set {} on suggestion js obj", field)))?;
+        }
+        return Ok(JsValue::from(obj))
+    }
+}
+
+/// Every suggestion under the root, ordered by where they start in the document
+pub fn get_suggestions(root_block_id: &String, block_map: &BlockMap) -> Result<Vec<Suggestion>, StepError> {
+    let mut suggestions: Vec<Suggestion> = vec![];
+    for block in get_standard_blocks_in_order(root_block_id, block_map)?.into_iter().filter(|block| block.has_content()) {
+        for inline_block in block.get_inline_blocks(block_map)? {
+            let text = inline_block.text()?.clone().to_string();
+            for mark in &inline_block.marks {
+                let (suggestion_mark, is_insertion) = match mark {
+                    Mark::Insertion(suggestion_mark) => (suggestion_mark, true),
+                    Mark::Deletion(suggestion_mark) => (suggestion_mark, false),
+                    _ => continue
+                };
+                let i = match suggestions.iter().position(|suggestion| suggestion.suggestion_id == suggestion_mark.id) {
+                    Some(i) => i,
+                    None => {
+                        suggestions.push(Suggestion {
+                            suggestion_id: suggestion_mark.id.clone(),
+                            author: suggestion_mark.author.clone(),
+                            inserted_text: String::new(),
+                            deleted_text: String::new()
+                        });
+                        suggestions.len() - 1
+                    }
+                };
+                match is_insertion {
+                    true => suggestions[i].inserted_text.push_str(&text),
+                    false => suggestions[i].deleted_text.push_str(&text)
+                };
+            }
+        }
+    }
+    return Ok(suggestions)
+}
+
+/// Inline blocks under the root that are inserted or deleted by the suggestion
+pub fn get_inline_blocks_with_suggestion(root_block_id: &String, suggestion_id: &str, block_map: &BlockMap) -> Result<Vec<InlineBlock>, StepError> {
+    let mut inline_blocks = vec![];
+    for block in get_standard_blocks_in_order(root_block_id, block_map)?.into_iter().filter(|block| block.has_content()) {
+        for inline_block in block.get_inline_blocks(block_map)? {
+            if inline_block.marks.iter().any(|mark| is_suggestion_with_id(mark, suggestion_id)) {
+                inline_blocks.push(inline_block);
+            }
+        }
+    }
+    return Ok(inline_blocks)
+}
+
+pub fn is_suggestion_with_id(mark: &Mark, suggestion_id: &str) -> bool {
+    return match mark {
+        Mark::Insertion(suggestion) | Mark::Deletion(suggestion) => suggestion.id == suggestion_id,
+        _ => false
+    }
+}
+
+/// Text the author has suggested inserting, which they can still edit directly
+pub fn is_insertion_by(inline_block: &InlineBlock, author: &str) -> bool {
+    return inline_block.marks.iter().any(|mark| matches!(mark, Mark::Insertion(suggestion) if suggestion.author == author))
+}
+
+/// Id of a suggestion by the author in or next to the selected text,
+/// so deleting or typing beside it extends that suggestion instead of starting a new one
+pub fn adjacent_suggestion_id(from: &SubSelection, to: &SubSelection, author: &str, block_map: &BlockMap) -> Result<Option<String>, StepError> {
+    let from_raw = from.to_raw_selection(block_map)?;
+    let to_raw = to.to_raw_selection(block_map)?;
+    if from_raw.block_id != to_raw.block_id {
+        return Ok(None)
+    }
+    let mut start = 0;
+    for inline_block in block_map.get_standard_block(&from_raw.block_id)?.get_inline_blocks(block_map)? {
+        let end = start + inline_block.text()?.len();
+        if end >= from_raw.offset && start <= to_raw.offset {
+            for mark in &inline_block.marks {
+                match mark {
+                    Mark::Insertion(suggestion) | Mark::Deletion(suggestion) if suggestion.author == author => return Ok(Some(suggestion.id.clone())),
+                    _ => {}
+                };
+            }
+        }
+        start = end;
+    }
+    return Ok(None)
+}
+
+pub fn is_suggested_deletion(inline_block: &InlineBlock) -> bool {
+    return inline_block.marks.iter().any(|mark| matches!(mark, Mark::Deletion(_)))
+}
+
+/// The closest character to the raw offset that isn't already suggested for deletion:
+/// -> backwards: the character before the offset
+/// -> forwards: the character after the offset
+/// Returns the inline block it is in & its offset in that inline block
+pub fn closest_undeleted_char(
+    std_block: &StandardBlock,
+    raw_offset: usize,
+    backwards: bool,
+    block_map: &BlockMap
+) -> Result<Option<(InlineBlock, usize)>, StepError> {
+    let mut inline_blocks_with_start = vec![];
+    let mut start = 0;
+    for inline_block in std_block.get_inline_blocks(block_map)? {
+        let len = inline_block.text()?.len();
+        inline_blocks_with_start.push((inline_block, start));
+        start += len;
+    }
+    if backwards {
+        inline_blocks_with_start.reverse();
+    }
+    for (inline_block, start) in inline_blocks_with_start {
+        let end = start + inline_block.text()?.len();
+        if is_suggested_deletion(&inline_block) {
+            continue
+        }
+        if backwards && start < raw_offset {
+            let char_offset = usize::min(raw_offset, end) - 1;
+            return Ok(Some((inline_block, char_offset - start)))
+        } else if !backwards && end > raw_offset {
+            let char_offset = usize::max(raw_offset, start);
+            return Ok(Some((inline_block, char_offset - start)))
+        }
+    }
+    return Ok(None)
+}
@@ -14,9 +14,7 @@ pub fn splice_children(
         child.parent = block.id();
         block_map.update_block(Block::StandardBlock(child), blocks_to_update)?;
     }
-    let mut children = block.children()?.clone();
-    children.splice(range, new_children);
-    block.update_children(children)?;
+    block.splice_children(range.start, range.end, new_children)?;
     block_map.update_block(block.clone(), blocks_to_update)?;
     return Ok(())
 }
@@ -33,9 +31,7 @@ pub fn splice_children_on_std_block(
         child.parent = block.id();
         block_map.update_block(Block::StandardBlock(child), blocks_to_update)?;
     }
-    let mut children = block.children.clone();
-    children.splice(range, new_children);
-    block.children = children;
+    block.splice_children(range, new_children);
     block_map.update_block(Block::StandardBlock(block.clone()), blocks_to_update)?;
     return Ok(())
 }
@@ -178,7 +178,7 @@ mod tests {
             inline_block1.to_string(), inline_block2.to_string(), paragraph_block1.to_string(), paragraph_block2.to_string(), root_block.to_string(),
             paragraph_block3.to_string(), paragraph_block4.to_string(), paragraph_block5.to_string()
         ]).unwrap();
-        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false } });
+        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false, smart_typography: false, suggesting_as: None, last_conversion: None } });
         let sub_selection = SubSelection::from(inline_block_id2.clone(), 4, None);
         let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
 
@@ -196,7 +196,7 @@ mod tests {
         let block_map = BlockMap::from(vec![
             inline_block1.to_string(), inline_block2.to_string(), paragraph_block1.to_string(), paragraph_block2.to_string(), root_block.to_string()
         ]).unwrap();
-        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false } });
+        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false, smart_typography: false, suggesting_as: None, last_conversion: None } });
         let sub_selection = SubSelection::from(inline_block_id1.clone(), 4, None);
         let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
 
@@ -262,7 +262,7 @@ mod tests {
         let block_map = BlockMap::from(vec![
             inline_block1.to_string(), inline_block2.to_string(), paragraph_block1.to_string(), paragraph_block2.to_string(), root_block.to_string()
         ]).unwrap();
-        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false } });
+        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false, smart_typography: false, suggesting_as: None, last_conversion: None } });
         let sub_selection = SubSelection::from(inline_block_id2.clone(), 4, None);
         let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
 
@@ -358,7 +358,7 @@ mod tests {
             inline_block1.to_string(), inline_block2.to_string(), paragraph_block1.to_string(), paragraph_block2.to_string(), root_block.to_string(), paragraph_block3.to_string(),
             inline_block3.to_string()
         ]).unwrap();
-        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: false, meta_down: false, ctrl_down: false, alt_down: false } });
+        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: false, meta_down: false, ctrl_down: false, alt_down: false, smart_typography: false, suggesting_as: None, last_conversion: None } });
         let from_sub_selection = SubSelection {
             block_id: paragraph_block_id1.clone(),
             offset: 0,
@@ -489,7 +489,7 @@ mod tests {
             inline_block1.to_string(), inline_block2.to_string(), paragraph_block1.to_string(), paragraph_block2.to_string(), root_block.to_string(),
             paragraph_block3.to_string(), paragraph_block4.to_string(), inline_block3.to_string()
         ]).unwrap();
-        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false } });
+        let event = Event::KeyPress(KeyPress { key: Key::Tab, metadata: KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: false, smart_typography: false, suggesting_as: None, last_conversion: None } });
         let anchor_sub_selection = SubSelection { block_id: paragraph_block_id2.clone(), offset: 0, subselection: Some(Box::new(SubSelection {
             block_id: inline_block_id2.clone(),
             offset: 4,
@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{RootBlock, BlockMap}, mark::{Mark, CommentMark},
+        steps_generator::{event::{Event, CommentEvent}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, step::{Step, ResolveCommentStep, DeleteCommentStep},
+        utilities::comments::get_comments, new_ids::NewIds, custom_copy::CustomCopy};
+    use serde_json::json;
+
+    /// root -> [a: "Hello " "world" (comment c1), b: "Again" (comment c1 & c2)]
+    fn block_map() -> BlockMap {
+        return BlockMap::from(vec![
+            json!({ "_id": "a1", "kind": "inline", "_type": "text", "content": { "text": "Hello " }, "marks": [], "parent": "a" }).to_string(),
+            json!({ "_id": "a2", "kind": "inline", "_type": "text", "content": { "text": "world" }, "marks": ["comment(c1)"], "parent": "a" }).to_string(),
+            json!({ "_id": "a", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["a1", "a2"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            json!({ "_id": "b1", "kind": "inline", "_type": "text", "content": { "text": "Again" }, "marks": ["comment(c1)", "comment(c2, resolved)"], "parent": "b" }).to_string(),
+            json!({ "_id": "b", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["b1"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            RootBlock::json_from("root".to_string(), vec!["a".to_string(), "b".to_string()]).to_string()
+        ]).unwrap()
+    }
+
+    #[test]
+    fn comment_mark_round_trips_through_string() -> Result<(), StepError> {
+        let mark = Mark::from_str("comment(c2, resolved)")?;
+        assert_eq!(mark, Mark::Comment(CommentMark { id: "c2".to_string(), resolved: true }));
+        assert_eq!(mark.to_string(), "comment(c2, resolved)".to_string());
+        assert!(Mark::from_str("comment()").is_err());
+        return Ok(())
+    }
+
+    #[test]
+    fn can_list_comments_with_their_ranges() -> Result<(), StepError> {
+        let comments = get_comments(&"root".to_string(), &block_map())?;
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].comment_id, "c1".to_string());
+        assert_eq!(comments[0].selection, Selection::from(
+            SubSelection::from("a2".to_string(), 0, None),
+            SubSelection::from("b1".to_string(), 5, None)
+        ));
+        assert_eq!(comments[0].text, "world\nAgain".to_string());
+        assert!(!comments[0].resolved);
+        assert_eq!(comments[1].comment_id, "c2".to_string());
+        assert!(comments[1].resolved);
+        return Ok(())
+    }
+
+    #[test]
+    fn adding_a_comment_marks_the_selected_text() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let event = Event::Comment(CommentEvent::Add("c3".to_string()));
+        let selection = Selection::from(SubSelection::from("a1".to_string(), 0, None), SubSelection::from("a1".to_string(), 5, None));
+        let steps = generate_steps(&event, &block_map(), selection, &CustomCopy::new(), &mut new_ids)?;
+        match &steps[0] {
+            Step::AddMarkStep(mark_step) => assert_eq!(mark_step.mark, Mark::Comment(CommentMark { id: "c3".to_string(), resolved: false })),
+            step => panic!("Expected an add mark step, got: {:?}", step)
+        };
+
+        let updated_state = actualise_steps(steps, block_map(), &mut new_ids, CustomCopy::new())?;
+        let comments = get_comments(&"root".to_string(), &updated_state.block_map)?;
+        let added = comments.iter().find(|comment| comment.comment_id == "c3".to_string()).unwrap();
+        assert_eq!(added.text, "Hello".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn can_resolve_a_comment() -> Result<(), StepError> {
+        let step = Step::ResolveComment(ResolveCommentStep { root_block_id: "root".to_string(), comment_id: "c1".to_string(), resolved: true });
+        let updated_state = actualise_steps(vec![step], block_map(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+        let comments = get_comments(&"root".to_string(), &updated_state.block_map)?;
+        assert!(comments[0].resolved);
+        assert_eq!(comments[0].text, "world\nAgain".to_string());
+        assert!(updated_state.blocks_to_update.contains(&"a2".to_string()));
+        assert!(updated_state.blocks_to_update.contains(&"b1".to_string()));
+        return Ok(())
+    }
+
+    #[test]
+    fn deleting_a_comment_removes_its_marks_and_merges_inline_blocks() -> Result<(), StepError> {
+        let step = Step::DeleteComment(DeleteCommentStep { root_block_id: "root".to_string(), comment_id: "c1".to_string() });
+        let updated_state = actualise_steps(vec![step], block_map(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+        let block_map = updated_state.block_map;
+
+        let a = block_map.get_standard_block("a")?;
+        assert_eq!(a.content_block()?.inline_blocks, vec!["a1".to_string()]);
+        assert_eq!(block_map.get_inline_block(&"a1".to_string())?.text()?.clone().to_string(), "Hello world".to_string());
+        let b1 = block_map.get_inline_block(&"b1".to_string())?;
+        assert_eq!(b1.marks, vec![Mark::Comment(CommentMark { id: "c2".to_string(), resolved: true })]);
+        return Ok(())
+    }
+}
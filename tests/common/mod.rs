@@ -0,0 +1,115 @@
+// each test file only uses some of these
+#![allow(dead_code)]
+
+use rust_mirror::{blocks::{RootBlock, BlockMap}, step::Step, steps_generator::{StepError, event::Event, selection::{SubSelection, Selection}, generate_steps},
+    steps_actualisor::{actualise_steps, UpdatedState}, new_ids::NewIds, custom_copy::CustomCopy};
+use serde_json::{json, Value};
+
+/// A standard block with one text inline block, whose id is "<id>_inline"
+pub fn block_with_text(id: &str, _type: &str, text: &str, children: Vec<&str>, parent: &str) -> Vec<String> {
+    return block_with_content(id, _type, text, json!({}), children, parent)
+}
+
+pub fn todo_with_text(id: &str, text: &str, completed: bool, children: Vec<&str>, parent: &str) -> Vec<String> {
+    return block_with_content(id, "to-do list", text, json!({ "completed": completed }), children, parent)
+}
+
+/// Like block_with_text, with extra fields in the standard block's content
+pub fn block_with_content(id: &str, _type: &str, text: &str, content: Value, children: Vec<&str>, parent: &str) -> Vec<String> {
+    let inline_id = format!("{}_inline", id);
+    let mut content = content;
+    content["inline_blocks"] = json!([inline_id]);
+    return vec![
+        inline_block(&inline_id, text, vec![], id),
+        json!({
+            "_id": id,
+            "kind": "standard",
+            "_type": _type,
+            "content": content,
+            "children": children,
+            "marks": [],
+            "parent": parent
+        }).to_string()
+    ]
+}
+
+pub fn inline_block(id: &str, text: &str, marks: Vec<&str>, parent: &str) -> String {
+    return json!({
+        "_id": id,
+        "kind": "inline",
+        "_type": "text",
+        "content": {
+            "text": text
+        },
+        "marks": marks,
+        "parent": parent
+    }).to_string()
+}
+
+/// A childless paragraph made of the given inline blocks (see inline_block)
+pub fn paragraph(id: &str, inline_blocks: Vec<&str>, parent: &str) -> String {
+    return json!({
+        "_id": id,
+        "kind": "standard",
+        "_type": "paragraph",
+        "content": {
+            "inline_blocks": inline_blocks
+        },
+        "children": [],
+        "marks": [],
+        "parent": parent
+    }).to_string()
+}
+
+/// A layout block, the columns of a horizontal layout are vertical layouts
+pub fn layout(id: &str, horizontal: bool, widths: Vec<f64>, children: Vec<&str>, parent: &str) -> Vec<String> {
+    return vec![json!({
+        "_id": id,
+        "kind": "standard",
+        "_type": "layout",
+        "content": {
+            "horizontal": horizontal,
+            "widths": widths
+        },
+        "children": children,
+        "marks": [],
+        "parent": parent
+    }).to_string()]
+}
+
+pub fn root(id: &str, children: Vec<&str>) -> String {
+    return RootBlock::json_from(id.to_string(), children.iter().map(|id| id.to_string()).collect()).to_string()
+}
+
+pub fn position(block_id: &str, offset: usize) -> SubSelection {
+    return SubSelection::from(block_id.to_string(), offset, None)
+}
+
+/// eg: block_map(vec![block_with_text(..), block_with_text(..), vec![root(..)]])
+pub fn block_map(blocks: Vec<Vec<String>>) -> BlockMap {
+    return BlockMap::from(blocks.into_iter().flatten().collect()).unwrap()
+}
+
+/// root -> [a: "Hello world", b: "Second"]
+pub fn two_paragraphs() -> BlockMap {
+    return block_map(vec![
+        block_with_text("a", "paragraph", "Hello world", vec![], "root"),
+        block_with_text("b", "paragraph", "Second", vec![], "root"),
+        vec![root("root", vec!["a", "b"])]
+    ])
+}
+
+/// (old block map, new block map) after applying the step to two_paragraphs
+pub fn apply_to_two_paragraphs(step: &Step) -> Result<(BlockMap, BlockMap), StepError> {
+    let old_block_map = two_paragraphs();
+    let new_block_map = actualise_steps(vec![step.clone()], old_block_map.clone(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?.block_map;
+    return Ok((old_block_map, new_block_map))
+}
+
+/// Generates & actualises the event with the caret at the start of the block's text (see block_with_text)
+pub fn apply_event(event: &Event, block_map: BlockMap, caret_block_id: &str) -> Result<UpdatedState, StepError> {
+    let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+    let caret = SubSelection::from(format!("{}_inline", caret_block_id), 0, None);
+    let steps = generate_steps(event, &block_map, Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)?;
+    return actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())
+}
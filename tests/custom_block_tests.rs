@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use rust_mirror::{blocks::{RootBlock, BlockMap, Block, standard_blocks::{StandardBlockType, custom_block::{CustomBlockType, CustomBlock,
+            EnterBehaviour, BackspaceBehaviour, DeclaredBlockType, register_custom_block_type}}},
+        steps_generator::{event::{Event, KeyPress, Key}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, step::Step, new_ids::NewIds, custom_copy::CustomCopy};
+    use serde_json::{json, Map, Value};
+
+    /// A callout has text & an emoji, enter continues the callout like a list
+    struct Callout;
+
+    impl CustomBlockType for Callout {
+        fn name(&self) -> String {
+            return "callout".to_string()
+        }
+        fn has_content(&self) -> bool {
+            return true
+        }
+        fn can_have_children(&self) -> bool {
+            return false
+        }
+        fn data_from_json(&self, content: &Value) -> Result<Map<String, Value>, StepError> {
+            let emoji = content.get("emoji").and_then(|emoji| emoji.as_str())
+                .ok_or(StepError("Callout should have an emoji".to_string()))?;
+            let mut data = Map::new();
+            data.insert("emoji".to_string(), json!(emoji));
+            return Ok(data)
+        }
+        fn default_data(&self) -> Map<String, Value> {
+            let mut data = Map::new();
+            data.insert("emoji".to_string(), json!("💡"));
+            return data
+        }
+        fn enter_behaviour(&self) -> EnterBehaviour {
+            return EnterBehaviour::NewBlockOfSameType
+        }
+        fn backspace_behaviour(&self) -> BackspaceBehaviour {
+            return BackspaceBehaviour::TurnIntoParagraph
+        }
+    }
+
+    /// root -> [callout: "Note", 1: "Below"]
+    fn callout_block_map(callout_text: &str) -> BlockMap {
+        register_custom_block_type(Rc::new(Callout)).unwrap();
+        return BlockMap::from(vec![
+            json!({ "_id": "callout_inline", "kind": "inline", "_type": "text", "content": { "text": callout_text }, "marks": [], "parent": "callout" }).to_string(),
+            json!({ "_id": "callout", "kind": "standard", "_type": "callout", "content": { "inline_blocks": ["callout_inline"], "emoji": "🔥" },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            json!({ "_id": "1_inline", "kind": "inline", "_type": "text", "content": { "text": "Below" }, "marks": [], "parent": "1" }).to_string(),
+            json!({ "_id": "1", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["1_inline"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            RootBlock::json_from("root".to_string(), vec!["callout".to_string(), "1".to_string()]).to_string()
+        ]).unwrap()
+    }
+
+    fn press(key: Key, block_id: &str, offset: usize, block_map: &BlockMap) -> Result<Vec<Step>, StepError> {
+        let caret = SubSelection::from(block_id.to_string(), offset, None);
+        let event = Event::KeyPress(KeyPress::new(key, None));
+        return generate_steps(&event, block_map, Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut NewIds::hardcoded_new_ids_for_tests())
+    }
+
+    #[test]
+    fn custom_block_round_trips_through_json() -> Result<(), StepError> {
+        let block_map = callout_block_map("Note");
+        let callout = block_map.get_standard_block("callout")?;
+        assert_eq!(callout.content_block()?.inline_blocks, vec!["callout_inline".to_string()]);
+        assert_eq!(callout.content._type_as_string(), "callout".to_string());
+
+        let json = Block::StandardBlock(callout.clone()).to_json()?;
+        assert_eq!(json.get("content").unwrap(), &json!({ "inline_blocks": ["callout_inline"], "emoji": "🔥" }));
+        assert_eq!(StandardBlockType::from_json_block(&json)?, callout.content);
+
+        assert!(StandardBlockType::from_json_block(&json!({ "_type": "callout", "content": { "inline_blocks": [] } })).is_err());
+        assert!(StandardBlockType::from_json_block(&json!({ "_type": "unregistered", "content": {} })).is_err());
+        return Ok(())
+    }
+
+    #[test]
+    fn enter_continues_the_custom_block() -> Result<(), StepError> {
+        let block_map = callout_block_map("Note");
+        let steps = press(Key::Enter, "callout_inline", 4, &block_map)?;
+        let updated_state = actualise_steps(steps, block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        let new_block = updated_state.block_map.get_standard_block(&root.children[1])?;
+        assert!(matches!(&new_block.content, StandardBlockType::Custom(CustomBlock { _type, data, .. })
+            if _type == "callout" && data.get("emoji") == Some(&json!("💡"))));
+
+        // enter in an empty callout turns it into a paragraph
+        let steps = press(Key::Enter, "callout_inline", 0, &callout_block_map(""))?;
+        assert!(matches!(&steps[..], [Step::TurnInto(_)]));
+        return Ok(())
+    }
+
+    #[test]
+    fn backspace_at_start_turns_custom_block_into_paragraph() -> Result<(), StepError> {
+        let block_map = callout_block_map("Note");
+        let steps = press(Key::Backspace, "callout_inline", 0, &block_map)?;
+        let updated_state = actualise_steps(steps, block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+        assert!(matches!(updated_state.block_map.get_standard_block("callout")?.content, StandardBlockType::Paragraph(_)));
+        return Ok(())
+    }
+
+    #[test]
+    fn cannot_tab_under_custom_block_without_children() -> Result<(), StepError> {
+        let block_map = callout_block_map("Note");
+        assert_eq!(press(Key::Tab, "1_inline", 0, &block_map)?.len(), 0);
+        return Ok(())
+    }
+
+    #[test]
+    fn cannot_register_a_custom_block_type_with_a_built_in_name() {
+        for name in ["paragraph", "layout", "table of contents"] {
+            let block_type = DeclaredBlockType {
+                name: name.to_string(),
+                has_content: true,
+                can_have_children: true,
+                enter_behaviour: EnterBehaviour::NewParagraph,
+                backspace_behaviour: BackspaceBehaviour::JoinWithBlockAbove
+            };
+            assert!(register_custom_block_type(Rc::new(block_type)).is_err());
+        }
+    }
+}
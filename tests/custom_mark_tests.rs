@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use rust_mirror::{blocks::{RootBlock, BlockMap}, mark::{Mark, CustomMark, CustomMarkType, DeclaredMarkType, register_custom_mark_type},
+        steps_generator::{event::{Event, FormatBarEvent}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, new_ids::NewIds, custom_copy::CustomCopy};
+    use serde_json::json;
+
+    /// A highlight has a colour name, only one highlight can be on the text at a time
+    struct Highlight;
+
+    impl CustomMarkType for Highlight {
+        fn name(&self) -> String {
+            return "highlight".to_string()
+        }
+        fn has_value(&self) -> bool {
+            return true
+        }
+        fn value_from_str(&self, value: &str) -> Result<String, StepError> {
+            return match value {
+                "yellow" | "green" => Ok(value.to_string()),
+                _ => Err(StepError(format!("Invalid highlight: {}", value)))
+            }
+        }
+    }
+
+    /// A spoiler has no value
+    struct Spoiler;
+
+    impl CustomMarkType for Spoiler {
+        fn name(&self) -> String {
+            return "spoiler".to_string()
+        }
+    }
+
+    fn register_marks() {
+        register_custom_mark_type(Rc::new(Highlight)).unwrap();
+        register_custom_mark_type(Rc::new(Spoiler)).unwrap();
+    }
+
+    fn highlight(value: &str) -> Mark {
+        return Mark::Custom(CustomMark { name: "highlight".to_string(), value: Some(value.to_string()) })
+    }
+
+    /// root -> [1: "Hello" (marks_1) + " World" (marks_2)]
+    fn block_map(marks_1: Vec<String>, marks_2: Vec<String>) -> BlockMap {
+        register_marks();
+        return BlockMap::from(vec![
+            json!({ "_id": "inline_1", "kind": "inline", "_type": "text", "content": { "text": "Hello" }, "marks": marks_1, "parent": "1" }).to_string(),
+            json!({ "_id": "inline_2", "kind": "inline", "_type": "text", "content": { "text": " World" }, "marks": marks_2, "parent": "1" }).to_string(),
+            json!({ "_id": "1", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["inline_1", "inline_2"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            RootBlock::json_from("root".to_string(), vec!["1".to_string()]).to_string()
+        ]).unwrap()
+    }
+
+    fn apply_mark(mark: &str, from: (&str, usize), to: (&str, usize), block_map: BlockMap) -> Result<BlockMap, StepError> {
+        let event = Event::FormatBar(FormatBarEvent::Custom(CustomMark::from_str(mark)?));
+        let selection = Selection::from(SubSelection::from(from.0.to_string(), from.1, None), SubSelection::from(to.0.to_string(), to.1, None));
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut NewIds::hardcoded_new_ids_for_tests())?;
+        return Ok(actualise_steps(steps, block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?.block_map)
+    }
+
+    #[test]
+    fn custom_marks_round_trip_through_strings() -> Result<(), StepError> {
+        register_marks();
+        assert_eq!(Mark::from_str("highlight(yellow)")?, highlight("yellow"));
+        assert_eq!(highlight("yellow").to_string(), "highlight(yellow)".to_string());
+        assert_eq!(Mark::from_str("spoiler")?, Mark::Custom(CustomMark { name: "spoiler".to_string(), value: None }));
+
+        assert!(Mark::from_str("highlight(purple)").is_err());
+        assert!(Mark::from_str("highlight").is_err());
+        assert!(Mark::from_str("spoiler(yes)").is_err());
+        assert!(Mark::from_str("unregistered").is_err());
+        return Ok(())
+    }
+
+    #[test]
+    fn marks_in_the_same_group_replace_each_other() -> Result<(), StepError> {
+        register_marks();
+        assert!(highlight("yellow").is_same_type(&highlight("green")));
+        assert!(!highlight("yellow").is_same_type(&Mark::from_str("spoiler")?));
+        assert!(!highlight("yellow").is_same_type(&Mark::Bold));
+
+        let block_map = apply_mark("highlight(green)", ("inline_1", 0), ("inline_1", 5), block_map(vec!["highlight(yellow)".to_string()], vec![]))?;
+        let inline_block = block_map.get_inline_block(&"inline_1".to_string())?;
+        assert_eq!(inline_block.marks, vec![highlight("green")]);
+        return Ok(())
+    }
+
+    #[test]
+    fn can_toggle_custom_mark_and_merge_blocks() -> Result<(), StepError> {
+        let block_map = apply_mark("spoiler", ("inline_2", 0), ("inline_2", 6), block_map(vec!["spoiler".to_string()], vec![]))?;
+        let paragraph = block_map.get_standard_block("1")?;
+        let inline_blocks = &paragraph.content_block()?.inline_blocks;
+        assert_eq!(inline_blocks.len(), 1);
+        let inline_block = block_map.get_inline_block(&inline_blocks[0])?;
+        assert_eq!(inline_block.text()?.to_string(), "Hello World".to_string());
+        assert_eq!(inline_block.marks, vec![Mark::from_str("spoiler")?]);
+
+        // applying it again over text that already has it removes it
+        let block_map = apply_mark("spoiler", (&inline_blocks[0].clone(), 0), (&inline_blocks[0].clone(), 11), block_map)?;
+        let paragraph = block_map.get_standard_block("1")?;
+        let inline_block = block_map.get_inline_block(&paragraph.content_block()?.inline_blocks[0])?;
+        assert_eq!(inline_block.marks.len(), 0);
+        return Ok(())
+    }
+
+    #[test]
+    fn cannot_register_a_custom_mark_shadowed_by_a_built_in_mark() {
+        for name in ["bold", "comment", "deletion", "fore_color", "my_back_color", "turn_into"] {
+            let mark_type = DeclaredMarkType { name: name.to_string(), has_value: false, exclusivity_group: None };
+            assert!(register_custom_mark_type(Rc::new(mark_type)).is_err());
+        }
+    }
+}
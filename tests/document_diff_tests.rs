@@ -0,0 +1,178 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap, mark::Mark,
+        steps_generator::{document_diff::generate_diff_steps, StepError},
+        steps_actualisor::actualise_steps, step::Step, new_ids::NewIds, custom_copy::CustomCopy};
+    use serde_json::json;
+
+    use crate::common::{block_with_text, todo_with_text, root};
+
+    fn diff_and_apply(old_block_map: BlockMap, new_block_map: &BlockMap) -> Result<(Vec<Step>, BlockMap), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let steps = generate_diff_steps(&"root".to_string(), &old_block_map, new_block_map, &mut new_ids)?;
+        let updated_state = actualise_steps(steps.clone(), old_block_map, &mut new_ids, CustomCopy::new())?;
+        return Ok((steps, updated_state.block_map))
+    }
+
+    fn text(block_map: &BlockMap, id: &str) -> Result<String, StepError> {
+        let block = block_map.get_standard_block(id)?;
+        let mut text = String::new();
+        for inline_block in block.get_inline_blocks(block_map)? {
+            text.push_str(&inline_block.text()?.clone().to_string());
+        }
+        return Ok(text)
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_steps() -> Result<(), StepError> {
+        let mut blocks = block_with_text("a", "paragraph", "Hello", vec![], "root");
+        blocks.push(root("root", vec!["a"]));
+        let block_map = BlockMap::from(blocks).unwrap();
+        let steps = generate_diff_steps(&"root".to_string(), &block_map, &block_map, &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert!(steps.is_empty());
+        return Ok(())
+    }
+
+    #[test]
+    fn text_change_is_a_single_replace_step() -> Result<(), StepError> {
+        let mut old_blocks = block_with_text("a", "paragraph", "Hello world", vec![], "root");
+        old_blocks.push(root("root", vec!["a"]));
+        let mut new_blocks = block_with_text("a", "paragraph", "Hello there world", vec![], "root");
+        new_blocks.push(root("root", vec!["a"]));
+        let new_block_map = BlockMap::from(new_blocks).unwrap();
+
+        let (steps, block_map) = diff_and_apply(BlockMap::from(old_blocks).unwrap(), &new_block_map)?;
+        assert_eq!(steps.len(), 1);
+        match &steps[0] {
+            Step::ReplaceStep(replace_step) => {
+                assert_eq!(replace_step.from.offset, 6);
+                assert_eq!(replace_step.to.offset, 6);
+            },
+            step => panic!("Expected a replace step, got: {:?}", step)
+        };
+        assert_eq!(text(&block_map, "a")?, "Hello there world".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn changed_marks_become_mark_steps() -> Result<(), StepError> {
+        let mut old_blocks = block_with_text("a", "paragraph", "Hello world", vec![], "root");
+        old_blocks.push(root("root", vec!["a"]));
+        let new_blocks = vec![
+            json!({ "_id": "i1", "kind": "inline", "_type": "text", "content": { "text": "Hello " }, "marks": [], "parent": "a" }).to_string(),
+            json!({ "_id": "i2", "kind": "inline", "_type": "text", "content": { "text": "world" }, "marks": ["bold"], "parent": "a" }).to_string(),
+            json!({ "_id": "a", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["i1", "i2"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            root("root", vec!["a"])
+        ];
+        let new_block_map = BlockMap::from(new_blocks).unwrap();
+
+        let (steps, block_map) = diff_and_apply(BlockMap::from(old_blocks).unwrap(), &new_block_map)?;
+        assert_eq!(steps.len(), 1);
+        assert!(matches!(steps[0], Step::AddMarkStep(_)));
+        let inline_blocks = block_map.get_standard_block("a")?.get_inline_blocks(&block_map)?;
+        assert_eq!(inline_blocks.len(), 2);
+        assert_eq!(inline_blocks[1].text()?.clone().to_string(), "world".to_string());
+        assert_eq!(inline_blocks[1].marks, vec![Mark::Bold]);
+        return Ok(())
+    }
+
+    #[test]
+    fn removed_marks_stay_removed_after_a_json_round_trip() -> Result<(), StepError> {
+        let old_blocks = vec![
+            json!({ "_id": "i1", "kind": "inline", "_type": "text", "content": { "text": "Hello " }, "marks": [], "parent": "a" }).to_string(),
+            json!({ "_id": "i2", "kind": "inline", "_type": "text", "content": { "text": "world" }, "marks": ["bold"], "parent": "a" }).to_string(),
+            json!({ "_id": "a", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["i1", "i2"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            root("root", vec!["a"])
+        ];
+        let old_block_map = BlockMap::from(old_blocks).unwrap();
+        let mut new_blocks = block_with_text("a", "paragraph", "Hello world", vec![], "root");
+        new_blocks.push(root("root", vec!["a"]));
+        let new_block_map = BlockMap::from(new_blocks).unwrap();
+
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let mut steps = vec![];
+        for step in generate_diff_steps(&"root".to_string(), &old_block_map, &new_block_map, &mut new_ids)? {
+            let (_type, data) = step.to_json()?;
+            steps.push(Step::from_json(&_type, &data)?);
+        }
+        assert!(matches!(&steps[..], [Step::RemoveMarkStep(_)]));
+        let block_map = actualise_steps(steps, old_block_map, &mut new_ids, CustomCopy::new())?.block_map;
+        for inline_block in block_map.get_standard_block("a")?.get_inline_blocks(&block_map)? {
+            assert!(inline_block.marks.is_empty());
+        }
+        assert_eq!(text(&block_map, "a")?, "Hello world".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn moved_and_deleted_blocks_become_move_and_delete_steps() -> Result<(), StepError> {
+        let mut old_blocks = block_with_text("a", "paragraph", "A", vec![], "root");
+        old_blocks.append(&mut block_with_text("b", "paragraph", "B", vec![], "root"));
+        old_blocks.append(&mut block_with_text("c", "paragraph", "C", vec![], "root"));
+        old_blocks.push(root("root", vec!["a", "b", "c"]));
+        // c becomes a child of a & b is deleted
+        let mut new_blocks = block_with_text("a", "paragraph", "A", vec!["c"], "root");
+        new_blocks.append(&mut block_with_text("c", "paragraph", "C", vec![], "a"));
+        new_blocks.push(root("root", vec!["a"]));
+        let new_block_map = BlockMap::from(new_blocks).unwrap();
+
+        let (steps, block_map) = diff_and_apply(BlockMap::from(old_blocks).unwrap(), &new_block_map)?;
+        assert_eq!(steps.len(), 2);
+        assert!(matches!(steps[0], Step::MoveBlocks(_)));
+        assert!(matches!(steps[1], Step::DeleteBlock(_)));
+        assert_eq!(block_map.get_root_block(&"root".to_string())?.children, vec!["a".to_string()]);
+        assert_eq!(block_map.get_standard_block("a")?.children, vec!["c".to_string()]);
+        assert_eq!(block_map.get_standard_block("c")?.parent, "a".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn changed_block_settings_become_toggle_and_turn_into_steps() -> Result<(), StepError> {
+        let mut old_blocks = todo_with_text("a", "Milk", false, vec![], "root");
+        old_blocks.append(&mut block_with_text("b", "paragraph", "Title", vec![], "root"));
+        old_blocks.push(root("root", vec!["a", "b"]));
+        let mut new_blocks = todo_with_text("a", "Milk", true, vec![], "root");
+        new_blocks.append(&mut block_with_text("b", "h1", "Title", vec![], "root"));
+        new_blocks.push(root("root", vec!["a", "b"]));
+        let new_block_map = BlockMap::from(new_blocks).unwrap();
+
+        let (steps, block_map) = diff_and_apply(BlockMap::from(old_blocks).unwrap(), &new_block_map)?;
+        assert_eq!(steps.len(), 2);
+        assert!(matches!(steps[0], Step::ToggleCompleted(_)));
+        assert!(matches!(steps[1], Step::TurnInto(_)));
+        assert_eq!(block_map.get_standard_block("b")?.content._type_as_string(), "h1".to_string());
+        assert_eq!(text(&block_map, "b")?, "Title".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn new_blocks_are_added_with_their_text() -> Result<(), StepError> {
+        let mut old_blocks = block_with_text("a", "paragraph", "A", vec![], "root");
+        old_blocks.push(root("root", vec!["a"]));
+        let mut new_blocks = block_with_text("a", "paragraph", "A", vec![], "root");
+        new_blocks.append(&mut block_with_text("b", "h2", "New", vec![], "root"));
+        new_blocks.push(root("root", vec!["a", "b"]));
+        let new_block_map = BlockMap::from(new_blocks).unwrap();
+
+        let steps = generate_diff_steps(&"root".to_string(), &BlockMap::from(old_blocks).unwrap(), &new_block_map,
+            &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert_eq!(steps.len(), 2);
+        match &steps[0] {
+            Step::AddBlock(add_block_step) => {
+                assert_eq!(add_block_step.new_std_block_id, "b".to_string());
+                assert_eq!(add_block_step.new_inline_block_id, "b_inline".to_string());
+                assert_eq!(add_block_step.child_offset, 1);
+            },
+            step => panic!("Expected an add block step, got: {:?}", step)
+        };
+        match &steps[1] {
+            Step::ReplaceStep(replace_step) => assert_eq!(replace_step.from.block_id, "b_inline".to_string()),
+            step => panic!("Expected a replace step, got: {:?}", step)
+        };
+        return Ok(())
+    }
+}
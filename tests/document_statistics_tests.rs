@@ -0,0 +1,65 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap, steps_generator::{selection::{Selection, SubSelection}, StepError},
+        utilities::document_statistics::{get_document_statistics, get_selection_statistics, DocumentStatistics}};
+
+    use crate::common::{self, block_with_text, todo_with_text, root};
+
+    /// root -> [title, paragraph -> [milk], dog]
+    fn block_map() -> BlockMap {
+        return common::block_map(vec![
+            block_with_text("title", "h1", "Title", vec![], "root"),
+            block_with_text("paragraph", "paragraph", "Hello world. How are you?", vec!["milk"], "root"),
+            todo_with_text("milk", "Buy milk", true, vec![], "paragraph"),
+            todo_with_text("dog", "Walk dog", false, vec![], "root"),
+            vec![root("root", vec!["title", "paragraph", "dog"])]
+        ])
+    }
+
+    #[test]
+    fn can_count_whole_document() -> Result<(), StepError> {
+        let statistics = get_document_statistics(&"root".to_string(), &block_map())?;
+        assert_eq!(statistics, DocumentStatistics {
+            characters: 46,
+            characters_excluding_spaces: 40,
+            words: 10,
+            sentences: 5,
+            blocks: 4,
+            headings: 1,
+            todos: 2,
+            completed_todos: 1,
+            reading_time_minutes: 1
+        });
+        return Ok(())
+    }
+
+    #[test]
+    fn can_count_selected_text_in_either_direction() -> Result<(), StepError> {
+        let anchor = SubSelection::from("paragraph_inline".to_string(), 6, None);
+        let head = SubSelection::from("milk_inline".to_string(), 3, None);
+        let expected = DocumentStatistics {
+            characters: 22,
+            characters_excluding_spaces: 19,
+            words: 5,
+            sentences: 3,
+            blocks: 2,
+            headings: 0,
+            todos: 1,
+            completed_todos: 1,
+            reading_time_minutes: 1
+        };
+        let block_map = block_map();
+        assert_eq!(get_selection_statistics(&Selection::from(anchor.clone(), head.clone()), &block_map)?, expected);
+        assert_eq!(get_selection_statistics(&Selection::from(head, anchor), &block_map)?, expected);
+        return Ok(())
+    }
+
+    #[test]
+    fn empty_document_has_no_reading_time() -> Result<(), StepError> {
+        let block_map = common::block_map(vec![vec![root("root", vec![])]]);
+        assert_eq!(get_document_statistics(&"root".to_string(), &block_map)?, DocumentStatistics::default());
+        return Ok(())
+    }
+}
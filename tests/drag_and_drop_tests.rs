@@ -1,3 +1,5 @@
+mod common;
+
 #[cfg(test)]
 mod tests {
     use std::{vec};
@@ -5,6 +7,8 @@ mod tests {
     use rust_mirror::{new_ids::NewIds, blocks::{RootBlock, BlockMap, standard_blocks::{StandardBlockType}}, steps_generator::{event::{Event, DropBlockEvent, Side}, selection::{SubSelection, Selection}, generate_steps}, steps_actualisor::actualise_steps, custom_copy::CustomCopy};
     use serde_json::json;
 
+    use crate::common::{self, block_with_text, root};
+
 
     #[test]
     fn can_drop_block_below() {
@@ -64,7 +68,7 @@ mod tests {
             inline_block1.to_string(), inline_block2.to_string(), paragraph_block1.to_string(), paragraph_block2.to_string(), root_block.to_string()
         ]).unwrap();
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id1.clone(),
+            drag_block_ids: vec![paragraph_block_id1.clone()],
             drop_block_id: paragraph_block_id2.clone(),
             side_dropped: Side::Bottom
         });
@@ -149,7 +153,7 @@ mod tests {
             paragraph_block3.to_string(), root_block.to_string()
         ]).unwrap();
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id2.clone(),
+            drag_block_ids: vec![paragraph_block_id2.clone()],
             drop_block_id: paragraph_block_id3.clone(),
             side_dropped: Side::Bottom
         });
@@ -236,7 +240,7 @@ mod tests {
             paragraph_block3.to_string(), root_block.to_string()
         ]).unwrap();
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id3.clone(),
+            drag_block_ids: vec![paragraph_block_id3.clone()],
             drop_block_id: paragraph_block_id2.clone(),
             side_dropped: Side::Top
         });
@@ -311,7 +315,7 @@ mod tests {
             inline_block1.to_string(), inline_block2.to_string(), paragraph_block1.to_string(), paragraph_block2.to_string(), root_block.to_string()
         ]).unwrap();
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id1.clone(),
+            drag_block_ids: vec![paragraph_block_id1.clone()],
             drop_block_id: paragraph_block_id2.clone(),
             side_dropped: Side::Left
         });
@@ -463,7 +467,7 @@ mod tests {
         ]).unwrap();
 
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id3.clone(),
+            drag_block_ids: vec![paragraph_block_id3.clone()],
             drop_block_id: layout_block_id1.clone(),
             side_dropped: Side::Left
         });
@@ -607,7 +611,7 @@ mod tests {
         ]).unwrap();
 
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id3.clone(),
+            drag_block_ids: vec![paragraph_block_id3.clone()],
             drop_block_id: layout_block_id1.clone(),
             side_dropped: Side::Right
         });
@@ -751,7 +755,7 @@ mod tests {
         ]).unwrap();
 
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id3.clone(),
+            drag_block_ids: vec![paragraph_block_id3.clone()],
             drop_block_id: layout_block_id2.clone(),
             side_dropped: Side::Left
         });
@@ -894,7 +898,7 @@ mod tests {
         ]).unwrap();
 
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id3.clone(),
+            drag_block_ids: vec![paragraph_block_id3.clone()],
             drop_block_id: layout_block_id2.clone(),
             side_dropped: Side::Right
         });
@@ -1037,7 +1041,7 @@ mod tests {
         ]).unwrap();
 
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id3.clone(),
+            drag_block_ids: vec![paragraph_block_id3.clone()],
             drop_block_id: paragraph_block_id2.clone(),
             side_dropped: Side::Left
         });
@@ -1180,7 +1184,7 @@ mod tests {
         ]).unwrap();
 
         let event = Event::DropBlock(DropBlockEvent {
-            drag_block_id: paragraph_block_id3.clone(),
+            drag_block_ids: vec![paragraph_block_id3.clone()],
             drop_block_id: paragraph_block_id2.clone(),
             side_dropped: Side::Right
         });
@@ -1212,4 +1216,169 @@ mod tests {
         };
         assert_eq!(new_vertical_layout_block.children, vec![paragraph_block_id3]);
     }
+
+    /// root -> [1 -> [1a], 2, 3, 4]
+    fn multi_block_map() -> BlockMap {
+        return common::block_map(vec![
+            block_with_text("1", "paragraph", "1", vec!["1a"], "root"),
+            block_with_text("1a", "paragraph", "1a", vec![], "1"),
+            block_with_text("2", "paragraph", "2", vec![], "root"),
+            block_with_text("3", "paragraph", "3", vec![], "root"),
+            block_with_text("4", "paragraph", "4", vec![], "root"),
+            vec![root("root", vec!["1", "2", "3", "4"])]
+        ])
+    }
+
+    #[test]
+    fn can_drop_multiple_blocks_below_keeping_document_order() {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = multi_block_map();
+        // given out of order, and 1a is a child of 1 so it moves with it
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["3".to_string(), "1a".to_string(), "1".to_string()],
+            drop_block_id: "4".to_string(),
+            side_dropped: Side::Bottom
+        });
+        let sub_selection = SubSelection::from("4_inline".to_string(), 0, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
+
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids).unwrap();
+        assert_eq!(steps.len(), 1);
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new()).unwrap();
+
+        let updated_root_block = updated_state.block_map.get_root_block(&"root".to_string()).unwrap();
+        assert_eq!(updated_root_block.children, vec!["2".to_string(), "4".to_string(), "1".to_string(), "3".to_string()]);
+        let block1 = updated_state.block_map.get_standard_block("1").unwrap();
+        assert_eq!(block1.children, vec!["1a".to_string()]);
+    }
+
+    #[test]
+    fn can_drop_multiple_blocks_into_new_horizontal_layout() {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = multi_block_map();
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["2".to_string(), "3".to_string()],
+            drop_block_id: "4".to_string(),
+            side_dropped: Side::Left
+        });
+        let sub_selection = SubSelection::from("4_inline".to_string(), 0, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
+
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids).unwrap();
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new()).unwrap();
+
+        let updated_root_block = updated_state.block_map.get_root_block(&"root".to_string()).unwrap();
+        assert_eq!(updated_root_block.children.len(), 2);
+        let horizontal_layout_block = updated_state.block_map.get_standard_block(&updated_root_block.children[1]).unwrap();
+        let left_column = updated_state.block_map.get_standard_block(&horizontal_layout_block.children[0]).unwrap();
+        assert_eq!(left_column.children, vec!["2".to_string(), "3".to_string()]);
+        let right_column = updated_state.block_map.get_standard_block(&horizontal_layout_block.children[1]).unwrap();
+        assert_eq!(right_column.children, vec!["4".to_string()]);
+        let block3 = updated_state.block_map.get_standard_block("3").unwrap();
+        assert_eq!(block3.parent, left_column._id);
+    }
+
+    #[test]
+    fn cannot_drop_block_into_its_own_descendant() {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = multi_block_map();
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["1".to_string()],
+            drop_block_id: "1a".to_string(),
+            side_dropped: Side::Bottom
+        });
+        let sub_selection = SubSelection::from("4_inline".to_string(), 0, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
+
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids).unwrap();
+        assert!(actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new()).is_err());
+    }
+
+    #[test]
+    fn drop_block_event_serialises_through_json() {
+        let event = DropBlockEvent {
+            drag_block_ids: vec!["1".to_string(), "3".to_string()],
+            drop_block_id: "4".to_string(),
+            side_dropped: Side::Right
+        };
+        let json = event.clone().to_json().unwrap();
+        assert_eq!(DropBlockEvent::from_json(json).unwrap(), event);
+
+        let legacy_json = json!({ "drag_block_id": "1", "drop_block_id": "4", "side_dropped": "top" });
+        assert_eq!(DropBlockEvent::from_json(legacy_json).unwrap().drag_block_ids, vec!["1".to_string()]);
+    }
+
+    #[test]
+    fn can_drop_block_as_first_or_last_child() {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let sub_selection = SubSelection::from("4_inline".to_string(), 0, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
+
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["3".to_string()],
+            drop_block_id: "1".to_string(),
+            side_dropped: Side::FirstChild
+        });
+        let steps = generate_steps(&event, &multi_block_map(), selection.clone(), &CustomCopy::new(), &mut new_ids).unwrap();
+        let updated_state = actualise_steps(steps, multi_block_map(), &mut new_ids, CustomCopy::new()).unwrap();
+        let block1 = updated_state.block_map.get_standard_block("1").unwrap();
+        assert_eq!(block1.children, vec!["3".to_string(), "1a".to_string()]);
+        assert_eq!(updated_state.block_map.get_standard_block("3").unwrap().parent, "1".to_string());
+
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["3".to_string(), "4".to_string()],
+            drop_block_id: "1".to_string(),
+            side_dropped: Side::LastChild
+        });
+        let steps = generate_steps(&event, &multi_block_map(), selection, &CustomCopy::new(), &mut new_ids).unwrap();
+        let updated_state = actualise_steps(steps, multi_block_map(), &mut new_ids, CustomCopy::new()).unwrap();
+        let block1 = updated_state.block_map.get_standard_block("1").unwrap();
+        assert_eq!(block1.children, vec!["1a".to_string(), "3".to_string(), "4".to_string()]);
+        let updated_root_block = updated_state.block_map.get_root_block(&"root".to_string()).unwrap();
+        assert_eq!(updated_root_block.children, vec!["1".to_string(), "2".to_string()]);
+    }
+
+    #[test]
+    fn cannot_drop_block_as_child_of_block_that_cannot_have_children() {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        // horizontal layouts can only have columns as children
+        let horizontal_layout_block = json!({
+            "_id": "layout",
+            "kind": "standard",
+            "_type": "layout",
+            "content": {
+                "horizontal": true
+            },
+            "children": ["column"],
+            "marks": [],
+            "parent": "root"
+        }).to_string();
+        let column_block = json!({
+            "_id": "column",
+            "kind": "standard",
+            "_type": "layout",
+            "content": {
+                "horizontal": false
+            },
+            "children": ["2"],
+            "marks": [],
+            "parent": "layout"
+        }).to_string();
+        let mut blocks = block_with_text("1", "paragraph", "1", vec![], "root");
+        blocks.append(&mut block_with_text("2", "paragraph", "2", vec![], "column"));
+        blocks.push(horizontal_layout_block);
+        blocks.push(column_block);
+        blocks.push(RootBlock::json_from("root".to_string(), vec!["1".to_string(), "layout".to_string()]).to_string());
+        let block_map = BlockMap::from(blocks).unwrap();
+
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["1".to_string()],
+            drop_block_id: "layout".to_string(),
+            side_dropped: Side::LastChild
+        });
+        let sub_selection = SubSelection::from("1_inline".to_string(), 0, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection.clone());
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids).unwrap();
+        assert!(actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new()).is_err());
+    }
 }
@@ -0,0 +1,96 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap, mark::Mark,
+        steps_generator::{event::{Event, FindReplaceEvent}, selection::{SubSelection, Selection}, find_replace::{find_matches, SearchOptions}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, new_ids::NewIds, custom_copy::CustomCopy};
+
+    use crate::common::{inline_block, paragraph, position, root};
+
+    /// p1 -> "Hello Wo" + bold "rld and world"
+    /// p2 -> "worldwide WORLD"
+    fn block_map() -> BlockMap {
+        return BlockMap::from(vec![
+            inline_block("i1", "Hello Wo", vec![], "p1"),
+            inline_block("i2", "rld and world", vec!["bold"], "p1"),
+            paragraph("p1", vec!["i1", "i2"], "root"),
+            inline_block("i3", "worldwide WORLD", vec![], "p2"),
+            paragraph("p2", vec!["i3"], "root"),
+            root("root", vec!["p1", "p2"])
+        ]).unwrap()
+    }
+
+    fn text(block_map: &BlockMap, inline_block_id: &str) -> Result<String, StepError> {
+        return Ok(block_map.get_inline_block(&inline_block_id.to_string())?.text()?.clone().to_string())
+    }
+
+    fn replace(event: FindReplaceEvent, caret: SubSelection) -> Result<BlockMap, StepError> {
+        let block_map = block_map();
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let selection = Selection::from(caret.clone(), caret);
+        let steps = generate_steps(&Event::FindReplace(event), &block_map, selection, &CustomCopy::new(), &mut new_ids)?;
+        return Ok(actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?.block_map)
+    }
+
+    #[test]
+    fn finds_matches_spanning_inline_blocks() -> Result<(), StepError> {
+        let matches = find_matches("world", "root", &SearchOptions::default(), &block_map())?;
+        assert_eq!(matches.len(), 4);
+        assert_eq!(matches[0], Selection::from(position("i1", 6), position("i2", 3)));
+        assert_eq!(matches[1], Selection::from(position("i2", 8), position("i2", 13)));
+        assert_eq!(matches[2], Selection::from(position("i3", 0), position("i3", 5)));
+        assert_eq!(matches[3], Selection::from(position("i3", 10), position("i3", 15)));
+        return Ok(())
+    }
+
+    #[test]
+    fn can_search_case_sensitive_and_whole_word() -> Result<(), StepError> {
+        let case_sensitive = SearchOptions { case_sensitive: true, whole_word: false, regex: false };
+        let matches = find_matches("world", "root", &case_sensitive, &block_map())?;
+        assert_eq!(matches, vec![
+            Selection::from(position("i2", 8), position("i2", 13)),
+            Selection::from(position("i3", 0), position("i3", 5))
+        ]);
+
+        let whole_word = SearchOptions { case_sensitive: false, whole_word: true, regex: false };
+        let matches = find_matches("world", "root", &whole_word, &block_map())?;
+        assert_eq!(matches.len(), 3);
+        assert!(!matches.contains(&Selection::from(position("i3", 0), position("i3", 5))));
+
+        assert!(find_matches("", "root", &SearchOptions::default(), &block_map())?.is_empty());
+        return Ok(())
+    }
+
+    #[test]
+    fn replace_all_keeps_marks_of_first_replaced_char() -> Result<(), StepError> {
+        let event = FindReplaceEvent {
+            query: "world".to_string(),
+            replace_with: "earth".to_string(),
+            options: SearchOptions { case_sensitive: false, whole_word: true, regex: false },
+            replace_all: true
+        };
+        let block_map = replace(event, position("i1", 0))?;
+
+        assert_eq!(text(&block_map, "i1")?, "Hello earth".to_string());
+        assert_eq!(block_map.get_inline_block(&"i1".to_string())?.marks, vec![]);
+        assert_eq!(text(&block_map, "i2")?, " and earth".to_string());
+        assert_eq!(block_map.get_inline_block(&"i2".to_string())?.marks, vec![Mark::Bold]);
+        assert_eq!(text(&block_map, "i3")?, "worldwide earth".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn replace_replaces_next_match_after_caret() -> Result<(), StepError> {
+        let event = FindReplaceEvent {
+            query: "world".to_string(),
+            replace_with: "planet".to_string(),
+            options: SearchOptions::default(),
+            replace_all: false
+        };
+        let block_map = replace(event, position("i3", 0))?;
+        assert_eq!(text(&block_map, "i3")?, "planetwide WORLD".to_string());
+        assert_eq!(text(&block_map, "i2")?, "rld and world".to_string());
+        return Ok(())
+    }
+}
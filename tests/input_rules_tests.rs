@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{BlockMap, standard_blocks::StandardBlockType, RootBlock},
+        steps_generator::{event::{Event, KeyPress, Key}, selection::{SubSelection, Selection}, generate_steps, StepError,
+            generate_replace_selected_steps::generate_replace_selected_steps},
+        steps_actualisor::actualise_steps, step::Step, new_ids::NewIds, custom_copy::CustomCopy, mark::Mark};
+
+    use serde_json::json;
+
+    fn paragraph_block_map(text: &str) -> BlockMap {
+        let inline_block = json!({
+            "_id": "inline1",
+            "kind": "inline",
+            "_type": "text",
+            "content": {
+                "text": text
+            },
+            "marks": [],
+            "parent": "paragraph"
+        }).to_string();
+        let paragraph_block = json!({
+            "_id": "paragraph",
+            "kind": "standard",
+            "_type": "paragraph",
+            "content": {
+                "inline_blocks": ["inline1"]
+            },
+            "children": [],
+            "marks": [],
+            "parent": "root"
+        }).to_string();
+        let root_block = RootBlock::json_from("root".to_string(), vec!["paragraph".to_string()]).to_string();
+        return BlockMap::from(vec![inline_block, paragraph_block, root_block]).unwrap()
+    }
+
+    fn type_key(block_map: BlockMap, key: char, offset: usize, new_ids: &mut NewIds) -> Result<(Vec<Step>, BlockMap, Option<Selection>), StepError> {
+        let event = Event::KeyPress(KeyPress::new(Key::Standard(key), None));
+        let sub_selection = SubSelection::from("inline1".to_string(), offset, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection);
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), new_ids)?;
+        let updated_state = actualise_steps(steps.clone(), block_map, new_ids, CustomCopy::new())?;
+        return Ok((steps, updated_state.block_map, updated_state.selection))
+    }
+
+    #[test]
+    fn hash_space_turns_paragraph_into_heading() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let (steps, block_map, selection) = type_key(paragraph_block_map("##"), ' ', 2, &mut new_ids)?;
+
+        assert_eq!(steps.len(), 2);
+        let block = block_map.get_standard_block("paragraph")?;
+        match block.content {
+            StandardBlockType::H2(_) => {},
+            _ => panic!("Expected H2")
+        };
+        let inline_block = block_map.get_inline_block(&"inline1".to_string())?;
+        assert_eq!(inline_block.text()?.clone().to_string(), "".to_string());
+        let expected_selection = SubSelection::from("inline1".to_string(), 0, None);
+        assert_eq!(selection, Some(Selection::from(expected_selection.clone(), expected_selection)));
+        return Ok(())
+    }
+
+    #[test]
+    fn list_prefixes_turn_paragraph_into_lists() -> Result<(), StepError> {
+        let prefixes = vec![("-", "dotpoint list"), ("*", "dotpoint list"), ("1.", "numbered list"), ("[]", "to-do list"), (">", "arrow list")];
+        for (prefix, expected_type) in prefixes {
+            let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+            let (_, block_map, _) = type_key(paragraph_block_map(prefix), ' ', prefix.len(), &mut new_ids)?;
+            let block = block_map.get_standard_block("paragraph")?;
+            assert_eq!(block.content._type_as_string()?, expected_type.to_string());
+        }
+        return Ok(())
+    }
+
+    #[test]
+    fn space_after_other_text_does_not_trigger_rule() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let (steps, block_map, _) = type_key(paragraph_block_map("a#"), ' ', 2, &mut new_ids)?;
+
+        assert_eq!(steps.len(), 1);
+        let inline_block = block_map.get_inline_block(&"inline1".to_string())?;
+        assert_eq!(inline_block.text()?.clone().to_string(), "a# ".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn closing_double_asterisk_adds_bold_mark() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let (_, block_map, selection) = type_key(paragraph_block_map("a **bold*"), '*', 9, &mut new_ids)?;
+
+        let paragraph_block = block_map.get_standard_block("paragraph")?;
+        let inline_blocks = &paragraph_block.content_block()?.inline_blocks;
+        assert_eq!(inline_blocks.len(), 2);
+        let before_block = block_map.get_inline_block(&inline_blocks[0])?;
+        assert_eq!(before_block.text()?.clone().to_string(), "a ".to_string());
+        let bold_block = block_map.get_inline_block(&inline_blocks[1])?;
+        assert_eq!(bold_block.text()?.clone().to_string(), "bold".to_string());
+        assert_eq!(bold_block.marks, vec![Mark::Bold]);
+
+        let expected_selection = SubSelection::from(bold_block.id(), 4, None);
+        assert_eq!(selection, Some(Selection::from(expected_selection.clone(), expected_selection)));
+        return Ok(())
+    }
+
+    #[test]
+    fn closing_asterisk_and_tilde_add_italic_and_strikethrough_marks() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let (_, block_map, _) = type_key(paragraph_block_map("*it"), '*', 3, &mut new_ids)?;
+        let paragraph_block = block_map.get_standard_block("paragraph")?;
+        let italic_block = block_map.get_inline_block(&paragraph_block.content_block()?.inline_blocks[0])?;
+        assert_eq!(italic_block.text()?.clone().to_string(), "it".to_string());
+        assert_eq!(italic_block.marks, vec![Mark::Italic]);
+
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let (_, block_map, _) = type_key(paragraph_block_map("~~gone~"), '~', 7, &mut new_ids)?;
+        let paragraph_block = block_map.get_standard_block("paragraph")?;
+        let strikethrough_block = block_map.get_inline_block(&paragraph_block.content_block()?.inline_blocks[0])?;
+        assert_eq!(strikethrough_block.text()?.clone().to_string(), "gone".to_string());
+        assert_eq!(strikethrough_block.marks, vec![Mark::Strikethrough]);
+        return Ok(())
+    }
+
+    #[test]
+    fn delimiters_around_whitespace_are_inserted_as_text() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let (steps, block_map, _) = type_key(paragraph_block_map("* a "), '*', 4, &mut new_ids)?;
+
+        assert_eq!(steps.len(), 1);
+        let inline_block = block_map.get_inline_block(&"inline1".to_string())?;
+        assert_eq!(inline_block.text()?.clone().to_string(), "* a *".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn input_rules_apply_to_any_single_character_replacement() -> Result<(), StepError> {
+        let block_map = paragraph_block_map("#");
+        let caret = SubSelection::from("inline1".to_string(), 1, None);
+        let steps = generate_replace_selected_steps(&block_map, caret.clone(), caret.clone(), " ".to_string(), &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert!(matches!(&steps[..], [Step::TurnInto(_), Step::ReplaceStep(_)]));
+
+        // longer replacements are inserted as they are
+        let steps = generate_replace_selected_steps(&block_map, caret.clone(), caret, " a".to_string(), &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert!(matches!(&steps[..], [Step::ReplaceStep(_)]));
+        return Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{BlockMap, standard_blocks::{StandardBlockType, layout_block::LayoutBlock}},
+        steps_generator::{event::{Event, DropBlockEvent, Side, ResizeLayoutColumnsEvent}, StepError}};
+
+    use crate::common::{self, block_with_text, layout, root, apply_event};
+
+    /// root -> [layout -> [column1 -> [1], column2 -> [2]], 3]
+    fn layout_block_map() -> BlockMap {
+        return common::block_map(vec![
+            layout("layout", true, vec![0.7, 0.3], vec!["column1", "column2"], "root"),
+            layout("column1", false, vec![], vec!["1"], "layout"),
+            layout("column2", false, vec![], vec!["2"], "layout"),
+            block_with_text("1", "paragraph", "1", vec![], "column1"),
+            block_with_text("2", "paragraph", "2", vec![], "column2"),
+            block_with_text("3", "paragraph", "3", vec![], "root"),
+            vec![root("root", vec!["layout", "3"])]
+        ])
+    }
+
+    fn get_layout(block_map: &BlockMap, id: &str) -> Result<LayoutBlock, StepError> {
+        return match block_map.get_standard_block(id)?.content {
+            StandardBlockType::Layout(layout_block) => Ok(layout_block),
+            _ => Err(StepError("Expected layout block".to_string()))
+        }
+    }
+
+    fn assert_widths(widths: &Vec<f64>, expected: Vec<f64>) {
+        assert_eq!(widths.len(), expected.len());
+        for (width, expected) in widths.iter().zip(expected) {
+            assert!((width - expected).abs() < 0.0001, "Expected {:?} to equal {:?}", widths, expected);
+        }
+    }
+
+    #[test]
+    fn adding_a_column_gives_it_an_equal_share_and_normalises() -> Result<(), StepError> {
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["3".to_string()],
+            drop_block_id: "layout".to_string(),
+            side_dropped: Side::Right
+        });
+        let block_map = apply_event(&event, layout_block_map(), "3")?.block_map;
+
+        let layout = get_layout(&block_map, "layout")?;
+        // new column gets 1/3, the rest keep their relative sizes
+        assert_widths(&layout.widths, vec![0.7 / (4.0 / 3.0), 0.3 / (4.0 / 3.0), (1.0 / 3.0) / (4.0 / 3.0)]);
+        let total: f64 = layout.widths.iter().sum();
+        assert!((total - 1.0).abs() < 0.0001);
+        return Ok(())
+    }
+
+    #[test]
+    fn removing_a_column_normalises_widths() -> Result<(), StepError> {
+        let block_map = apply_event(&Event::DeleteBlock("column2".to_string()), layout_block_map(), "3")?.block_map;
+        let layout = get_layout(&block_map, "layout")?;
+        assert_widths(&layout.widths, vec![1.0]);
+        return Ok(())
+    }
+
+    #[test]
+    fn new_horizontal_layout_has_equal_widths() -> Result<(), StepError> {
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["1".to_string()],
+            drop_block_id: "3".to_string(),
+            side_dropped: Side::Left
+        });
+        let block_map = apply_event(&event, layout_block_map(), "3")?.block_map;
+        let root = block_map.get_root_block(&"root".to_string())?;
+        let new_layout = get_layout(&block_map, &root.children[1])?;
+        assert_widths(&new_layout.widths, vec![0.5, 0.5]);
+        return Ok(())
+    }
+
+    #[test]
+    fn can_resize_adjacent_columns() -> Result<(), StepError> {
+        let event = Event::ResizeLayoutColumns(ResizeLayoutColumnsEvent {
+            layout_block_id: "layout".to_string(),
+            column_index: 0,
+            ratio: 0.25
+        });
+        let block_map = apply_event(&event, layout_block_map(), "3")?.block_map;
+        assert_widths(&get_layout(&block_map, "layout")?.widths, vec![0.25, 0.75]);
+
+        let event = Event::ResizeLayoutColumns(ResizeLayoutColumnsEvent {
+            layout_block_id: "layout".to_string(),
+            column_index: 1,
+            ratio: 0.5
+        });
+        assert!(apply_event(&event, layout_block_map(), "3").is_err());
+        return Ok(())
+    }
+}
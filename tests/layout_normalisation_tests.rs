@@ -0,0 +1,101 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap, steps_generator::{event::{Event, DropBlockEvent, Side}, StepError}};
+
+    use crate::common::{self, block_with_text, layout, root, apply_event};
+
+    /// root -> [layout -> [column1 -> [1], column2 -> [2, 3]], 4]
+    fn two_column_block_map() -> BlockMap {
+        return common::block_map(vec![
+            layout("layout", true, vec![], vec!["column1", "column2"], "root"),
+            layout("column1", false, vec![], vec!["1"], "layout"),
+            layout("column2", false, vec![], vec!["2", "3"], "layout"),
+            block_with_text("1", "paragraph", "1", vec![], "column1"),
+            block_with_text("2", "paragraph", "2", vec![], "column2"),
+            block_with_text("3", "paragraph", "3", vec![], "column2"),
+            block_with_text("4", "paragraph", "4", vec![], "root"),
+            vec![root("root", vec!["layout", "4"])]
+        ])
+    }
+
+    /// root -> [layout -> [column1 -> [1], column2 -> [2], column3 -> [3]], 4]
+    fn three_column_block_map() -> BlockMap {
+        return common::block_map(vec![
+            layout("layout", true, vec![], vec!["column1", "column2", "column3"], "root"),
+            layout("column1", false, vec![], vec!["1"], "layout"),
+            layout("column2", false, vec![], vec!["2"], "layout"),
+            layout("column3", false, vec![], vec!["3"], "layout"),
+            block_with_text("1", "paragraph", "1", vec![], "column1"),
+            block_with_text("2", "paragraph", "2", vec![], "column2"),
+            block_with_text("3", "paragraph", "3", vec![], "column3"),
+            block_with_text("4", "paragraph", "4", vec![], "root"),
+            vec![root("root", vec!["layout", "4"])]
+        ])
+    }
+
+    fn ids(ids: Vec<&str>) -> Vec<String> {
+        return ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn dragging_last_block_out_of_column_collapses_two_column_layout() -> Result<(), StepError> {
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["1".to_string()],
+            drop_block_id: "4".to_string(),
+            side_dropped: Side::Bottom
+        });
+        let updated_state = apply_event(&event, two_column_block_map(), "4")?;
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children, ids(vec!["2", "3", "4", "1"]));
+        assert_eq!(updated_state.block_map.get_standard_block("2")?.parent, "root".to_string());
+        assert!(updated_state.blocks_to_remove.contains(&"column1".to_string()));
+        assert!(updated_state.blocks_to_remove.contains(&"column2".to_string()));
+        assert!(updated_state.blocks_to_remove.contains(&"layout".to_string()));
+        assert!(!updated_state.blocks_to_update.contains(&"layout".to_string()));
+        return Ok(())
+    }
+
+    #[test]
+    fn empty_column_is_removed_from_multi_column_layout() -> Result<(), StepError> {
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["2".to_string()],
+            drop_block_id: "4".to_string(),
+            side_dropped: Side::Bottom
+        });
+        let updated_state = apply_event(&event, three_column_block_map(), "4")?;
+
+        let layout = updated_state.block_map.get_standard_block("layout")?;
+        assert_eq!(layout.children, ids(vec!["column1", "column3"]));
+        assert_eq!(updated_state.blocks_to_remove, ids(vec!["column2"]));
+        return Ok(())
+    }
+
+    #[test]
+    fn deleting_a_column_collapses_layout_into_parent() -> Result<(), StepError> {
+        let updated_state = apply_event(&Event::DeleteBlock("column1".to_string()), two_column_block_map(), "4")?;
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children, ids(vec!["2", "3", "4"]));
+        assert!(updated_state.blocks_to_remove.contains(&"layout".to_string()));
+        assert!(updated_state.blocks_to_remove.contains(&"column2".to_string()));
+        return Ok(())
+    }
+
+    #[test]
+    fn layouts_with_several_columns_are_left_alone() -> Result<(), StepError> {
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["2".to_string()],
+            drop_block_id: "4".to_string(),
+            side_dropped: Side::Bottom
+        });
+        let updated_state = apply_event(&event, two_column_block_map(), "4")?;
+
+        let layout = updated_state.block_map.get_standard_block("layout")?;
+        assert_eq!(layout.children, ids(vec!["column1", "column2"]));
+        assert!(updated_state.blocks_to_remove.is_empty());
+        return Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{RootBlock, BlockMap}, steps_generator::StepError, utilities::list_numbering::get_list_numbering};
+    use serde_json::{json, Value};
+
+    fn list_block(id: &str, _type: &str, start: Option<usize>, children: Vec<&str>, parent: &str) -> Value {
+        json!({
+            "_id": id,
+            "kind": "standard",
+            "_type": _type,
+            "content": {
+                "inline_blocks": [],
+                "completed": false,
+                "start": start
+            },
+            "children": children,
+            "marks": [],
+            "parent": parent
+        })
+    }
+
+    #[test]
+    fn can_number_numbered_lists_with_nesting_interruptions_and_restarts() -> Result<(), StepError> {
+        let root_block_id = "root".to_string();
+        let blocks = vec![
+            list_block("1", "numbered list", None, vec![], &root_block_id),
+            list_block("2", "numbered list", None, vec!["2a", "2b"], &root_block_id),
+            list_block("2a", "numbered list", None, vec!["2ai"], "2"),
+            list_block("2ai", "numbered list", None, vec![], "2a"),
+            list_block("2b", "numbered list", None, vec![], "2"),
+            list_block("dot", "dotpoint list", None, vec![], &root_block_id),
+            list_block("3", "numbered list", None, vec![], &root_block_id),
+            list_block("4", "numbered list", Some(5), vec![], &root_block_id),
+            list_block("5", "numbered list", None, vec![], &root_block_id),
+        ];
+        let root_block = RootBlock::json_from(root_block_id.clone(), vec![
+            "1".to_string(), "2".to_string(), "dot".to_string(), "3".to_string(), "4".to_string(), "5".to_string()
+        ]);
+        let mut blocks: Vec<String> = blocks.iter().map(|block| block.to_string()).collect();
+        blocks.push(root_block.to_string());
+        let block_map = BlockMap::from(blocks)?;
+
+        let numbering = get_list_numbering(&root_block_id, &block_map)?;
+
+        assert_eq!(numbering.len(), 7);
+        assert_eq!(numbering.get("1").unwrap().label(), "1.");
+        assert_eq!(numbering.get("2").unwrap().label(), "2.");
+        assert_eq!(numbering.get("2a").unwrap().label(), "a.");
+        assert_eq!(numbering.get("2b").unwrap().label(), "b.");
+        assert_eq!(numbering.get("2ai").unwrap().label(), "i.");
+        assert_eq!(numbering.get("3").unwrap().label(), "1.");
+        assert_eq!(numbering.get("4").unwrap().label(), "5.");
+        assert_eq!(numbering.get("5").unwrap().label(), "6.");
+        assert!(numbering.get("dot").is_none());
+
+        return Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap,
+        steps_generator::{event::{Event, KeyPress, Key, KeyPressMetadata}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::{actualise_steps, UpdatedState}, step::Step, new_ids::NewIds, custom_copy::CustomCopy};
+
+    use crate::common::{block_map, block_with_text, root};
+
+    /// root -> [a -> [a1, a2], b]
+    fn nested_block_map() -> BlockMap {
+        return block_map(vec![
+            block_with_text("a", "paragraph", "A", vec!["a1", "a2"], "root"),
+            block_with_text("a1", "paragraph", "A1", vec![], "a"),
+            block_with_text("a2", "paragraph", "A2", vec![], "a"),
+            block_with_text("b", "paragraph", "B", vec![], "root"),
+            vec![root("root", vec!["a", "b"])]
+        ])
+    }
+
+    fn move_block(block_map: BlockMap, from: SubSelection, to: SubSelection, key: Key) -> Result<(Vec<Step>, UpdatedState), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let metadata = KeyPressMetadata { shift_down: true, meta_down: false, ctrl_down: false, alt_down: true, smart_typography: false, suggesting_as: None, last_conversion: None };
+        let event = Event::KeyPress(KeyPress::new(key, Some(metadata)));
+        let selection = Selection::from(from, to);
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids)?;
+        let updated_state = actualise_steps(steps.clone(), block_map, &mut new_ids, CustomCopy::new())?;
+        return Ok((steps, updated_state))
+    }
+
+    #[test]
+    fn can_move_block_up_with_its_children() -> Result<(), StepError> {
+        let caret = SubSelection::from("b_inline".to_string(), 1, None);
+        let (_, updated_state) = move_block(nested_block_map(), caret.clone(), caret.clone(), Key::ArrowUp)?;
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children, vec!["b".to_string(), "a".to_string()]);
+        let a = updated_state.block_map.get_standard_block("a")?;
+        assert_eq!(a.children, vec!["a1".to_string(), "a2".to_string()]);
+        assert_eq!(updated_state.selection, Some(Selection::from(caret.clone(), caret)));
+        return Ok(())
+    }
+
+    #[test]
+    fn moving_last_child_down_escapes_into_parent_level() -> Result<(), StepError> {
+        let caret = SubSelection::from("a2_inline".to_string(), 0, None);
+        let (_, updated_state) = move_block(nested_block_map(), caret.clone(), caret, Key::ArrowDown)?;
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children, vec!["a".to_string(), "a2".to_string(), "b".to_string()]);
+        let a2 = updated_state.block_map.get_standard_block("a2")?;
+        assert_eq!(a2.parent, "root".to_string());
+        let a = updated_state.block_map.get_standard_block("a")?;
+        assert_eq!(a.children, vec!["a1".to_string()]);
+        return Ok(())
+    }
+
+    #[test]
+    fn can_move_selected_range_of_siblings_down() -> Result<(), StepError> {
+        let block_map = block_map(vec![
+            block_with_text("a", "paragraph", "A", vec![], "root"),
+            block_with_text("b", "paragraph", "B", vec![], "root"),
+            block_with_text("c", "paragraph", "C", vec![], "root"),
+            vec![root("root", vec!["a", "b", "c"])]
+        ]);
+
+        let from = SubSelection::from("a_inline".to_string(), 0, None);
+        let to = SubSelection::from("b_inline".to_string(), 1, None);
+        let (steps, updated_state) = move_block(block_map, from, to, Key::ArrowDown)?;
+        assert_eq!(steps.len(), 1);
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children, vec!["c".to_string(), "a".to_string(), "b".to_string()]);
+        return Ok(())
+    }
+
+    #[test]
+    fn moving_first_block_of_root_up_does_nothing() -> Result<(), StepError> {
+        let caret = SubSelection::from("a_inline".to_string(), 0, None);
+        let (steps, _) = move_block(nested_block_map(), caret.clone(), caret, Key::ArrowUp)?;
+        assert_eq!(steps.len(), 0);
+        return Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rust_mirror::{blocks::{BlockMap, RootBlock, Block},
+        steps_generator::{event::{Event, DropBlockEvent, Side}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, step::{Step, MoveBlocksToPageStep}, new_ids::NewIds, custom_copy::CustomCopy,
+        backend_interface::actualise_mirror_step_across_documents};
+
+    use serde_json::json;
+
+    use crate::common::block_with_text;
+
+    fn page_block(id: &str, page_id: &str, parent: &str) -> String {
+        return json!({
+            "_id": id,
+            "kind": "standard",
+            "_type": "inline page",
+            "content": {
+                "page_id": page_id
+            },
+            "children": [],
+            "marks": [],
+            "parent": parent
+        }).to_string()
+    }
+
+    /// root -> [1 -> [1a], page (-> other_root)]
+    fn source_blocks() -> Vec<String> {
+        let mut blocks = block_with_text("1", "paragraph", "1", vec!["1a"], "root");
+        blocks.append(&mut block_with_text("1a", "paragraph", "1a", vec![], "1"));
+        blocks.push(page_block("page", "other_root", "root"));
+        blocks.push(RootBlock::json_from("root".to_string(), vec!["1".to_string(), "page".to_string()]).to_string());
+        return blocks
+    }
+
+    /// other_root -> [2]
+    fn target_blocks() -> Vec<String> {
+        let mut blocks = block_with_text("2", "paragraph", "2", vec![], "other_root");
+        blocks.push(RootBlock::json_from("other_root".to_string(), vec!["2".to_string()]).to_string());
+        return blocks
+    }
+
+    fn to_hash_map(blocks: Vec<String>) -> HashMap<String, String> {
+        return match BlockMap::from(blocks).unwrap() {
+            BlockMap::Rust(block_map) => block_map,
+            BlockMap::Js(_) => unreachable!()
+        }
+    }
+
+    fn drop_on_page(block_map: &BlockMap, side_dropped: Side) -> Result<Vec<Step>, StepError> {
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["1".to_string()],
+            drop_block_id: "page".to_string(),
+            side_dropped
+        });
+        let sub_selection = SubSelection::from("1_inline".to_string(), 0, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection);
+        return generate_steps(&event, block_map, selection, &CustomCopy::new(), &mut NewIds::hardcoded_new_ids_for_tests())
+    }
+
+    #[test]
+    fn dropping_block_into_page_block_generates_move_to_page_step() -> Result<(), StepError> {
+        let block_map = BlockMap::from(source_blocks()).unwrap();
+        let steps = drop_on_page(&block_map, Side::FirstChild)?;
+        match &steps[0] {
+            Step::MoveBlocksToPage(step) => assert_eq!(step, &MoveBlocksToPageStep {
+                block_ids: vec!["1".to_string()],
+                page_id: "other_root".to_string(),
+                child_offset: Some(0)
+            }),
+            step => panic!("Expected move blocks to page step, got: {:?}", step)
+        };
+
+        // dropping above or below a page block is still a normal drop
+        let steps = drop_on_page(&block_map, Side::Bottom)?;
+        assert!(matches!(steps[0], Step::DropBlock(_)));
+        return Ok(())
+    }
+
+    #[test]
+    fn cannot_drop_an_ancestor_of_a_page_block_into_its_page() -> Result<(), StepError> {
+        // root -> [1 -> [page (-> other_root)]]
+        let mut blocks = block_with_text("1", "paragraph", "1", vec!["page"], "root");
+        blocks.push(page_block("page", "other_root", "1"));
+        blocks.push(RootBlock::json_from("root".to_string(), vec!["1".to_string()]).to_string());
+        let block_map = BlockMap::from(blocks).unwrap();
+        assert!(drop_on_page(&block_map, Side::FirstChild).is_err());
+        assert!(drop_on_page(&block_map, Side::LastChild).is_err());
+        return Ok(())
+    }
+
+    #[test]
+    fn can_move_subtree_into_page_in_same_block_map() -> Result<(), StepError> {
+        let mut blocks = source_blocks();
+        blocks.append(&mut target_blocks());
+        let block_map = BlockMap::from(blocks).unwrap();
+        let steps = drop_on_page(&block_map, Side::LastChild)?;
+        let updated_state = actualise_steps(steps, block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children, vec!["page".to_string()]);
+        let other_root = updated_state.block_map.get_root_block(&"other_root".to_string())?;
+        assert_eq!(other_root.children, vec!["2".to_string(), "1".to_string()]);
+        assert_eq!(updated_state.block_map.get_standard_block("1")?.parent, "other_root".to_string());
+        assert_eq!(updated_state.block_map.get_standard_block("1a")?.parent, "1".to_string());
+        assert!(updated_state.blocks_to_remove.is_empty());
+        return Ok(())
+    }
+
+    #[test]
+    fn page_outside_block_map_only_updates_source_document() -> Result<(), StepError> {
+        let block_map = BlockMap::from(source_blocks()).unwrap();
+        let steps = drop_on_page(&block_map, Side::LastChild)?;
+        let updated_state = actualise_steps(steps, block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children, vec!["page".to_string()]);
+        let mut blocks_to_remove = updated_state.blocks_to_remove.clone();
+        blocks_to_remove.sort();
+        assert_eq!(blocks_to_remove, vec!["1".to_string(), "1_inline".to_string(), "1a".to_string(), "1a_inline".to_string()]);
+        assert!(!updated_state.block_map.contains("1a"));
+        return Ok(())
+    }
+
+    #[test]
+    fn backend_can_apply_step_across_two_documents() -> Result<(), StepError> {
+        let step = MoveBlocksToPageStep {
+            block_ids: vec!["1".to_string(), "1a".to_string()],
+            page_id: "other_root".to_string(),
+            child_offset: Some(0)
+        };
+        let (source_state, target_state) = actualise_mirror_step_across_documents(
+            ("MoveBlocksToPage".to_string(), step.to_json()?.to_string()),
+            to_hash_map(source_blocks()),
+            to_hash_map(target_blocks())
+        )?;
+        assert!(source_state.blocks_to_remove.contains(&"1".to_string()) && source_state.blocks_to_update.contains(&"root".to_string()));
+        assert!(target_state.blocks_to_update.contains(&"1".to_string()) && target_state.blocks_to_update.contains(&"other_root".to_string()));
+        let source = source_state.block_map;
+        let target = target_state.block_map;
+
+        assert_eq!(source.get_root_block(&"root".to_string())?.children, vec!["page".to_string()]);
+        assert!(!source.contains("1") && !source.contains("1a_inline"));
+
+        assert_eq!(target.get_root_block(&"other_root".to_string())?.children, vec!["1".to_string(), "2".to_string()]);
+        let moved_block = target.get_standard_block("1")?;
+        assert_eq!(moved_block.parent, "other_root".to_string());
+        assert_eq!(moved_block.children, vec!["1a".to_string()]);
+        match target.get_block("1a_inline")? {
+            Block::InlineBlock(inline_block) => assert_eq!(inline_block.parent, "1a".to_string()),
+            _ => panic!("Expected inline block")
+        };
+        return Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{BlockMap, Block, nesting_schema::{NestingSchema, set_nesting_schema, with_nesting_schema},
+        standard_blocks::{StandardBlockType, content_block::ContentBlock}}, step::{Step, TurnInto, AddBlockStep},
+        steps_generator::{event::{Event, KeyPress, Key, DropBlockEvent, Side}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, new_ids::NewIds, custom_copy::CustomCopy};
+    use serde_json::json;
+
+    use crate::common::{self, block_with_text, root};
+
+    /// root -> [heading: h1, 1: paragraph, 2: paragraph]
+    fn block_map() -> BlockMap {
+        return common::block_map(vec![
+            block_with_text("heading", "h1", "heading", vec![], "root"),
+            block_with_text("1", "paragraph", "1", vec![], "root"),
+            block_with_text("2", "paragraph", "2", vec![], "root"),
+            vec![root("root", vec!["heading", "1", "2"])]
+        ])
+    }
+
+    fn caret(block_id: &str) -> Selection {
+        let caret = SubSelection::from(format!("{}_inline", block_id), 0, None);
+        return Selection::from(caret.clone(), caret)
+    }
+
+    #[test]
+    fn default_schema_keeps_pages_and_horizontal_layouts_childless() -> Result<(), StepError> {
+        set_nesting_schema(NestingSchema::default());
+        let block_map = BlockMap::from(vec![
+            json!({ "_id": "page", "kind": "standard", "_type": "inline page", "content": { "page_id": "p" },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            json!({ "_id": "layout", "kind": "standard", "_type": "layout", "content": { "horizontal": true },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            root("root", vec!["page", "layout"])
+        ]).unwrap();
+        assert!(!block_map.get_standard_block("page")?.content.can_have_children());
+        assert!(!block_map.get_standard_block("layout")?.content.can_have_children());
+        let root = block_map.get_block("root")?;
+        let page = block_map.get_standard_block("page")?;
+        assert!(with_nesting_schema(|schema| schema.allows_child(&root, &page.content)));
+        return Ok(())
+    }
+
+    #[test]
+    fn custom_schema_still_keeps_pages_childless() -> Result<(), StepError> {
+        set_nesting_schema(NestingSchema::from_json(&json!({ "childless_types": ["h1", "h2", "h3"] }))?);
+        let block_map = BlockMap::from(vec![
+            json!({ "_id": "page", "kind": "standard", "_type": "inline page", "content": { "page_id": "p" },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            root("root", vec!["page"])
+        ]).unwrap();
+        let page = block_map.get_block("page")?;
+        assert!(!block_map.get_standard_block("page")?.content.can_have_children());
+        assert!(!with_nesting_schema(|schema| schema.allows_child(&page, &StandardBlockType::Paragraph(ContentBlock::new(vec![])))));
+        set_nesting_schema(NestingSchema::default());
+        return Ok(())
+    }
+
+    #[test]
+    fn schema_is_parsed_from_json() -> Result<(), StepError> {
+        let schema = NestingSchema::from_json(&json!({ "childless_types": ["h1"], "allowed_children": { "root": ["paragraph"] } }))?;
+        assert_eq!(schema.childless_types, vec!["h1".to_string()]);
+        assert_eq!(schema.allowed_children.get("root"), Some(&vec!["paragraph".to_string()]));
+        assert_eq!(schema.layout_column_types, None);
+        assert!(NestingSchema::from_json(&json!({ "childless_types": "h1" })).is_err());
+        return Ok(())
+    }
+
+    #[test]
+    fn cannot_tab_under_a_childless_block() -> Result<(), StepError> {
+        set_nesting_schema(NestingSchema::from_json(&json!({ "childless_types": ["h1"] }))?);
+        let block_map = block_map();
+        let event = Event::KeyPress(KeyPress::new(Key::Tab, None));
+        let steps = generate_steps(&event, &block_map, caret("1"), &CustomCopy::new(), &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert_eq!(steps.len(), 0);
+
+        // a paragraph can still have children
+        let steps = generate_steps(&event, &block_map, caret("2"), &CustomCopy::new(), &mut NewIds::hardcoded_new_ids_for_tests())?;
+        let updated_state = actualise_steps(steps, block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+        assert_eq!(updated_state.block_map.get_standard_block("1")?.children, vec!["2".to_string()]);
+        set_nesting_schema(NestingSchema::default());
+        return Ok(())
+    }
+
+    #[test]
+    fn drops_that_break_the_schema_are_rejected() -> Result<(), StepError> {
+        set_nesting_schema(NestingSchema::from_json(&json!({ "layout_column_types": ["paragraph"] }))?);
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["heading".to_string()],
+            drop_block_id: "2".to_string(),
+            side_dropped: Side::Left
+        });
+        let steps = generate_steps(&event, &block_map(), caret("2"), &CustomCopy::new(), &mut new_ids)?;
+        assert!(actualise_steps(steps, block_map(), &mut new_ids, CustomCopy::new()).is_err());
+
+        let event = Event::DropBlock(DropBlockEvent {
+            drag_block_ids: vec!["1".to_string()],
+            drop_block_id: "2".to_string(),
+            side_dropped: Side::Left
+        });
+        let steps = generate_steps(&event, &block_map(), caret("2"), &CustomCopy::new(), &mut new_ids)?;
+        let updated_state = actualise_steps(steps, block_map(), &mut new_ids, CustomCopy::new())?;
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert!(matches!(updated_state.block_map.get_block(&root.children[1])?, Block::StandardBlock(layout) if layout.is_horizontal_layout()));
+        set_nesting_schema(NestingSchema::default());
+        return Ok(())
+    }
+
+    #[test]
+    fn cannot_turn_a_block_with_children_into_a_childless_type() -> Result<(), StepError> {
+        set_nesting_schema(NestingSchema::from_json(&json!({ "childless_types": ["h1"] }))?);
+        let block_map = common::block_map(vec![
+            block_with_text("1", "paragraph", "1", vec!["2"], "root"),
+            block_with_text("2", "paragraph", "2", vec![], "1"),
+            vec![root("root", vec!["1"])]
+        ]);
+        let turn_into = |block_id: &str| Step::TurnInto(TurnInto {
+            block_id: block_id.to_string(),
+            new_block_type: StandardBlockType::H1(ContentBlock::new(vec![]))
+        });
+        assert!(actualise_steps(vec![turn_into("1")], block_map.clone(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new()).is_err());
+
+        // a block without children can still become a heading
+        let updated_state = actualise_steps(vec![turn_into("2")], block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+        assert_eq!(updated_state.block_map.get_standard_block("2")?.content._type_as_string(), "h1".to_string());
+        set_nesting_schema(NestingSchema::default());
+        return Ok(())
+    }
+
+    #[test]
+    fn cannot_add_a_block_type_the_parent_does_not_allow() -> Result<(), StepError> {
+        set_nesting_schema(NestingSchema::from_json(&json!({ "allowed_children": { "root": ["paragraph"] } }))?);
+        let add_block = |block_type: StandardBlockType| Step::AddBlock(AddBlockStep {
+            block_id: "root".to_string(),
+            new_std_block_id: "new".to_string(),
+            new_inline_block_id: "new_inline".to_string(),
+            child_offset: 1,
+            block_type,
+            focus_block_below: false
+        });
+        let step = add_block(StandardBlockType::H1(ContentBlock::new(vec![])));
+        assert!(actualise_steps(vec![step], block_map(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new()).is_err());
+
+        let step = add_block(StandardBlockType::Paragraph(ContentBlock::new(vec![])));
+        let updated_state = actualise_steps(vec![step], block_map(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?;
+        assert_eq!(updated_state.block_map.get_root_block(&"root".to_string())?.children.len(), 4);
+        set_nesting_schema(NestingSchema::default());
+        return Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{BlockMap, standard_blocks::StandardBlockType},
+        steps_generator::{selection::SubSelection, StepError},
+        steps_actualisor::{actualise_steps, UpdatedState}, step::{Step, AddTableOfContentsStep, ReplaceStep, ReplaceSlice},
+        utilities::outline::{get_outline, get_headings, slugify},
+        new_ids::NewIds, custom_copy::CustomCopy};
+
+    use crate::common::{self, block_with_text, root};
+
+    /// root -> [intro (h1), body (paragraph) -> [details (h3)], setup (h2), intro_again (h1), plain (paragraph)]
+    fn block_map() -> BlockMap {
+        return common::block_map(vec![
+            block_with_text("intro", "h1", "Intro", vec![], "root"),
+            block_with_text("body", "paragraph", "Some text", vec!["details"], "root"),
+            block_with_text("details", "h3", "The Details!", vec![], "body"),
+            block_with_text("setup", "h2", "Set up", vec![], "root"),
+            block_with_text("intro_again", "h1", "Intro", vec![], "root"),
+            block_with_text("plain", "paragraph", "Plain", vec![], "root"),
+            vec![root("root", vec!["intro", "body", "setup", "intro_again", "plain"])]
+        ])
+    }
+
+    fn add_table_of_contents(block_map: BlockMap) -> Result<UpdatedState, StepError> {
+        let step = Step::AddTableOfContents(AddTableOfContentsStep {
+            block_id: "root".to_string(),
+            new_block_id: "toc".to_string(),
+            child_offset: 0
+        });
+        return actualise_steps(vec![step], block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())
+    }
+
+    fn replace_text(block_id: &str, from: usize, to: usize, text: &str, block_map: BlockMap) -> Result<UpdatedState, StepError> {
+        let inline_id = format!("{}_inline", block_id);
+        let step = Step::ReplaceStep(ReplaceStep {
+            block_id: block_id.to_string(),
+            from: SubSelection::from(inline_id.clone(), from, None),
+            to: SubSelection::from(inline_id, to, None),
+            slice: ReplaceSlice::String(text.to_string())
+        });
+        return actualise_steps(vec![step], block_map, &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())
+    }
+
+    fn table_of_contents_texts(block_map: &BlockMap) -> Result<Vec<String>, StepError> {
+        return match block_map.get_standard_block("toc")?.content {
+            StandardBlockType::TableOfContents(block) => Ok(block.entries.into_iter().map(|entry| entry.text).collect()),
+            block => Err(StepError(format!("Expected a table of contents block, got: {:#?}", block)))
+        }
+    }
+
+    #[test]
+    fn can_nest_headings_by_level() -> Result<(), StepError> {
+        let outline = get_outline(&"root".to_string(), &block_map())?;
+        assert_eq!(outline.len(), 2);
+        assert_eq!(outline[0].block_id, "intro".to_string());
+        // h3 directly under an h1 is nested in the h1, followed by the h2
+        let children: Vec<String> = outline[0].children.iter().map(|item| item.block_id.clone()).collect();
+        assert_eq!(children, vec!["details".to_string(), "setup".to_string()]);
+        assert_eq!(outline[0].children[0].level, 3);
+        assert_eq!(outline[1].block_id, "intro_again".to_string());
+        assert!(outline[1].children.is_empty());
+        return Ok(())
+    }
+
+    #[test]
+    fn anchors_are_slugs_and_unique() -> Result<(), StepError> {
+        let anchors: Vec<String> = get_headings(&"root".to_string(), &block_map())?.into_iter().map(|heading| heading.anchor).collect();
+        assert_eq!(anchors, vec!["intro".to_string(), "the-details".to_string(), "set-up".to_string(), "intro-1".to_string()]);
+        assert_eq!(slugify("  Hello -- World_2 "), "hello-world-2".to_string());
+        assert_eq!(slugify("!!"), "section".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn can_add_table_of_contents_block() -> Result<(), StepError> {
+        let updated_state = add_table_of_contents(block_map())?;
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children[0], "toc".to_string());
+        assert_eq!(table_of_contents_texts(&updated_state.block_map)?,
+            vec!["Intro".to_string(), "The Details!".to_string(), "Set up".to_string(), "Intro".to_string()]);
+        assert!(updated_state.blocks_to_update.contains(&"toc".to_string()));
+        return Ok(())
+    }
+
+    #[test]
+    fn editing_a_heading_regenerates_table_of_contents() -> Result<(), StepError> {
+        let block_map = add_table_of_contents(block_map())?.block_map;
+        let updated_state = replace_text("setup", 6, 6, " guide", block_map)?;
+        assert_eq!(table_of_contents_texts(&updated_state.block_map)?,
+            vec!["Intro".to_string(), "The Details!".to_string(), "Set up guide".to_string(), "Intro".to_string()]);
+        assert!(updated_state.blocks_to_update.contains(&"toc".to_string()));
+        return Ok(())
+    }
+
+    #[test]
+    fn editing_a_paragraph_does_not_update_table_of_contents() -> Result<(), StepError> {
+        let block_map = add_table_of_contents(block_map())?.block_map;
+        let updated_state = replace_text("plain", 5, 5, "!", block_map)?;
+        assert!(!updated_state.blocks_to_update.contains(&"toc".to_string()));
+        return Ok(())
+    }
+}
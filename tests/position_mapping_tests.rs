@@ -0,0 +1,95 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap,
+        steps_generator::{event::{Event, KeyPress, Key}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, step::{Step, ReplaceStep, ReplaceSlice, SplitStep},
+        utilities::position_mapping::{map_position, map_selection}, new_ids::NewIds, custom_copy::CustomCopy};
+
+    use crate::common::{two_paragraphs, apply_to_two_paragraphs, position};
+
+    /// Applies the step & maps the position through it
+    fn apply_and_map(step: Step, position: SubSelection) -> Result<(Option<SubSelection>, BlockMap), StepError> {
+        let (old_block_map, new_block_map) = apply_to_two_paragraphs(&step)?;
+        let mapped_position = map_position(&position, &step, &old_block_map, &new_block_map)?;
+        return Ok((mapped_position, new_block_map))
+    }
+
+    fn replace(from: usize, to: usize, text: &str) -> Step {
+        return Step::ReplaceStep(ReplaceStep {
+            block_id: "a".to_string(),
+            from: position("a_inline", from),
+            to: position("a_inline", to),
+            slice: ReplaceSlice::String(text.to_string())
+        })
+    }
+
+    #[test]
+    fn inserted_text_before_a_position_moves_it() -> Result<(), StepError> {
+        let (mapped_position, _) = apply_and_map(replace(6, 6, "big "), position("a_inline", 8))?;
+        assert_eq!(mapped_position, Some(position("a_inline", 12)));
+        let (mapped_position, _) = apply_and_map(replace(6, 6, "big "), position("a_inline", 2))?;
+        assert_eq!(mapped_position, Some(position("a_inline", 2)));
+        return Ok(())
+    }
+
+    #[test]
+    fn position_in_deleted_text_goes_to_end_of_the_new_text() -> Result<(), StepError> {
+        let (mapped_position, _) = apply_and_map(replace(4, 9, "!"), position("a_inline", 6))?;
+        assert_eq!(mapped_position, Some(position("a_inline", 5)));
+        return Ok(())
+    }
+
+    #[test]
+    fn position_after_a_split_moves_to_the_new_block() -> Result<(), StepError> {
+        let step = Step::SplitStep(SplitStep {
+            subselection: position("a_inline", 5),
+            new_std_block_id: "new_std".to_string(),
+            new_inline_block_id: "new_inline".to_string()
+        });
+        let (mapped_position, _) = apply_and_map(step, position("a_inline", 8))?;
+        assert_eq!(mapped_position, Some(position("new_inline", 3)));
+        return Ok(())
+    }
+
+    #[test]
+    fn position_in_a_merged_block_moves_to_the_block_it_joined() -> Result<(), StepError> {
+        // backspace at the start of "Second" joins it onto "Hello world"
+        let old_block_map = two_paragraphs();
+        let event = Event::KeyPress(KeyPress::new(Key::Backspace, None));
+        let steps = generate_steps(&event, &old_block_map, Selection::from(position("b_inline", 0), position("b_inline", 0)), &CustomCopy::new(),
+            &mut NewIds::hardcoded_new_ids_for_tests())?;
+        let new_block_map = actualise_steps(steps.clone(), old_block_map.clone(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?.block_map;
+
+        let mapped_position = map_position(&position("b_inline", 2), &steps[0], &old_block_map, &new_block_map)?.unwrap();
+        let raw_position = mapped_position.to_raw_selection(&new_block_map)?;
+        assert_eq!(raw_position, position("a", 13));
+        return Ok(())
+    }
+
+    #[test]
+    fn position_in_a_deleted_block_moves_to_the_previous_block() -> Result<(), StepError> {
+        let (mapped_position, _) = apply_and_map(Step::DeleteBlock("b".to_string()), position("b_inline", 3))?;
+        assert_eq!(mapped_position, Some(position("a_inline", 11)));
+        return Ok(())
+    }
+
+    #[test]
+    fn splitting_inline_blocks_keeps_the_text_position() -> Result<(), StepError> {
+        let old_block_map = two_paragraphs();
+        let event = Event::KeyPress(KeyPress::new(Key::Standard('b'), Some(rust_mirror::steps_generator::event::KeyPressMetadata {
+            shift_down: false, meta_down: true, ctrl_down: false, alt_down: false, smart_typography: false, suggesting_as: None, last_conversion: None
+        })));
+        let selection = Selection::from(position("a_inline", 6), position("a_inline", 11));
+        let steps = generate_steps(&event, &old_block_map, selection.clone(), &CustomCopy::new(), &mut NewIds::hardcoded_new_ids_for_tests())?;
+        let new_block_map = actualise_steps(steps.clone(), old_block_map.clone(), &mut NewIds::hardcoded_new_ids_for_tests(), CustomCopy::new())?.block_map;
+
+        let mapped_selection = map_selection(&selection, &steps[0], &old_block_map, &new_block_map)?.unwrap();
+        assert_eq!(mapped_selection.anchor.to_raw_selection(&new_block_map)?, position("a", 6));
+        assert_eq!(mapped_selection.head.to_raw_selection(&new_block_map)?, position("a", 11));
+        let bold_block = new_block_map.get_inline_block(&mapped_selection.head.block_id)?;
+        assert_eq!(bold_block.text()?.clone().to_string(), "world".to_string());
+        return Ok(())
+    }
+}
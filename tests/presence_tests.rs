@@ -0,0 +1,70 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{mark::Color,
+        steps_generator::{selection::{SubSelection, Selection}, StepError},
+        step::{Step, ReplaceStep, ReplaceSlice}, utilities::presence::{RemotePresence, update_presence, remove_presence, map_presences_through_step}};
+    use serde_json::json;
+
+    use crate::common::apply_to_two_paragraphs;
+
+    fn presence(user_id: &str, block_id: &str, from: usize, to: usize) -> RemotePresence {
+        return RemotePresence {
+            user_id: user_id.to_string(),
+            color: Color(255, 0, 0, 50),
+            selection: Selection::from(SubSelection::from(block_id.to_string(), from, None), SubSelection::from(block_id.to_string(), to, None))
+        }
+    }
+
+    fn apply_and_map(step: Step, presences: Vec<RemotePresence>) -> Result<Vec<RemotePresence>, StepError> {
+        let (old_block_map, new_block_map) = apply_to_two_paragraphs(&step)?;
+        return Ok(map_presences_through_step(presences, &step, &old_block_map, &new_block_map))
+    }
+
+    #[test]
+    fn presence_round_trips_through_json() -> Result<(), StepError> {
+        let presence = presence("ann", "a_inline", 2, 5);
+        let json = presence.clone().to_json()?;
+        assert_eq!(json.get("color").unwrap(), &json!("(255, 0, 0, 0.5)"));
+        assert_eq!(RemotePresence::from_json(&json)?, presence);
+        return Ok(())
+    }
+
+    #[test]
+    fn presences_move_with_text_edited_before_them() -> Result<(), StepError> {
+        let step = Step::ReplaceStep(ReplaceStep {
+            block_id: "a".to_string(),
+            from: SubSelection::from("a_inline".to_string(), 0, None),
+            to: SubSelection::from("a_inline".to_string(), 0, None),
+            slice: ReplaceSlice::String("Oh ".to_string())
+        });
+        let presences = apply_and_map(step, vec![presence("ann", "a_inline", 6, 11), presence("bob", "b_inline", 1, 1)])?;
+        assert_eq!(presences, vec![presence("ann", "a_inline", 9, 14), presence("bob", "b_inline", 1, 1)]);
+        return Ok(())
+    }
+
+    #[test]
+    fn presences_in_removed_blocks_are_dropped() -> Result<(), StepError> {
+        let presences = apply_and_map(Step::DeleteBlock("b".to_string()), vec![presence("ann", "a_inline", 0, 5), presence("bob", "b_inline", 1, 3)])?;
+        assert_eq!(presences, vec![presence("ann", "a_inline", 0, 5)]);
+        return Ok(())
+    }
+
+    #[test]
+    fn presences_that_cannot_be_mapped_are_dropped_without_dropping_the_others() -> Result<(), StepError> {
+        let presences = apply_and_map(Step::DeleteBlock("b".to_string()), vec![presence("ann", "missing_inline", 0, 0), presence("bob", "a_inline", 1, 3)])?;
+        assert_eq!(presences, vec![presence("bob", "a_inline", 1, 3)]);
+        return Ok(())
+    }
+
+    #[test]
+    fn updating_a_presence_replaces_the_users_previous_one() {
+        let mut presences = vec![presence("ann", "a_inline", 0, 0), presence("bob", "b_inline", 0, 0)];
+        update_presence(&mut presences, presence("ann", "a_inline", 4, 4));
+        update_presence(&mut presences, presence("cat", "b_inline", 2, 2));
+        assert_eq!(presences, vec![presence("ann", "a_inline", 4, 4), presence("bob", "b_inline", 0, 0), presence("cat", "b_inline", 2, 2)]);
+        remove_presence(&mut presences, "bob");
+        assert_eq!(presences.iter().map(|presence| presence.user_id.clone()).collect::<Vec<String>>(), vec!["ann".to_string(), "cat".to_string()]);
+    }
+}
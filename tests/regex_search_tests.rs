@@ -0,0 +1,104 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap, mark::Mark,
+        steps_generator::{event::{Event, FindReplaceEvent}, selection::Selection, find_replace::{find_matches, SearchOptions}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, new_ids::NewIds, custom_copy::CustomCopy};
+
+    use crate::common::{inline_block, paragraph, position, root};
+
+    /// p1 -> "Due 2023-" + bold "04-01, then 2024-05-02"
+    fn block_map() -> BlockMap {
+        return BlockMap::from(vec![
+            inline_block("i1", "Due 2023-", vec![], "p1"),
+            inline_block("i2", "04-01, then 2024-05-02", vec!["bold"], "p1"),
+            paragraph("p1", vec!["i1", "i2"], "root"),
+            root("root", vec!["p1"])
+        ]).unwrap()
+    }
+
+    fn regex_options() -> SearchOptions {
+        return SearchOptions { case_sensitive: false, whole_word: false, regex: true }
+    }
+
+    #[test]
+    fn regex_matches_map_back_to_inline_blocks() -> Result<(), StepError> {
+        let matches = find_matches("(\\d{4})-(\\d\\d)-(\\d\\d)", "root", &regex_options(), &block_map())?;
+        assert_eq!(matches, vec![
+            Selection::from(position("i1", 4), position("i2", 5)),
+            Selection::from(position("i2", 12), position("i2", 22))
+        ]);
+
+        // a match starting on an inline block boundary starts in the block of its first char
+        let matches = find_matches("04", "root", &regex_options(), &block_map())?;
+        assert_eq!(matches, vec![Selection::from(position("i2", 0), position("i2", 2))]);
+        return Ok(())
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error() {
+        assert!(find_matches("(\\d+", "root", &regex_options(), &block_map()).is_err());
+    }
+
+    #[test]
+    fn replace_all_substitutes_capture_groups() -> Result<(), StepError> {
+        let block_map = block_map();
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let event = Event::FindReplace(FindReplaceEvent {
+            query: "(\\d{4})-(\\d\\d)-(\\d\\d)".to_string(),
+            replace_with: "$3/$2/$1".to_string(),
+            options: regex_options(),
+            replace_all: true
+        });
+        let caret = position("i1", 0);
+        let steps = generate_steps(&event, &block_map, Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)?;
+        let block_map = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?.block_map;
+
+        let i1 = block_map.get_inline_block(&"i1".to_string())?;
+        assert_eq!(i1.text()?.clone().to_string(), "Due 01/04/2023".to_string());
+        assert_eq!(i1.marks, vec![]);
+        let i2 = block_map.get_inline_block(&"i2".to_string())?;
+        assert_eq!(i2.text()?.clone().to_string(), ", then 02/05/2024".to_string());
+        assert_eq!(i2.marks, vec![Mark::Bold]);
+        return Ok(())
+    }
+
+    fn replace_all_in_text(text: &str, query: &str, replace_with: &str) -> Result<String, StepError> {
+        let block_map = BlockMap::from(vec![
+            inline_block("i1", text, vec![], "p1"),
+            paragraph("p1", vec!["i1"], "root"),
+            root("root", vec!["p1"])
+        ]).unwrap();
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let event = Event::FindReplace(FindReplaceEvent {
+            query: query.to_string(),
+            replace_with: replace_with.to_string(),
+            options: SearchOptions { case_sensitive: true, whole_word: false, regex: true },
+            replace_all: true
+        });
+        let caret = position("i1", 0);
+        let steps = generate_steps(&event, &block_map, Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)?;
+        let block_map = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?.block_map;
+        let i1 = block_map.get_inline_block(&"i1".to_string())?;
+        return Ok(i1.text()?.clone().to_string())
+    }
+
+    #[test]
+    fn regex_supports_classes_alternation_and_lazy_repeats() -> Result<(), StepError> {
+        assert_eq!(replace_all_in_text("<a> cat concat <b>", "<.+?>|\\bcat\\b", "_")?, "_ _ concat _".to_string());
+        assert_eq!(replace_all_in_text("ab", "(a)(x)?b", "$1-${2}-$$")?, "a--$".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn nested_quantifiers_do_not_backtrack_exponentially() -> Result<(), StepError> {
+        let text = "a".repeat(5000);
+        assert_eq!(find_matches("(a+)+b", "root", &regex_options(), &BlockMap::from(vec![
+            inline_block("i1", &text, vec![], "p1"),
+            paragraph("p1", vec!["i1"], "root"),
+            root("root", vec!["p1"])
+        ]).unwrap())?.len(), 0);
+        return Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{RootBlock, BlockMap},
+        steps_generator::{slash_scrim::{query_slash_commands, get_slash_scrim_candidates, SLASH_COMMANDS}, selection::SubSelection, StepError}};
+    use serde_json::json;
+
+    fn names(query: &str) -> Vec<&'static str> {
+        return query_slash_commands(query).iter().map(|command| command.name).collect()
+    }
+
+    #[test]
+    fn empty_query_returns_every_command_in_registry_order() {
+        let expected: Vec<&str> = SLASH_COMMANDS.iter().map(|command| command.name).collect();
+        assert_eq!(names(""), expected);
+    }
+
+    #[test]
+    fn prefix_and_alias_matches_rank_first() {
+        assert_eq!(names("head")[0..3], ["heading 1", "heading 2", "heading 3"]);
+        assert_eq!(names("h2")[0], "heading 2");
+        assert_eq!(names("bullet")[0], "dotpoint list");
+        assert_eq!(names("TODO")[0], "to-do list");
+    }
+
+    #[test]
+    fn fuzzy_query_matches_chars_in_order() {
+        assert_eq!(names("nmbrd")[0], "numbered list");
+        assert!(names("zzz").is_empty());
+    }
+
+    #[test]
+    fn can_get_candidates_from_text_after_slash() -> Result<(), StepError> {
+        let inline_block = json!({
+            "_id": "inline",
+            "kind": "inline",
+            "_type": "text",
+            "content": {
+                "text": "Hello /togg"
+            },
+            "marks": [],
+            "parent": "paragraph"
+        }).to_string();
+        let block = json!({
+            "_id": "paragraph",
+            "kind": "standard",
+            "_type": "paragraph",
+            "content": {
+                "inline_blocks": ["inline"]
+            },
+            "children": [],
+            "marks": [],
+            "parent": "root"
+        }).to_string();
+        let root_block = RootBlock::json_from("root".to_string(), vec!["paragraph".to_string()]).to_string();
+        let block_map = BlockMap::from(vec![inline_block, block, root_block]).unwrap();
+
+        let candidates = get_slash_scrim_candidates(&SubSelection::from("inline".to_string(), 11, None), &block_map)?;
+        assert_eq!(candidates[0].name, "toggle list");
+        return Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{BlockMap, RootBlock},
+        steps_generator::{event::{Event, KeyPress, Key, KeyPressMetadata}, selection::{SubSelection, Selection}, generate_steps, StepError,
+            smart_typography::{Conversion, get_smart_typography_conversion}},
+        steps_actualisor::actualise_steps, new_ids::NewIds, custom_copy::CustomCopy};
+
+    use serde_json::json;
+
+    fn paragraph_block_map(text: &str, marks: Vec<&str>) -> BlockMap {
+        let inline_block = json!({
+            "_id": "inline1",
+            "kind": "inline",
+            "_type": "text",
+            "content": {
+                "text": text
+            },
+            "marks": marks,
+            "parent": "paragraph"
+        }).to_string();
+        let paragraph_block = json!({
+            "_id": "paragraph",
+            "kind": "standard",
+            "_type": "paragraph",
+            "content": {
+                "inline_blocks": ["inline1"]
+            },
+            "children": [],
+            "marks": [],
+            "parent": "root"
+        }).to_string();
+        let root_block = RootBlock::json_from("root".to_string(), vec!["paragraph".to_string()]).to_string();
+        return BlockMap::from(vec![inline_block, paragraph_block, root_block]).unwrap()
+    }
+
+    fn press_key(block_map: BlockMap, key: Key, offset: usize, smart_typography: bool) -> Result<BlockMap, StepError> {
+        let (block_map, _) = press_key_after(block_map, key, offset, smart_typography, None)?;
+        return Ok(block_map)
+    }
+
+    /// Like the frontend: the conversion returned with the previous key press is sent in the next one's metadata
+    fn press_key_after(
+        block_map: BlockMap,
+        key: Key,
+        offset: usize,
+        smart_typography: bool,
+        last_conversion: Option<Conversion>
+    ) -> Result<(BlockMap, Option<Conversion>), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let metadata = KeyPressMetadata { shift_down: false, meta_down: false, ctrl_down: false, alt_down: false, smart_typography, suggesting_as: None, last_conversion };
+        let key_press = KeyPress::new(key, Some(metadata));
+        let sub_selection = SubSelection::from("inline1".to_string(), offset, None);
+        let conversion = get_smart_typography_conversion(&key_press, &block_map, &sub_selection, &sub_selection)?;
+        let selection = Selection::from(sub_selection.clone(), sub_selection);
+        let steps = generate_steps(&Event::KeyPress(key_press), &block_map, selection, &CustomCopy::new(), &mut new_ids)?;
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?;
+        return Ok((updated_state.block_map, conversion))
+    }
+
+    fn text(block_map: &BlockMap) -> Result<String, StepError> {
+        return Ok(block_map.get_inline_block(&"inline1".to_string())?.text()?.clone().to_string())
+    }
+
+    #[test]
+    fn converts_dashes_ellipsis_and_arrows() -> Result<(), StepError> {
+        let block_map = press_key(paragraph_block_map("a-", vec![]), Key::Standard('-'), 2, true)?;
+        assert_eq!(text(&block_map)?, "a—".to_string());
+
+        let block_map = press_key(paragraph_block_map("wait..", vec![]), Key::Standard('.'), 6, true)?;
+        assert_eq!(text(&block_map)?, "wait…".to_string());
+
+        let block_map = press_key(paragraph_block_map("a -", vec![]), Key::Standard('>'), 3, true)?;
+        assert_eq!(text(&block_map)?, "a →".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn converts_quotes_to_opening_and_closing_curly_quotes() -> Result<(), StepError> {
+        let block_map = press_key(paragraph_block_map("say ", vec![]), Key::Standard('"'), 4, true)?;
+        assert_eq!(text(&block_map)?, "say “".to_string());
+
+        let block_map = press_key(paragraph_block_map("“hi", vec![]), Key::Standard('"'), 3, true)?;
+        assert_eq!(text(&block_map)?, "“hi”".to_string());
+
+        let block_map = press_key(paragraph_block_map("don", vec![]), Key::Standard('\''), 3, true)?;
+        assert_eq!(text(&block_map)?, "don’".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn conversion_keeps_marks_at_caret() -> Result<(), StepError> {
+        let block_map = press_key(paragraph_block_map("bold-", vec!["bold"]), Key::Standard('-'), 5, true)?;
+        let inline_block = block_map.get_inline_block(&"inline1".to_string())?;
+        assert_eq!(inline_block.text()?.clone().to_string(), "bold—".to_string());
+        assert_eq!(inline_block.marks.len(), 1);
+        return Ok(())
+    }
+
+    #[test]
+    fn backspace_after_conversion_restores_typed_text() -> Result<(), StepError> {
+        let (block_map, conversion) = press_key_after(paragraph_block_map("a-", vec![]), Key::Standard('-'), 2, true, None)?;
+        assert_eq!(conversion, Some(Conversion { block_id: "inline1".to_string(), offset: 2, source: "--".to_string() }));
+        let (block_map, _) = press_key_after(block_map, Key::Backspace, 2, true, conversion)?;
+        assert_eq!(text(&block_map)?, "a--".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn backspace_deletes_typographic_characters_that_were_not_just_converted() -> Result<(), StepError> {
+        let block_map = press_key(paragraph_block_map("don’", vec![]), Key::Backspace, 4, true)?;
+        assert_eq!(text(&block_map)?, "don".to_string());
+
+        // only the key press straight after the conversion reverts it
+        let (block_map, conversion) = press_key_after(paragraph_block_map("a-", vec![]), Key::Standard('-'), 2, true, None)?;
+        let (block_map, conversion) = press_key_after(block_map, Key::Standard('b'), 2, true, conversion)?;
+        let (block_map, conversion) = press_key_after(block_map, Key::Backspace, 3, true, conversion)?;
+        let (block_map, _) = press_key_after(block_map, Key::Backspace, 2, true, conversion)?;
+        assert_eq!(text(&block_map)?, "a".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn does_not_convert_when_disabled() -> Result<(), StepError> {
+        let block_map = press_key(paragraph_block_map("a-", vec![]), Key::Standard('-'), 2, false)?;
+        assert_eq!(text(&block_map)?, "a--".to_string());
+
+        let block_map = press_key(paragraph_block_map("a—", vec![]), Key::Backspace, 2, false)?;
+        assert_eq!(text(&block_map)?, "a".to_string());
+        return Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::BlockMap,
+        steps_generator::{event::Event, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, step::Step,
+        utilities::snapshots::{capture_snapshot, list_snapshots}, new_ids::NewIds, custom_copy::CustomCopy};
+
+    use serde_json::json;
+
+    use crate::common::{self, block_with_text, root};
+
+    /// root -> [a: "Hello"] & other_root -> [other: "Other page"]
+    fn block_map(text: &str, root_children: Vec<&str>) -> BlockMap {
+        return common::block_map(vec![
+            block_with_text("a", "paragraph", text, vec![], "root"),
+            block_with_text("b", "paragraph", "Added later", vec![], "root"),
+            block_with_text("other", "paragraph", "Other page", vec![], "other_root"),
+            vec![root("root", root_children), root("other_root", vec!["other"])]
+        ])
+    }
+
+    fn text(block_map: &BlockMap, id: &str) -> Result<String, StepError> {
+        return Ok(block_map.get_inline_block(&format!("{}_inline", id))?.text()?.clone().to_string())
+    }
+
+    #[test]
+    fn snapshot_only_copies_the_page() -> Result<(), StepError> {
+        let snapshot = capture_snapshot("Draft".to_string(), 10.0, &"root".to_string(), &block_map("Hello", vec!["a"]),
+            &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert_eq!(text(&snapshot.block_map, "a")?, "Hello".to_string());
+        assert!(snapshot.block_map.contains("root"));
+        assert!(!snapshot.block_map.contains("b"));
+        assert!(!snapshot.block_map.contains("other"));
+        return Ok(())
+    }
+
+    #[test]
+    fn snapshots_are_listed_newest_first() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let snapshots = vec![
+            capture_snapshot("First".to_string(), 10.0, &"root".to_string(), &block_map("Hello", vec!["a"]), &mut new_ids)?,
+            capture_snapshot("Other".to_string(), 15.0, &"other_root".to_string(), &block_map("Hello", vec!["a"]), &mut new_ids)?,
+            capture_snapshot("Second".to_string(), 20.0, &"root".to_string(), &block_map("Hello", vec!["a"]), &mut new_ids)?,
+        ];
+        let names: Vec<String> = list_snapshots(&"root".to_string(), &snapshots).into_iter().map(|summary| summary.name).collect();
+        assert_eq!(names, vec!["Second".to_string(), "First".to_string()]);
+        return Ok(())
+    }
+
+    #[test]
+    fn restoring_a_snapshot_keeps_block_ids() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let snapshot = capture_snapshot("Draft".to_string(), 10.0, &"root".to_string(), &block_map("Hello", vec!["a"]), &mut new_ids)?;
+        let current_block_map = block_map("Hello world", vec!["a", "b"]);
+
+        let caret = SubSelection::from("a_inline".to_string(), 0, None);
+        let event = Event::RestoreSnapshot(snapshot);
+        let steps = generate_steps(&event, &current_block_map, Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)?;
+        assert!(steps.iter().any(|step| matches!(step, Step::DeleteBlock(id) if id == "b")));
+        assert!(steps.iter().all(|step| !matches!(step, Step::AddBlock(_))));
+
+        let updated_state = actualise_steps(steps, current_block_map, &mut new_ids, CustomCopy::new())?;
+        assert_eq!(updated_state.block_map.get_root_block(&"root".to_string())?.children, vec!["a".to_string()]);
+        assert_eq!(text(&updated_state.block_map, "a")?, "Hello".to_string());
+        assert_eq!(text(&updated_state.block_map, "other")?, "Other page".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn restoring_a_snapshot_through_json_steps_removes_marks_added_since() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let snapshot = capture_snapshot("Draft".to_string(), 10.0, &"root".to_string(), &block_map("Hello world", vec!["a"]), &mut new_ids)?;
+        // "world" was made bold after the snapshot
+        let current_block_map = common::block_map(vec![vec![
+            json!({ "_id": "a_inline", "kind": "inline", "_type": "text", "content": { "text": "Hello " }, "marks": [], "parent": "a" }).to_string(),
+            json!({ "_id": "a_bold", "kind": "inline", "_type": "text", "content": { "text": "world" }, "marks": ["bold"], "parent": "a" }).to_string(),
+            json!({ "_id": "a", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["a_inline", "a_bold"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            root("root", vec!["a"])
+        ]]);
+
+        let caret = SubSelection::from("a_inline".to_string(), 0, None);
+        let event = Event::RestoreSnapshot(snapshot);
+        let mut steps = vec![];
+        for step in generate_steps(&event, &current_block_map, Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)? {
+            let (_type, data) = step.to_json()?;
+            steps.push(Step::from_json(&_type, &data)?);
+        }
+        let block_map = actualise_steps(steps, current_block_map, &mut new_ids, CustomCopy::new())?.block_map;
+        for inline_block in block_map.get_standard_block("a")?.get_inline_blocks(&block_map)? {
+            assert!(inline_block.marks.is_empty());
+        }
+        return Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{BlockMap, standard_blocks::StandardBlockType},
+        steps_generator::{event::{Event, ContextMenuEvent, SlashScrimEvent}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, step::Step, new_ids::NewIds, custom_copy::CustomCopy};
+
+    use crate::common::{self, block_with_text, root};
+
+    /// root -> [1, 2, 3]
+    fn block_map(first_text: &str) -> BlockMap {
+        return common::block_map(vec![
+            block_with_text("1", "paragraph", first_text, vec![], "root"),
+            block_with_text("2", "paragraph", "two", vec![], "root"),
+            block_with_text("3", "paragraph", "three", vec![], "root"),
+            vec![root("root", vec!["1", "2", "3"])]
+        ])
+    }
+
+    fn get_new_page_id(steps: &Vec<Step>) -> String {
+        return match &steps[0] {
+            Step::AddPage(add_page_step) => add_page_step.root_block_id.clone(),
+            step => panic!("Expected add page step, got: {:?}", step)
+        }
+    }
+
+    #[test]
+    fn slash_scrim_page_creates_linked_sub_page_with_paragraph() -> Result<(), StepError> {
+        let block_map = block_map("Hello /sq");
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let event = Event::SlashScrim(SlashScrimEvent { block_type: "square page".to_string() });
+        let sub_selection = SubSelection::from("1_inline".to_string(), 9, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection);
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids)?;
+
+        let page_id = get_new_page_id(&steps);
+        let page_block_step = steps.iter().find_map(|step| match step {
+            Step::AddBlock(add_block_step) => match &add_block_step.block_type {
+                StandardBlockType::SquarePage(page_block) => Some(page_block.clone()),
+                _ => None
+            },
+            _ => None
+        }).unwrap();
+        assert_eq!(page_block_step.page_id, page_id);
+
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?;
+        let new_root = updated_state.block_map.get_root_block(&page_id)?;
+        assert_eq!(new_root.children.len(), 1);
+        let paragraph = updated_state.block_map.get_standard_block(&new_root.children[0])?;
+        assert!(matches!(paragraph.content, StandardBlockType::Paragraph(_)));
+        assert_eq!(paragraph.parent, page_id);
+        assert!(paragraph.text_is_empty(&updated_state.block_map)?);
+        return Ok(())
+    }
+
+    #[test]
+    fn link_page_does_not_create_a_sub_page() -> Result<(), StepError> {
+        let block_map = block_map("Hello /link");
+        let event = Event::SlashScrim(SlashScrimEvent { block_type: "link page".to_string() });
+        let sub_selection = SubSelection::from("1_inline".to_string(), 11, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection);
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert!(!steps.iter().any(|step| matches!(step, Step::AddPage(_))));
+        return Ok(())
+    }
+
+    #[test]
+    fn can_turn_selected_blocks_into_page() -> Result<(), StepError> {
+        let block_map = block_map("one");
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let event = Event::ContextMenu(ContextMenuEvent::TurnIntoPage);
+        let from = SubSelection::from("1_inline".to_string(), 1, None);
+        let to = SubSelection::from("2_inline".to_string(), 2, None);
+        let steps = generate_steps(&event, &block_map, Selection::from(from, to), &CustomCopy::new(), &mut new_ids)?;
+        let page_id = get_new_page_id(&steps);
+
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?;
+        let new_root = updated_state.block_map.get_root_block(&page_id)?;
+        assert_eq!(new_root.children, vec!["1".to_string(), "2".to_string()]);
+        assert_eq!(updated_state.block_map.get_standard_block("1")?.parent, page_id);
+
+        let root = updated_state.block_map.get_root_block(&"root".to_string())?;
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[1], "3".to_string());
+        match updated_state.block_map.get_standard_block(&root.children[0])?.content {
+            StandardBlockType::InlinePage(page_block) => assert_eq!(page_block.page_id, page_id),
+            _ => panic!("Expected inline page block")
+        };
+        return Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{RootBlock, BlockMap}, mark::{Mark, SuggestionMark}, utilities::Tree,
+        steps_generator::{event::{Event, KeyPress, Key, KeyPressMetadata, SuggestionEvent}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::{actualise_steps, UpdatedState}, step::Step,
+        utilities::suggestions::get_suggestions, new_ids::NewIds, custom_copy::CustomCopy};
+    use serde_json::json;
+
+    /// root -> [a: "Hello world"]
+    fn block_map() -> BlockMap {
+        return BlockMap::from(vec![
+            json!({ "_id": "a1", "kind": "inline", "_type": "text", "content": { "text": "Hello world" }, "marks": [], "parent": "a" }).to_string(),
+            json!({ "_id": "a", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["a1"] },
+                "children": [], "marks": [], "parent": "root" }).to_string(),
+            RootBlock::json_from("root".to_string(), vec!["a".to_string()]).to_string()
+        ]).unwrap()
+    }
+
+    fn suggesting_key_press(key: Key) -> Event {
+        return suggesting_shortcut(key, false)
+    }
+
+    fn suggesting_shortcut(key: Key, ctrl_down: bool) -> Event {
+        let metadata = KeyPressMetadata { shift_down: false, meta_down: false, ctrl_down, alt_down: false, smart_typography: false,
+            suggesting_as: Some("ann".to_string()), last_conversion: None };
+        return Event::KeyPress(KeyPress::new(key, Some(metadata)))
+    }
+
+    fn press(key: Key, from: usize, to: usize, block_map: BlockMap, new_ids: &mut NewIds) -> Result<UpdatedState, StepError> {
+        let selection = Selection::from(SubSelection::from("a1".to_string(), from, None), SubSelection::from("a1".to_string(), to, None));
+        let steps = generate_steps(&suggesting_key_press(key), &block_map, selection, &CustomCopy::new(), new_ids)?;
+        return actualise_steps(steps, block_map, new_ids, CustomCopy::new())
+    }
+
+    fn texts_and_marks(block_map: &BlockMap) -> Result<Vec<(String, Vec<Mark>)>, StepError> {
+        let mut texts_and_marks = vec![];
+        for inline_block in block_map.get_standard_block("a")?.get_inline_blocks(block_map)? {
+            texts_and_marks.push((inline_block.text()?.clone().to_string(), inline_block.marks));
+        }
+        return Ok(texts_and_marks)
+    }
+
+    fn resolve(accept: bool, block_map: BlockMap, new_ids: &mut NewIds) -> Result<UpdatedState, StepError> {
+        let suggestion_id = get_suggestions(&"root".to_string(), &block_map)?[0].suggestion_id.clone();
+        let event = Event::Suggestion(SuggestionEvent { accept, suggestion_id, root_block_id: "root".to_string() });
+        let selection = Selection::from(SubSelection::from("a1".to_string(), 0, None), SubSelection::from("a1".to_string(), 0, None));
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), new_ids)?;
+        return actualise_steps(steps, block_map, new_ids, CustomCopy::new())
+    }
+
+    #[test]
+    fn suggestion_mark_round_trips_through_string() -> Result<(), StepError> {
+        let mark = Mark::from_str("deletion(s1, ann)")?;
+        assert_eq!(mark, Mark::Deletion(SuggestionMark { id: "s1".to_string(), author: "ann".to_string() }));
+        assert_eq!(mark.to_string(), "deletion(s1, ann)".to_string());
+        assert!(Mark::from_str("insertion(s1)").is_err());
+        return Ok(())
+    }
+
+    #[test]
+    fn typing_inserts_suggested_text_and_keeps_typing_in_it() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let updated_state = press(Key::Standard('!'), 5, 5, block_map(), &mut new_ids)?;
+        let caret = updated_state.selection.clone().unwrap().anchor;
+        let texts = texts_and_marks(&updated_state.block_map)?;
+        assert_eq!(texts.iter().map(|(text, _)| text.clone()).collect::<Vec<String>>(),
+            vec!["Hello".to_string(), "!".to_string(), " world".to_string()]);
+        assert!(matches!(&texts[1].1[..], [Mark::Insertion(suggestion)] if suggestion.author == "ann".to_string()));
+
+        // typing again inside the pending insertion just extends it
+        let steps = generate_steps(&suggesting_key_press(Key::Standard('?')), &updated_state.block_map,
+            Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)?;
+        assert!(matches!(steps[0], Step::ReplaceStep(_)));
+        let updated_state = actualise_steps(steps, updated_state.block_map, &mut new_ids, CustomCopy::new())?;
+        let suggestions = get_suggestions(&"root".to_string(), &updated_state.block_map)?;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].inserted_text, "!?".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn typing_a_markdown_shortcut_while_suggesting_does_not_apply_it() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let updated_state = press(Key::Standard('#'), 0, 0, block_map(), &mut new_ids)?;
+        let caret = updated_state.selection.clone().unwrap().anchor;
+
+        // "# " is the heading input rule, but inside the pending insertion it's just text
+        let steps = generate_steps(&suggesting_key_press(Key::Standard(' ')), &updated_state.block_map,
+            Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)?;
+        assert!(matches!(&steps[..], [Step::ReplaceStep(_)]));
+        let updated_state = actualise_steps(steps, updated_state.block_map, &mut new_ids, CustomCopy::new())?;
+        assert_eq!(get_suggestions(&"root".to_string(), &updated_state.block_map)?[0].inserted_text, "# ".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn typing_over_a_selection_suggests_a_replacement() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let updated_state = press(Key::Standard('W'), 6, 11, block_map(), &mut new_ids)?;
+        let suggestions = get_suggestions(&"root".to_string(), &updated_state.block_map)?;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].deleted_text, "world".to_string());
+        assert_eq!(suggestions[0].inserted_text, "W".to_string());
+        let texts: Vec<String> = texts_and_marks(&updated_state.block_map)?.into_iter().map(|(text, _)| text).collect();
+        assert_eq!(texts, vec!["Hello ".to_string(), "world".to_string(), "W".to_string()]);
+        return Ok(())
+    }
+
+    #[test]
+    fn backspace_marks_text_as_deleted_and_extends_the_same_suggestion() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let updated_state = press(Key::Backspace, 11, 11, block_map(), &mut new_ids)?;
+        let caret = updated_state.selection.clone().unwrap().anchor;
+        let texts = texts_and_marks(&updated_state.block_map)?;
+        assert_eq!(texts[0].0, "Hello worl".to_string());
+        assert_eq!(texts[1].0, "d".to_string());
+        assert!(matches!(&texts[1].1[..], [Mark::Deletion(_)]));
+
+        let steps = generate_steps(&suggesting_key_press(Key::Backspace), &updated_state.block_map,
+            Selection::from(caret.clone(), caret), &CustomCopy::new(), &mut new_ids)?;
+        let updated_state = actualise_steps(steps, updated_state.block_map, &mut new_ids, CustomCopy::new())?;
+        let deleted: Vec<String> = get_suggestions(&"root".to_string(), &updated_state.block_map)?.into_iter()
+            .map(|suggestion| suggestion.deleted_text).collect();
+        assert_eq!(deleted, vec!["ld".to_string()]);
+        return Ok(())
+    }
+
+    #[test]
+    fn accepting_a_suggestion_applies_it() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = press(Key::Standard('W'), 6, 11, block_map(), &mut new_ids)?.block_map;
+        let updated_state = resolve(true, block_map, &mut new_ids)?;
+        assert_eq!(texts_and_marks(&updated_state.block_map)?, vec![("Hello W".to_string(), vec![])]);
+        assert!(get_suggestions(&"root".to_string(), &updated_state.block_map)?.is_empty());
+        return Ok(())
+    }
+
+    #[test]
+    fn rejecting_a_suggestion_reverts_it() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = press(Key::Standard('W'), 6, 11, block_map(), &mut new_ids)?.block_map;
+        let updated_state = resolve(false, block_map, &mut new_ids)?;
+        assert_eq!(texts_and_marks(&updated_state.block_map)?, vec![("Hello world".to_string(), vec![])]);
+        return Ok(())
+    }
+
+    #[test]
+    fn cut_suggests_deleting_the_selected_text() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = block_map();
+        let selection = Selection::from(SubSelection::from("a1".to_string(), 6, None), SubSelection::from("a1".to_string(), 11, None));
+        let steps = generate_steps(&suggesting_shortcut(Key::Standard('x'), true), &block_map, selection, &CustomCopy::new(), &mut new_ids)?;
+        assert!(matches!(steps[0], Step::Copy(_, _)));
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?;
+        let suggestions = get_suggestions(&"root".to_string(), &updated_state.block_map)?;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].deleted_text, "world".to_string());
+        assert_eq!(texts_and_marks(&updated_state.block_map)?.into_iter().map(|(text, _)| text).collect::<String>(), "Hello world".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn paste_suggests_inserting_the_copied_text() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let copied_block_map = BlockMap::from(vec![
+            json!({ "_id": "c1", "kind": "inline", "_type": "text", "content": { "text": "big " }, "marks": [], "parent": "c" }).to_string(),
+            json!({ "_id": "c", "kind": "standard", "_type": "paragraph", "content": { "inline_blocks": ["c1"] },
+                "children": [], "marks": [], "parent": "root" }).to_string()
+        ]).unwrap();
+        let copy = CustomCopy::from(Tree { top_blocks: vec![copied_block_map.get_standard_block("c")?], block_map: copied_block_map });
+        let block_map = block_map();
+        let caret = SubSelection::from("a1".to_string(), 6, None);
+        let steps = generate_steps(&suggesting_shortcut(Key::Standard('v'), true), &block_map, Selection::from(caret.clone(), caret), &copy, &mut new_ids)?;
+        assert!(matches!(&steps[..], [Step::Suggest(_)]));
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, copy)?;
+        let suggestions = get_suggestions(&"root".to_string(), &updated_state.block_map)?;
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].inserted_text, "big ".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn enter_does_not_split_the_block_while_suggesting() -> Result<(), StepError> {
+        let caret = SubSelection::from("a1".to_string(), 5, None);
+        let steps = generate_steps(&suggesting_key_press(Key::Enter), &block_map(), Selection::from(caret.clone(), caret), &CustomCopy::new(),
+            &mut NewIds::hardcoded_new_ids_for_tests())?;
+        assert!(steps.is_empty());
+        return Ok(())
+    }
+}
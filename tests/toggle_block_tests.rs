@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use rust_mirror::{blocks::{BlockMap, standard_blocks::StandardBlockType, RootBlock},
+        steps_generator::{event::{Event, KeyPress, Key}, selection::{SubSelection, Selection}, generate_steps, StepError},
+        steps_actualisor::actualise_steps, step::Step, new_ids::NewIds, custom_copy::CustomCopy, utilities::get_next_block_in_tree};
+
+    use serde_json::json;
+
+    fn toggle_block_map(collapsed: bool) -> BlockMap {
+        let inline_block1 = json!({
+            "_id": "inline1",
+            "kind": "inline",
+            "_type": "text",
+            "content": {
+                "text": "Toggle"
+            },
+            "marks": [],
+            "parent": "toggle"
+        }).to_string();
+        let toggle_block = json!({
+            "_id": "toggle",
+            "kind": "standard",
+            "_type": "toggle list",
+            "content": {
+                "inline_blocks": ["inline1"],
+                "collapsed": collapsed
+            },
+            "children": ["child"],
+            "marks": [],
+            "parent": "root"
+        }).to_string();
+        let inline_block2 = json!({
+            "_id": "inline2",
+            "kind": "inline",
+            "_type": "text",
+            "content": {
+                "text": "Hidden"
+            },
+            "marks": [],
+            "parent": "child"
+        }).to_string();
+        let child_block = json!({
+            "_id": "child",
+            "kind": "standard",
+            "_type": "paragraph",
+            "content": {
+                "inline_blocks": ["inline2"]
+            },
+            "children": [],
+            "marks": [],
+            "parent": "toggle"
+        }).to_string();
+        let inline_block3 = json!({
+            "_id": "inline3",
+            "kind": "inline",
+            "_type": "text",
+            "content": {
+                "text": "After"
+            },
+            "marks": [],
+            "parent": "after"
+        }).to_string();
+        let after_block = json!({
+            "_id": "after",
+            "kind": "standard",
+            "_type": "paragraph",
+            "content": {
+                "inline_blocks": ["inline3"]
+            },
+            "children": [],
+            "marks": [],
+            "parent": "root"
+        }).to_string();
+        let root_block = RootBlock::json_from("root".to_string(), vec!["toggle".to_string(), "after".to_string()]).to_string();
+        return BlockMap::from(vec![inline_block1, toggle_block, inline_block2, child_block, inline_block3, after_block, root_block]).unwrap()
+    }
+
+    #[test]
+    fn can_toggle_collapsed() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = toggle_block_map(false);
+        let event = Event::ToggleCollapsed("toggle".to_string());
+        let sub_selection = SubSelection::from("inline1".to_string(), 0, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection);
+
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids)?;
+        let updated_state = actualise_steps(steps, block_map, &mut new_ids, CustomCopy::new())?;
+
+        let toggle_block = updated_state.block_map.get_standard_block(&"toggle".to_string())?;
+        assert!(toggle_block.is_collapsed());
+        assert_eq!(toggle_block.children, vec!["child".to_string()]);
+        return Ok(())
+    }
+
+    #[test]
+    fn next_block_in_tree_skips_collapsed_children() -> Result<(), StepError> {
+        let block_map = toggle_block_map(true);
+        let toggle_block = block_map.get_standard_block("toggle")?;
+        let next_block = get_next_block_in_tree(&toggle_block, &block_map, &mut 0)?;
+        assert_eq!(next_block.id(), "after".to_string());
+
+        let block_map = toggle_block_map(false);
+        let toggle_block = block_map.get_standard_block("toggle")?;
+        let next_block = get_next_block_in_tree(&toggle_block, &block_map, &mut 0)?;
+        assert_eq!(next_block.id(), "child".to_string());
+        return Ok(())
+    }
+
+    #[test]
+    fn enter_at_end_of_collapsed_toggle_adds_sibling_below() -> Result<(), StepError> {
+        let mut new_ids = NewIds::hardcoded_new_ids_for_tests();
+        let block_map = toggle_block_map(true);
+        let event = Event::KeyPress(KeyPress::new(Key::Enter, None));
+        let sub_selection = SubSelection::from("inline1".to_string(), 6, None);
+        let selection = Selection::from(sub_selection.clone(), sub_selection);
+
+        let steps = generate_steps(&event, &block_map, selection, &CustomCopy::new(), &mut new_ids)?;
+        assert_eq!(steps.len(), 1);
+        match &steps[0] {
+            Step::AddBlock(add_block_step) => {
+                assert_eq!(add_block_step.block_id, "root".to_string());
+                assert_eq!(add_block_step.child_offset, 1);
+                match add_block_step.block_type {
+                    StandardBlockType::ToggleList(_) => {},
+                    _ => panic!("Expected toggle list")
+                };
+            },
+            step => return Err(StepError(format!("Expected AddBlock step. Got: {:?}", step)))
+        };
+        return Ok(())
+    }
+}